@@ -1,127 +1,204 @@
-struct SleightaBase {
+use parsing::ParseError;
+
+pub struct SleightaBase {
     list: Vec<ParsedString>
 }
 
 impl SleightaBase {
-    fn new(input: String) -> Self {
+    pub fn new(input: String) -> Result<Self, ParseError> {
         let mut list : Vec<ParsedString> = Vec::new();
 
-        for line in input.lines().map(str::trim) {
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
             if line.is_empty() { continue };
 
-            list.push(ParsedString::new(line.to_string()))
+            list.push(ParsedString::new(line.to_string()).map_err(|error| error.with_line(index + 1))?)
         }
 
-        Self { list }
+        Ok(Self { list })
     }
 
-    fn compression(&self) -> usize {
+    // Characters of code saved by decoding every literal.
+    pub fn compression(&self) -> usize {
         self.list.iter().map(ParsedString::compression).sum()
     }
+
+    // Characters of code added by encoding every literal again.
+    pub fn expansion(&self) -> usize {
+        self.list.iter().map(ParsedString::expansion).sum()
+    }
 }
 
-struct ParsedString {
+pub struct ParsedString {
     original: String,
-    parsed: Vec<char>,
-    encoded: Vec<char>,
+    parsed: Vec<u8>,
+    encoded: String,
 }
 
 impl ParsedString {
-    fn new(original: String) -> Self {
-        let last_index = original.len() - 1;
-        let mut parsed : Vec<char> = Vec::new();
-        let mut encoded : Vec<char> = Vec::new();
-        todo!();
-        let mut characters = original.chars().enumerate();
-        loop {
-            match characters.next() {
-                Some((index, character)) => {
-                    if index == 0 || index == last_index {
-                        continue;
-                    }
-                    match character {
-                        '\\' => {
-                            let (_, second_character) = characters.next().unwrap();
-                            match second_character {
-                                '\\' | '"' => {
-                                    parsed.push(second_character)
-                                },
-                                'x' => {
-                                    let first_digit = characters.next().unwrap().1.to_digit(16).unwrap();
-                                    let second_digit = characters.next().unwrap().1.to_digit(16).unwrap();
-                                    parsed.push(char::from_u32(first_digit * 16 + second_digit).unwrap());
-                                }
-                                _ => panic!("Unknown escaped character: {second_character}")
-                            }
-                        },
-                        single_character => { parsed.push(single_character) }
-                    }
-                },
-                None => { break; }
-            }
-        }
+    pub fn new(original: String) -> Result<Self, ParseError> {
+        let parsed = decode(&original)?;
+        let encoded = encode(original.as_bytes());
 
-        Self { original, parsed, encoded }
+        Ok(Self { original, parsed, encoded })
     }
 
-    fn parsed_string(&self) -> String {
-        String::from_iter(self.parsed.iter())
-    }
-
-    fn original_chars(&self) -> Vec<u32> {
+    pub fn original_chars(&self) -> Vec<u32> {
         self.original.chars().map(|char| char as u32).collect()
     }
 
-    fn parsed_chars(&self) -> Vec<u32> {
-        self.parsed.iter().map(|&char| char as u32).collect()
+    pub fn parsed_chars(&self) -> Vec<u32> {
+        self.parsed.iter().map(|&byte| byte as u32).collect()
     }
 
-    fn compression(&self) -> usize {
-        // println!("{} : {}", self.original, self.original.len());
-        // println!("{} : {}", self.parsed_string(), self.parsed.len());
-        // println!("Difference : {}", self.original.len() - self.parsed.len());
+    pub fn compression(&self) -> usize {
         self.original.len() - self.parsed.len()
     }
+
+    pub fn expansion(&self) -> usize {
+        self.encoded.len() - self.original.len()
+    }
+}
+
+// Turns a quoted literal into the bytes it stands for. A \x escape is a single
+// byte, which need not be valid UTF-8 on its own.
+pub fn decode(literal: &str) -> Result<Vec<u8>, ParseError> {
+    let contents = literal.strip_prefix('"').ok_or_else(|| ParseError::at_column(1, "expected '\"'"))?;
+    let contents = contents.strip_suffix('"')
+        .filter(|_| literal.len() > 1)
+        .ok_or_else(|| ParseError::at_column(literal.chars().count() + 1, "expected closing '\"'"))?;
+
+    let mut bytes = Vec::with_capacity(contents.len());
+    let mut characters = contents.chars().enumerate().peekable();
+    while let Some((index, character)) = characters.next() {
+        // Columns count characters, and the opening quote too.
+        let column = index + 2;
+        match character {
+            '\\' => {
+                match characters.next() {
+                    Some((_, '\\')) => { bytes.push(b'\\') },
+                    Some((_, '"')) => { bytes.push(b'"') },
+                    Some((_, 'x')) => {
+                        let digits : String = (0..2).filter_map(|_| characters.next_if(|(_, digit)| digit.is_ascii_hexdigit())).map(|(_, digit)| digit).collect();
+                        if digits.len() < 2 {
+                            return Err(ParseError::at_column(column, "expected two hex digits after \\x"));
+                        }
+                        bytes.push(u8::from_str_radix(&digits, 16).unwrap());
+                    },
+                    Some((_, other)) => { return Err(ParseError::at_column(column, format!("unknown escape '\\{other}'"))) },
+                    None => { return Err(ParseError::at_column(column, "unfinished escape")) }
+                }
+            },
+            '"' => { return Err(ParseError::at_column(column, "unescaped '\"'")) },
+            character => {
+                let mut buffer = [0; 4];
+                bytes.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+// The shortest literal that decodes back to the bytes. Anything that isn't
+// printable ASCII is written as a \x escape.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut literal = String::with_capacity(bytes.len() + 2);
+    literal.push('"');
+    for &byte in bytes {
+        match byte {
+            b'"' => literal.push_str("\\\""),
+            b'\\' => literal.push_str("\\\\"),
+            b' '..=b'~' => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\x{byte:02x}"))
+        }
+    }
+    literal.push('"');
+
+    literal
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(SleightaBase::new(input.to_string())?.compression())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(SleightaBase::new(input.to_string())?.expansion())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
     use super::*;
 
     #[test]
     fn empty_string() {
-        let example_one = ParsedString::new(String::from(r#""""#));
+        let example_one = ParsedString::new(String::from(r#""""#)).unwrap();
         assert_eq!(example_one.original_chars().len(), 2);
         assert_eq!(example_one.parsed_chars().len(), 0);
     }
 
     #[test]
     fn simple_string() {
-        let example_one = ParsedString::new(String::from(r#""abc""#));
+        let example_one = ParsedString::new(String::from(r#""abc""#)).unwrap();
         assert_eq!(example_one.original_chars().len(), 5);
         assert_eq!(example_one.parsed_chars().len(), 3);
     }
 
     #[test]
     fn escaped_quote_string() {
-        let example_one = ParsedString::new(String::from(r#""aaa\"aaa""#));
+        let example_one = ParsedString::new(String::from(r#""aaa\"aaa""#)).unwrap();
         assert_eq!(example_one.original_chars().len(), 10);
         assert_eq!(example_one.parsed_chars().len(), 7);
     }
 
     #[test]
     fn hex_encoded_string() {
-        let example_one = ParsedString::new(String::from(r#""\x27""#));
+        let example_one = ParsedString::new(String::from(r#""\x27""#)).unwrap();
         assert_eq!(example_one.original_chars().len(), 6);
         assert_eq!(example_one.parsed_chars().len(), 1);
     }
 
     #[test]
     fn hex_encoded_string_two() {
-        let example_one = ParsedString::new(String::from(r#""\xa8""#));
+        let example_one = ParsedString::new(String::from(r#""\xa8""#)).unwrap();
         assert_eq!(example_one.original_chars().len(), 6);
         assert_eq!(example_one.parsed_chars().len(), 1);
+        assert_eq!(example_one.parsed, vec![0xa8]);
+    }
+
+    #[test]
+    fn escaped_backslash_before_x() {
+        assert_eq!(decode(r#""\\x27""#).unwrap(), b"\\x27");
+    }
+
+    #[test]
+    fn encoded_strings() {
+        assert_eq!(encode(br#""""#), r#""\"\"""#);
+        assert_eq!(encode(br#""abc""#), r#""\"abc\"""#);
+        assert_eq!(encode(br#""aaa\"aaa""#), r#""\"aaa\\\"aaa\"""#);
+        assert_eq!(encode(br#""\x27""#), r#""\"\\x27\"""#);
+        assert_eq!(encode(&[0, b'a', 0xff, b'\n']), r#""\x00a\xff\x0a""#);
+    }
+
+    #[test]
+    fn malformed_literals() {
+        let error = |literal: &str| decode(literal).unwrap_err().to_string();
+
+        assert_eq!(error("abc\""), "column 1: expected '\"'");
+        assert_eq!(error("\""), "column 2: expected closing '\"'");
+        assert_eq!(error("\"abc"), "column 5: expected closing '\"'");
+        assert_eq!(error(r#""ab\q""#), "column 4: unknown escape '\\q'");
+        assert_eq!(error(r#""ab\x4""#), "column 4: expected two hex digits after \\x");
+        assert_eq!(error(r#""\xg0""#), "column 2: expected two hex digits after \\x");
+        assert_eq!(error(r#""ab\""#), "column 4: unfinished escape");
+        assert_eq!(error(r#""a"b""#), "column 3: unescaped '\"'");
+        assert_eq!(error(r#""né\q""#), "column 4: unknown escape '\\q'");
+        assert_eq!(error("\"né"), "column 4: expected closing '\"'");
+
+        let error = SleightaBase::new(String::from("\"abc\"\n\n\"\\z\"")).err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 2: unknown escape '\\z'");
     }
 
     #[test]
@@ -133,14 +210,39 @@ mod tests {
         "\x27"
         "#);
 
-        assert_eq!(SleightaBase::new(example_input).compression(), 12);
+        let base = SleightaBase::new(example_input).unwrap();
+        assert_eq!(base.compression(), 12);
+        assert_eq!(base.expansion(), 19);
+    }
+
+    // Any bytes at all survive being encoded and decoded again, and so does any
+    // literal the encoder is given.
+    #[test]
+    fn round_trips() {
+        let mut random = StdRng::seed_from_u64(2015);
+        let alphabet = b"ab\\\"x0f9 ~\x00\x7f\xff";
+
+        for _ in 0..10_000 {
+            let length = random.gen_range(0..24);
+            let bytes : Vec<u8> = (0..length)
+                .map(|_| if random.gen_bool(0.5) { alphabet[random.gen_range(0..alphabet.len())] } else { random.gen() })
+                .collect();
+
+            let literal = encode(&bytes);
+            assert_eq!(decode(&literal).unwrap(), bytes, "{literal}");
+            assert!(literal.bytes().all(|byte| (b' '..=b'~').contains(&byte)), "{literal}");
+
+            assert_eq!(decode(&encode(literal.as_bytes())).unwrap(), literal.as_bytes());
+        }
     }
 
     #[test]
     fn part_one() {
-        assert_eq!(
-            SleightaBase::new(fs::read_to_string("input.txt").unwrap()).compression(),
-            1371 // too low
-        )
+        assert_eq!(super::part_one(&fixtures::input(2015, 8).unwrap()).unwrap(), 1371);
+    }
+
+    #[test]
+    fn part_two() {
+        assert_eq!(super::part_two(&fixtures::input(2015, 8).unwrap()).unwrap(), 2117);
     }
 }
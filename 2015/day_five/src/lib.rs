@@ -1,38 +1,194 @@
-fn is_vowel(character: char) -> bool {
-    match character {
-        'a' | 'e' | 'i' | 'o' | 'u' => true,
-        _ => false
+use std::fmt;
+use std::str::FromStr;
+use parsing::ParseError;
+
+mod stream;
+
+pub use stream::{Classifier, CorpusStats, RULES};
+
+// What a single rule looks for in a word.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Check {
+    // At least this many of the letters, counting repeats.
+    AtLeast { count: usize, letters: String },
+    // A letter that appears again with this many letters in between.
+    Repeat { gap: usize },
+    // None of these strings anywhere in the word.
+    NoneOf(Vec<String>),
+    // A pair of letters that appears twice without overlapping.
+    PairTwice
+}
+
+impl Check {
+    fn passes(&self, characters: &[char]) -> bool {
+        match self {
+            Check::AtLeast { count, letters } => {
+                characters.iter().filter(|character| letters.contains(**character)).count() >= *count
+            },
+            Check::Repeat { gap } => { repeat_exists(characters, *gap) },
+            Check::NoneOf(banned) => {
+                let word = String::from_iter(characters);
+                !banned.iter().any(|banned| word.contains(banned.as_str()))
+            },
+            Check::PairTwice => { pair_of_pairs_exists(characters) }
+        }
     }
 }
 
-fn part_one_is_nice(input: &str) -> bool {
-    let mut vowel_count = 0;
-    let mut has_double_letter = false;
-    let mut no_banned_pairings = true;
-    let mut previous_character : char = '!'; // initial value not used
+// Written the way the config gives them.
+impl fmt::Display for Check {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Check::AtLeast { count, letters } => write!(formatter, "at least {count} of {letters}"),
+            Check::Repeat { gap } => write!(formatter, "repeat with gap {gap}"),
+            Check::NoneOf(banned) => write!(formatter, "none of {}", banned.join(" ")),
+            Check::PairTwice => write!(formatter, "pair twice")
+        }
+    }
+}
 
-    for (index, character) in input.chars().enumerate() {
-        if is_vowel(character) {
-            vowel_count += 1;
+impl FromStr for Check {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let words : Vec<&str> = text.split_whitespace().collect();
+        match words[..] {
+            ["at", "least", count, "of", letters] => Ok(Check::AtLeast { count: parsing::number(text, count)?, letters: letters.to_string() }),
+            ["repeat", "with", "gap", gap] => Ok(Check::Repeat { gap: parsing::number(text, gap)? }),
+            ["none", "of", ref banned @ ..] if !banned.is_empty() => Ok(Check::NoneOf(banned.iter().map(|banned| banned.to_string()).collect())),
+            ["pair", "twice"] => Ok(Check::PairTwice),
+            _ => Err(ParseError::at_column(1, format!("unknown check '{text}'")))
         }
-        if index > 0 {
-            match (previous_character, character) {
-                (x, y) if x == y => {
-                    has_double_letter = true
-                },
-                ('a', 'b') | ('c', 'd') | ('p', 'q') | ('x', 'y') => {
-                    no_banned_pairings = false
-                },
-                _ => ()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub check: Check
+}
+
+// A word is nice when it passes every rule in the set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ruleset {
+    rules: Vec<Rule>
+}
+
+// One rule per line, as "name: check". Blank lines and lines starting with #
+// are skipped.
+impl FromStr for Ruleset {
+    type Err = ParseError;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        let mut rules = Self::new();
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
+
+            let rule = parsing::split_once(line, ":").and_then(|(name, check)| {
+                let (name, check) = (name.trim(), check.trim());
+                rules.check_name(name).map_err(|error| ParseError::at_column(1, error.message))?;
+                Ok(Rule { name: name.to_string(), check: check.parse().map_err(|error: ParseError| error.within(line, check))? })
+            });
+            rules.rules.push(rule.map_err(|error| error.with_line(index + 1))?);
         }
-        previous_character = character;
+
+        Ok(rules)
     }
+}
 
-    vowel_count > 2 && has_double_letter && no_banned_pairings
+impl Ruleset {
+    pub fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    // Takes the same names a config would, so none that are blank or taken.
+    pub fn with_rule(mut self, name: &str, check: Check) -> Result<Self, ParseError> {
+        self.check_name(name)?;
+        self.rules.push(Rule { name: name.to_string(), check });
+
+        Ok(self)
+    }
+
+    fn check_name(&self, name: &str) -> Result<(), ParseError> {
+        match name {
+            "" => Err(ParseError::new("rule has no name")),
+            name if self.rules.iter().any(|rule| rule.name == name) => Err(ParseError::new(format!("rule '{name}' is defined twice"))),
+            _ => Ok(())
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn classify<'a>(&'a self, word: &'a str) -> Classification<'a> {
+        let characters : Vec<char> = word.chars().collect();
+        let results = self.rules.iter().map(|rule| (rule.name.as_str(), rule.check.passes(&characters))).collect();
+
+        Classification { word, results }
+    }
+
+    pub fn is_nice(&self, word: &str) -> bool {
+        let characters : Vec<char> = word.chars().collect();
+        self.rules.iter().all(|rule| rule.check.passes(&characters))
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-fn pair_of_pairs_exists(characters: &Vec<char>) -> bool {
+// How a word did against each rule, in the order the rules were given.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Classification<'a> {
+    pub word: &'a str,
+    pub results: Vec<(&'a str, bool)>
+}
+
+impl Classification<'_> {
+    pub fn is_nice(&self) -> bool {
+        self.results.iter().all(|&(_, passed)| passed)
+    }
+
+    pub fn failed(&self) -> Vec<&str> {
+        self.results.iter().filter(|(_, passed)| !passed).map(|&(name, _)| name).collect()
+    }
+}
+
+impl fmt::Display for Classification<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.failed()[..] {
+            [] => write!(formatter, "{} is nice", self.word),
+            ref failed => write!(formatter, "{} is naughty, it fails {}", self.word, failed.join(", "))
+        }
+    }
+}
+
+pub const PART_ONE_RULES : &str = "\
+vowels: at least 3 of aeiou
+double letter: repeat with gap 0
+no banned pairs: none of ab cd pq xy
+";
+
+pub const PART_TWO_RULES : &str = "\
+pair twice: pair twice
+split pair: repeat with gap 1
+";
+
+fn repeat_exists(characters: &[char], gap: usize) -> bool {
+    characters.windows(gap + 2).any(|window| window[0] == window[gap + 1])
+}
+
+fn pair_of_pairs_exists(characters: &[char]) -> bool {
+    if characters.len() < 4 {
+        return false;
+    }
+
     for start_pair_index in 0..=(characters.len() - 4) {
         let pair = (characters[start_pair_index], characters[start_pair_index + 1]);
 
@@ -49,29 +205,42 @@ fn pair_of_pairs_exists(characters: &Vec<char>) -> bool {
     false
 }
 
-fn split_pair_exists(characters: &Vec<char>) -> bool {
-    for start_pair_index in 0..=(characters.len() - 3) {
-        if characters[start_pair_index] == characters[start_pair_index + 2] {
-            return true;
+// Santa's list only has lowercase words on it, one per line.
+fn words(input: &str) -> Result<Vec<&str>, ParseError> {
+    input.lines().enumerate().filter(|(_row, line)| !line.is_empty()).map(|(row, line)| {
+        match line.char_indices().find(|(_column, character)| !character.is_ascii_lowercase()) {
+            Some((column, character)) => Err(ParseError::unexpected_character(character, column + 1).with_line(row + 1)),
+            None => Ok(line)
         }
-    }
+    }).collect()
+}
 
-    false
+pub fn count_nice(input: &str, rules: &Ruleset) -> Result<usize, ParseError> {
+    Ok(words(input)?.into_iter().filter(|word| rules.is_nice(word)).count())
 }
 
-fn part_two_is_nice(input: &str) -> bool {
-    let characters : Vec<char> = input.chars().collect();
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    count_nice(input, &PART_ONE_RULES.parse()?)
+}
 
-    pair_of_pairs_exists(&characters) && split_pair_exists(&characters)
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    count_nice(input, &PART_TWO_RULES.parse()?)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     fn input() -> String {
-        fs::read_to_string("input.txt").unwrap()
+        fixtures::input(2015, 5).unwrap()
+    }
+
+    fn part_one_is_nice(input: &str) -> bool {
+        PART_ONE_RULES.parse::<Ruleset>().unwrap().is_nice(input)
+    }
+
+    fn part_two_is_nice(input: &str) -> bool {
+        PART_TWO_RULES.parse::<Ruleset>().unwrap().is_nice(input)
     }
 
     #[test]
@@ -91,12 +260,71 @@ mod tests {
         )
     }
 
+    #[test]
+    fn invalid_words() {
+        assert_eq!(words("aaa\n\nab-c").err().unwrap().to_string(), "line 3, column 3: unexpected character '-'");
+    }
+
     #[test]
     fn part_two_examples() {
         assert!(part_two_is_nice("qjhvhtzxzqqjkmpb"));
         assert!(part_two_is_nice("xxyxx"));
         assert!(!part_two_is_nice("uurcxstgmygtbstg"));
         assert!(!part_two_is_nice("ieodomkazucvgmuy"));
+        assert!(!part_two_is_nice("aba"));
+    }
+
+    #[test]
+    fn explanations() {
+        let rules : Ruleset = PART_ONE_RULES.parse().unwrap();
+        let classification = rules.classify("haegwjzuvuyypxyu");
+        assert_eq!(classification.results, [("vowels", true), ("double letter", true), ("no banned pairs", false)]);
+        assert!(!classification.is_nice());
+        assert_eq!(classification.to_string(), "haegwjzuvuyypxyu is naughty, it fails no banned pairs");
+
+        assert_eq!(rules.classify("dvszwmarrgswjxmb").failed(), ["vowels"]);
+        assert_eq!(rules.classify("jchzalrnumimnmhp").failed(), ["double letter"]);
+        assert_eq!(rules.classify("ugknbfddgicrmopn").to_string(), "ugknbfddgicrmopn is nice");
+
+        let rules : Ruleset = PART_TWO_RULES.parse().unwrap();
+        assert_eq!(rules.classify("aba").to_string(), "aba is naughty, it fails pair twice");
+        assert_eq!(rules.classify("xyz").failed(), ["pair twice", "split pair"]);
+    }
+
+    #[test]
+    fn building_rulesets() {
+        let rules = Ruleset::new()
+            .with_rule("two zs", Check::AtLeast { count: 2, letters: String::from("z") }).unwrap()
+            .with_rule("wide repeat", Check::Repeat { gap: 3 }).unwrap();
+        assert!(rules.is_nice("zabcz"));
+        assert_eq!(rules.classify("zabz").failed(), ["wide repeat"]);
+        assert!(Ruleset::default().is_nice("anything"));
+
+        let error = rules.clone().with_rule("two zs", Check::PairTwice).err().unwrap();
+        assert_eq!(error.to_string(), "rule 'two zs' is defined twice");
+        assert_eq!(rules.clone().with_rule("", Check::PairTwice).err().unwrap().to_string(), "rule has no name");
+        let rules = rules.with_rule("three zs", Check::AtLeast { count: 3, letters: String::from("z") }).unwrap();
+        assert_eq!(rules.classify("zabcz").failed(), ["three zs"]);
+
+        let config = "# Both parts at once\n\nvowels: at least 3 of aeiou\n  clean : none of ab cd\npairs: pair twice\n";
+        let rules : Ruleset = config.parse().unwrap();
+        assert_eq!(rules.rules().iter().map(|rule| rule.name.as_str()).collect::<Vec<_>>(), ["vowels", "clean", "pairs"]);
+        assert_eq!(rules.rules()[1].check, Check::NoneOf(vec![String::from("ab"), String::from("cd")]));
+        for rule in rules.rules() {
+            assert_eq!(rule.check.to_string().parse::<Check>().unwrap(), rule.check);
+        }
+    }
+
+    #[test]
+    fn invalid_rulesets() {
+        let error = |config: &str| config.parse::<Ruleset>().err().unwrap().to_string();
+
+        assert_eq!(error("vowels: at least 3 of aeiou\nrepeats: repeat with gap two"), "line 2, column 26: invalid number 'two'");
+        assert_eq!(error("# comment\nvowels: at most 3 of aeiou"), "line 2, column 9: unknown check 'at most 3 of aeiou'");
+        assert_eq!(error("vowels at least 3 of aeiou"), "line 1: expected ':' in 'vowels at least 3 of aeiou'");
+        assert_eq!(error(": pair twice"), "line 1, column 1: rule has no name");
+        assert_eq!(error("pairs: pair twice\npairs: pair twice"), "line 2, column 1: rule 'pairs' is defined twice");
+        assert_eq!(error("clean: none of"), "line 1, column 8: unknown check 'none of'");
     }
 
     #[test]
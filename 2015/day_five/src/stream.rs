@@ -0,0 +1,242 @@
+use std::fmt;
+use std::io::BufRead;
+use parsing::{ParseError, ReadError};
+
+// The rules of both parts, in the order their statistics are kept. The names
+// match the ones in PART_ONE_RULES and PART_TWO_RULES.
+pub const RULES : [&str; 5] = ["vowels", "double letter", "no banned pairs", "pair twice", "split pair"];
+
+const VOWELS : usize = 0;
+const DOUBLE_LETTER : usize = 1;
+const NO_BANNED_PAIRS : usize = 2;
+const PAIR_TWICE : usize = 3;
+const SPLIT_PAIR : usize = 4;
+
+// Counts are u64 so lists of billions of words still fit.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CorpusStats {
+    pub words: u64,
+    pub nice_part_one: u64,
+    pub nice_part_two: u64,
+    pub passed: [u64; 5]
+}
+
+impl CorpusStats {
+    pub fn passed(&self, rule: &str) -> Option<u64> {
+        RULES.iter().position(|name| *name == rule).map(|index| self.passed[index])
+    }
+
+    pub fn failed(&self, rule: &str) -> Option<u64> {
+        self.passed(rule).map(|passed| self.words - passed)
+    }
+}
+
+impl fmt::Display for CorpusStats {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "{} word(s), {} nice in part one, {} nice in part two", self.words, self.nice_part_one, self.nice_part_two)?;
+        for (rule, passed) in RULES.iter().zip(self.passed) {
+            writeln!(formatter, "{rule}: {passed} passed, {} failed", self.words - passed)?;
+        }
+
+        Ok(())
+    }
+}
+
+// What is known about the word being read so far.
+#[derive(Clone, Copy, Default)]
+struct Word {
+    length: u64,
+    vowels: u8,
+    passed: [bool; 5],
+    previous: Option<u8>,
+    before_previous: Option<u8>
+}
+
+fn pair_index(first: u8, second: u8) -> usize {
+    (first - b'a') as usize * 26 + (second - b'a') as usize
+}
+
+// Reads words a byte at a time and checks every rule of both parts as it goes,
+// so memory stays the same however long the list or its words are. A pair
+// that shows up twice is found by remembering where each of the 676 pairs
+// first started in the current word, tagged with the word's number so the
+// table never needs clearing.
+pub struct Classifier {
+    word: Word,
+    pairs: [(u64, u64); 676],
+    started: u64,
+    stats: CorpusStats,
+    line: usize,
+    column: usize
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Classifier {
+    pub fn new() -> Self {
+        Self { word: Word::default(), pairs: [(0, 0); 676], started: 0, stats: CorpusStats::default(), line: 0, column: 0 }
+    }
+
+    pub fn read<R: BufRead>(mut self, reader: R) -> Result<CorpusStats, ReadError> {
+        parsing::read_chunks(reader, |bytes| self.feed(bytes))?;
+        Ok(self.finish())
+    }
+
+    // Words can be split anywhere between calls.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<(), ParseError> {
+        for &byte in bytes {
+            self.column += 1;
+            match byte {
+                b'a'..=b'z' => { self.letter(byte) },
+                b'\n' => { self.end_word(); self.line += 1; self.column = 0; },
+                b'\r' => {},
+                byte if byte.is_ascii() => {
+                    return Err(ParseError::unexpected_character(byte as char, self.column).with_line(self.line + 1))
+                },
+                byte => {
+                    return Err(ParseError::at_column(self.column, format!("unexpected byte 0x{byte:02x}")).with_line(self.line + 1))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Counts a last word that has no line break after it.
+    pub fn finish(mut self) -> CorpusStats {
+        self.end_word();
+        self.stats
+    }
+
+    fn letter(&mut self, byte: u8) {
+        if self.word.length == 0 {
+            self.started += 1;
+            self.word.passed[NO_BANNED_PAIRS] = true;
+        }
+        let word = &mut self.word;
+        let position = word.length;
+
+        if matches!(byte, b'a' | b'e' | b'i' | b'o' | b'u') {
+            word.vowels = (word.vowels + 1).min(3);
+            word.passed[VOWELS] = word.vowels == 3;
+        }
+        if let Some(previous) = word.previous {
+            if previous == byte {
+                word.passed[DOUBLE_LETTER] = true;
+            }
+            if matches!((previous, byte), (b'a', b'b') | (b'c', b'd') | (b'p', b'q') | (b'x', b'y')) {
+                word.passed[NO_BANNED_PAIRS] = false;
+            }
+
+            let pair = &mut self.pairs[pair_index(previous, byte)];
+            let start = position - 1;
+            match *pair {
+                (word_number, first) if word_number == self.started => {
+                    if first + 2 <= start {
+                        word.passed[PAIR_TWICE] = true;
+                    }
+                },
+                _ => { *pair = (self.started, start) }
+            }
+        }
+        if word.before_previous == Some(byte) {
+            word.passed[SPLIT_PAIR] = true;
+        }
+
+        word.before_previous = word.previous;
+        word.previous = Some(byte);
+        word.length += 1;
+    }
+
+    // Blank lines aren't words.
+    fn end_word(&mut self) {
+        if self.word.length == 0 {
+            return;
+        }
+
+        let passed = self.word.passed;
+        self.stats.words += 1;
+        for (count, passed) in self.stats.passed.iter_mut().zip(passed) {
+            *count += passed as u64;
+        }
+        self.stats.nice_part_one += (passed[VOWELS] && passed[DOUBLE_LETTER] && passed[NO_BANNED_PAIRS]) as u64;
+        self.stats.nice_part_two += (passed[PAIR_TWICE] && passed[SPLIT_PAIR]) as u64;
+        self.word = Word::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+    use crate::{words, Ruleset, PART_ONE_RULES, PART_TWO_RULES};
+    use super::*;
+
+    // The rule engine, one word at a time.
+    fn expected(input: &str) -> CorpusStats {
+        let (part_one, part_two) : (Ruleset, Ruleset) = (PART_ONE_RULES.parse().unwrap(), PART_TWO_RULES.parse().unwrap());
+        let mut stats = CorpusStats::default();
+        for word in words(input).unwrap() {
+            stats.words += 1;
+            let (one, two) = (part_one.classify(word), part_two.classify(word));
+            stats.nice_part_one += one.is_nice() as u64;
+            stats.nice_part_two += two.is_nice() as u64;
+            for (name, passed) in one.results.into_iter().chain(two.results) {
+                stats.passed[RULES.iter().position(|rule| *rule == name).unwrap()] += passed as u64;
+            }
+        }
+
+        stats
+    }
+
+    #[test]
+    fn examples() {
+        let input = "ugknbfddgicrmopn\naaa\njchzalrnumimnmhp\n\nhaegwjzuvuyypxyu\ndvszwmarrgswjxmb\nqjhvhtzxzqqjkmpb\nxxyxx\nuurcxstgmygtbstg\nieodomkazucvgmuy\naaaa\naaa";
+        let stats = Classifier::new().read(input.as_bytes()).unwrap();
+        assert_eq!(stats, expected(input));
+        assert_eq!((stats.words, stats.nice_part_one, stats.nice_part_two), (11, 4, 3));
+        assert_eq!(stats.passed("pair twice"), Some(4));
+        assert_eq!(stats.failed("no banned pairs"), Some(2));
+        assert_eq!(stats.passed("consonants"), None);
+        assert_eq!(stats.to_string().lines().nth(4), Some("pair twice: 4 passed, 7 failed"));
+    }
+
+    #[test]
+    fn matches_the_rule_engine() {
+        let input = fixtures::input(2015, 5).unwrap();
+        let stats = Classifier::new().read(BufReader::with_capacity(7, input.as_bytes())).unwrap();
+        assert_eq!(stats, expected(&input));
+        assert_eq!((stats.nice_part_one, stats.nice_part_two), (255, 55));
+    }
+
+    // Long words built from a few letters repeat pairs at every distance.
+    #[test]
+    fn generated_words() {
+        let mut seed : u64 = 2015;
+        let mut input = String::new();
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let length = (seed >> 33) % 12;
+            for _ in 0..length {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                input.push(b"abcdepqxy"[(seed >> 33) as usize % 9] as char);
+            }
+            input.push('\n');
+        }
+
+        assert_eq!(Classifier::new().read(input.as_bytes()).unwrap(), expected(&input));
+    }
+
+    #[test]
+    fn invalid_words() {
+        let mut classifier = Classifier::new();
+        classifier.feed(b"aaa\n\nab").unwrap();
+        assert_eq!(classifier.feed(b"-c").err().unwrap().to_string(), "line 3, column 3: unexpected character '-'");
+
+        let error = Classifier::new().read("ab\nné".as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 2: unexpected byte 0xc3");
+    }
+}
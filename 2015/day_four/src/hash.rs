@@ -0,0 +1,433 @@
+// Hash functions that can be fed their input in pieces. Mining hashes the same
+// key again and again with a different counter after it, so the state after
+// the key is kept and cloned for each counter.
+pub trait Hash: Clone + Send + Sync {
+    type Digest: AsRef<[u8]>;
+
+    // Bytes in a digest.
+    const LENGTH: usize;
+
+    fn new() -> Self;
+    fn update(&mut self, bytes: &[u8]);
+    fn digest(self) -> Self::Digest;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        let mut hash = Self::new();
+        hash.update(bytes);
+        hash.digest()
+    }
+
+    // Hashes what has been taken in so far followed by each of the suffixes.
+    // Hashes that can work on several messages side by side do so here.
+    fn digest_lanes(&self, suffixes: [&[u8]; LANES]) -> [Self::Digest; LANES] {
+        suffixes.map(|suffix| {
+            let mut hash = self.clone();
+            hash.update(suffix);
+            hash.digest()
+        })
+    }
+}
+
+// Messages hashed side by side by `digest_lanes`.
+pub const LANES : usize = 8;
+
+// MD5, SHA-1 and SHA-256 split their input into 64 byte blocks the same way,
+// and pad the last one with a single set bit, zeros and the length in bits.
+// MD5 writes the length little endian and the others big endian.
+#[derive(Clone)]
+struct Blocks {
+    buffer: [u8; 64],
+    filled: usize,
+    length: u64
+}
+
+impl Blocks {
+    fn new() -> Self {
+        Self { buffer: [0; 64], filled: 0, length: 0 }
+    }
+
+    fn update<F: FnMut(&[u8; 64])>(&mut self, mut bytes: &[u8], mut compress: F) {
+        self.length += bytes.len() as u64;
+        while !bytes.is_empty() {
+            let taken = bytes.len().min(64 - self.filled);
+            self.buffer[self.filled..self.filled + taken].copy_from_slice(&bytes[..taken]);
+            self.filled += taken;
+            bytes = &bytes[taken..];
+            if self.filled == 64 {
+                compress(&self.buffer);
+                self.filled = 0;
+            }
+        }
+    }
+
+    fn finish<F: FnMut(&[u8; 64])>(self, compress: F) {
+        self.pad(u64::to_be_bytes, compress)
+    }
+
+    fn pad<F: FnMut(&[u8; 64])>(mut self, length_bytes: fn(u64) -> [u8; 8], mut compress: F) {
+        let bits = self.length.wrapping_mul(8);
+        self.buffer[self.filled] = 0x80;
+        self.buffer[self.filled + 1..].fill(0);
+        if self.filled >= 56 {
+            compress(&self.buffer);
+            self.buffer.fill(0);
+        }
+        self.buffer[56..].copy_from_slice(&length_bytes(bits));
+        compress(&self.buffer);
+    }
+}
+
+const MD5_ROUND_CONSTANTS : [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391
+];
+
+const MD5_SHIFTS : [[u32; 4]; 4] = [[7, 12, 17, 22], [5, 9, 14, 20], [4, 11, 16, 23], [6, 10, 15, 21]];
+
+#[derive(Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    blocks: Blocks
+}
+
+impl Md5 {
+    // Compresses a block for each of `N` messages at once. Every step does the
+    // same sums on each message's words, so the loops over them compile to
+    // SIMD instructions where the target has them.
+    fn compress<const N: usize>(state: &mut [[u32; N]; 4], words: &[[u32; N]; 16]) {
+        let [mut a, mut b, mut c, mut d] = *state;
+        for (step, constant) in MD5_ROUND_CONSTANTS.into_iter().enumerate() {
+            let (word, shift) = match step / 16 {
+                0 => (step, MD5_SHIFTS[0][step % 4]),
+                1 => ((5 * step + 1) % 16, MD5_SHIFTS[1][step % 4]),
+                2 => ((3 * step + 5) % 16, MD5_SHIFTS[2][step % 4]),
+                _ => ((7 * step) % 16, MD5_SHIFTS[3][step % 4])
+            };
+
+            let mut next = [0; N];
+            for lane in 0..N {
+                let (b, c, d) = (b[lane], c[lane], d[lane]);
+                let mixed = match step / 16 {
+                    0 => (b & c) | (!b & d),
+                    1 => (d & b) | (!d & c),
+                    2 => b ^ c ^ d,
+                    _ => c ^ (b | !d)
+                };
+                next[lane] = b.wrapping_add(
+                    a[lane].wrapping_add(mixed).wrapping_add(constant).wrapping_add(words[word][lane]).rotate_left(shift)
+                );
+            }
+
+            a = d;
+            d = c;
+            c = b;
+            b = next;
+        }
+
+        for (words, values) in state.iter_mut().zip([a, b, c, d]) {
+            for (word, value) in words.iter_mut().zip(values) {
+                *word = word.wrapping_add(value);
+            }
+        }
+    }
+
+    fn compress_one(state: &mut [u32; 4], block: &[u8; 64]) {
+        let mut lanes = state.map(|word| [word]);
+        let mut words = [[0; 1]; 16];
+        for (word, chunk) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = [u32::from_le_bytes(chunk.try_into().unwrap())];
+        }
+
+        Self::compress(&mut lanes, &words);
+        *state = lanes.map(|[word]| word);
+    }
+}
+
+impl Hash for Md5 {
+    type Digest = [u8; 16];
+    const LENGTH: usize = 16;
+
+    fn new() -> Self {
+        Self { state: [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476], blocks: Blocks::new() }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(bytes, |block| Self::compress_one(state, block));
+    }
+
+    fn digest(mut self) -> Self::Digest {
+        let state = &mut self.state;
+        self.blocks.pad(u64::to_le_bytes, |block| Self::compress_one(state, block));
+
+        let mut digest = [0; 16];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    // When every suffix fits in the last block along with its padding, that
+    // block is built for each of them and all are compressed together.
+    // Otherwise they're hashed one after another.
+    fn digest_lanes(&self, suffixes: [&[u8]; LANES]) -> [Self::Digest; LANES] {
+        let filled = self.blocks.filled;
+        if suffixes.iter().any(|suffix| filled + suffix.len() + 9 > 64) {
+            return suffixes.map(|suffix| {
+                let mut hash = self.clone();
+                hash.update(suffix);
+                hash.digest()
+            });
+        }
+
+        let mut words = [[0; LANES]; 16];
+        for (lane, suffix) in suffixes.into_iter().enumerate() {
+            let mut block = self.blocks.buffer;
+            block[filled..filled + suffix.len()].copy_from_slice(suffix);
+            block[filled + suffix.len()] = 0x80;
+            block[filled + suffix.len() + 1..56].fill(0);
+            let bits = (self.blocks.length + suffix.len() as u64).wrapping_mul(8);
+            block[56..].copy_from_slice(&bits.to_le_bytes());
+
+            for (word, chunk) in words.iter_mut().zip(block.chunks_exact(4)) {
+                word[lane] = u32::from_le_bytes(chunk.try_into().unwrap());
+            }
+        }
+
+        let mut state = self.state.map(|word| [word; LANES]);
+        Self::compress(&mut state, &words);
+
+        std::array::from_fn(|lane| {
+            let mut digest = [0; 16];
+            for (chunk, words) in digest.chunks_exact_mut(4).zip(&state) {
+                chunk.copy_from_slice(&words[lane].to_le_bytes());
+            }
+            digest
+        })
+    }
+}
+
+fn words<const N: usize>(block: &[u8; 64]) -> [u32; N] {
+    let mut words = [0; N];
+    for (index, chunk) in block.chunks_exact(4).enumerate() {
+        words[index] = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+fn to_bytes<const N: usize, const M: usize>(state: [u32; N]) -> [u8; M] {
+    let mut bytes = [0; M];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    bytes
+}
+
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    blocks: Blocks
+}
+
+impl Sha1 {
+    fn compress(state: &mut [u32; 5], block: &[u8; 64]) {
+        let mut schedule : [u32; 80] = words(block);
+        for index in 16..80 {
+            schedule[index] = (schedule[index - 3] ^ schedule[index - 8] ^ schedule[index - 14] ^ schedule[index - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = *state;
+        for (index, word) in schedule.into_iter().enumerate() {
+            let (f, k) = match index {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6)
+            };
+            let temporary = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temporary;
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+impl Hash for Sha1 {
+    type Digest = [u8; 20];
+    const LENGTH: usize = 20;
+
+    fn new() -> Self {
+        Self { state: [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0], blocks: Blocks::new() }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(bytes, |block| Self::compress(state, block));
+    }
+
+    fn digest(mut self) -> Self::Digest {
+        let state = &mut self.state;
+        self.blocks.finish(|block| Self::compress(state, block));
+        to_bytes(self.state)
+    }
+}
+
+const SHA256_ROUND_CONSTANTS : [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+];
+
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    blocks: Blocks
+}
+
+impl Sha256 {
+    fn compress(state: &mut [u32; 8], block: &[u8; 64]) {
+        let mut schedule : [u32; 64] = words(block);
+        for index in 16..64 {
+            let s0 = schedule[index - 15].rotate_right(7) ^ schedule[index - 15].rotate_right(18) ^ (schedule[index - 15] >> 3);
+            let s1 = schedule[index - 2].rotate_right(17) ^ schedule[index - 2].rotate_right(19) ^ (schedule[index - 2] >> 10);
+            schedule[index] = schedule[index - 16].wrapping_add(s0).wrapping_add(schedule[index - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (word, constant) in schedule.into_iter().zip(SHA256_ROUND_CONSTANTS) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let first = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(constant).wrapping_add(word);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let second = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(first);
+            d = c;
+            c = b;
+            b = a;
+            a = first.wrapping_add(second);
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+impl Hash for Sha256 {
+    type Digest = [u8; 32];
+    const LENGTH: usize = 32;
+
+    fn new() -> Self {
+        Self {
+            state: [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19],
+            blocks: Blocks::new()
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(bytes, |block| Self::compress(state, block));
+    }
+
+    fn digest(mut self) -> Self::Digest {
+        let state = &mut self.state;
+        self.blocks.finish(|block| Self::compress(state, block));
+        to_bytes(self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(digest: impl AsRef<[u8]>) -> String {
+        digest.as_ref().iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    const LONG : &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+    #[test]
+    fn md5() {
+        assert_eq!(hex(Md5::hash(b"abcdef609043")), "000001dbbfa3a5c83a2d506429c7b00e");
+        assert_eq!(hex(Md5::hash(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hex(Md5::hash(LONG)), "8215ef0796a20bcaaae116d3876c664a");
+    }
+
+    // Side by side or one at a time, and however long the key, the digests
+    // match the md5 crate's.
+    #[test]
+    fn md5_lanes() {
+        for key_length in [0, 8, 47, 55, 56, 63, 64, 100] {
+            let key : Vec<u8> = (0..key_length).map(|index| b'a' + (index % 26) as u8).collect();
+            let mut prefix = Md5::new();
+            prefix.update(&key);
+
+            for first in [1_u64, 99_999_996, u64::MAX - 7] {
+                let counters : Vec<String> = (0..LANES as u64).map(|lane| (first + lane).to_string()).collect();
+                let suffixes : [&[u8]; LANES] = std::array::from_fn(|lane| counters[lane].as_bytes());
+
+                for (suffix, digest) in suffixes.into_iter().zip(prefix.digest_lanes(suffixes)) {
+                    let message = [key.as_slice(), suffix].concat();
+                    assert_eq!(digest, md5::compute(&message).0, "{key_length} byte key, suffix {suffix:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sha1() {
+        assert_eq!(hex(Sha1::hash(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(hex(Sha1::hash(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(hex(Sha1::hash(LONG)), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+    }
+
+    #[test]
+    fn sha256() {
+        assert_eq!(hex(Sha256::hash(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(Sha256::hash(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hex(Sha256::hash(LONG)), "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1");
+    }
+
+    #[test]
+    fn updates_in_pieces() {
+        let bytes : Vec<u8> = (0..1000u32).map(|index| (index * 31 % 251) as u8).collect();
+
+        for pieces in [1, 7, 63, 64, 65, 999] {
+            let mut md5 = Md5::new();
+            let mut sha1 = Sha1::new();
+            let mut sha256 = Sha256::new();
+            for chunk in bytes.chunks(pieces) {
+                md5.update(chunk);
+                sha1.update(chunk);
+                sha256.update(chunk);
+            }
+            assert_eq!(md5.digest(), md5::compute(&bytes).0, "{pieces}");
+            assert_eq!(sha1.digest(), Sha1::hash(&bytes), "{pieces}");
+            assert_eq!(sha256.digest(), Sha256::hash(&bytes), "{pieces}");
+        }
+        assert_eq!(hex(Sha1::hash(&bytes)), "0e4578c1606bbc12094a6359df15cea4e1118583");
+        assert_eq!(hex(Sha256::hash(&bytes)), "f3f55c45264850b8475533289ff43ab81fa1eb3bf781267db645e1ce0c193379");
+    }
+}
@@ -1,37 +1,397 @@
-use md5;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use parsing::ParseError;
 
-fn find_suffix_for(input: &str, prefix: &str) -> u32 {
-    let mut counter : u32 = 1;
-    let mut coin_cleartext: String;
-    let mut coin_hex : String;
+mod hash;
 
+pub use hash::{Hash, Md5, Sha1, Sha256, LANES};
+
+// Threads take counters to try in batches of this many.
+const BATCH_SIZE : u64 = 4096;
+
+fn secret_key(input: &str) -> Result<&str, ParseError> {
+    match input.trim() {
+        "" => Err(ParseError::new("expected a secret key")),
+        key if key.contains(char::is_whitespace) => Err(ParseError::new(format!("expected a single secret key, found '{key}'"))),
+        key => Ok(key)
+    }
+}
+
+// Whether the digest written in hex would start with `zeros` zeros, checked on
+// the bytes themselves, two hex digits to a byte.
+fn has_leading_zeros(digest: &[u8], zeros: usize) -> bool {
+    if zeros > digest.len() * 2 {
+        return false;
+    }
+
+    digest[..zeros / 2].iter().all(|&byte| byte == 0) && (zeros.is_multiple_of(2) || digest[zeros / 2] >> 4 == 0)
+}
+
+// The counter's decimal digits, written into the end of the buffer.
+fn decimal(buffer: &mut [u8; 20], mut number: u64) -> &[u8] {
+    let mut start = buffer.len();
     loop {
-        coin_cleartext = format!("{input}{counter}");
-        coin_hex = format!("{:x?}", md5::compute(coin_cleartext.as_bytes()));
-        if coin_hex.starts_with(prefix) {
-            return counter;
+        start -= 1;
+        buffer[start] = b'0' + (number % 10) as u8;
+        number /= 10;
+        if number == 0 {
+            return &buffer[start..];
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    pub hashes: u64,
+    pub elapsed: Duration
+}
+
+impl Progress {
+    pub fn hashes_per_second(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            0.0 => 0.0,
+            seconds => self.hashes as f64 / seconds
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mined {
+    pub suffix: u64,
+    pub progress: Progress
+}
+
+// Decides which digests count as proof of work.
+pub trait Predicate: Sync {
+    fn matches(&self, digest: &[u8]) -> bool;
+
+    // Whether any digest this many bytes long could match, so that searches
+    // for something impossible can give up straight away.
+    fn is_satisfiable(&self, _digest_length: usize) -> bool {
+        true
+    }
+}
+
+impl<F> Predicate for F where F: Fn(&[u8]) -> bool + Sync {
+    fn matches(&self, digest: &[u8]) -> bool {
+        self(digest)
+    }
+}
+
+// The digest written in hex starts with this many zeros.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LeadingZeros(pub usize);
+
+impl Predicate for LeadingZeros {
+    fn matches(&self, digest: &[u8]) -> bool {
+        has_leading_zeros(digest, self.0)
+    }
+
+    fn is_satisfiable(&self, digest_length: usize) -> bool {
+        self.0 <= digest_length * 2
+    }
+}
+
+// Searches the counters from `start` up to, but not including, `limit` for
+// ones that hashed after the key give a digest matching the predicate. The
+// first match can be mined across threads, which claim batches of counters in
+// order and stop once every batch below the best match so far has been tried.
+// Within a batch, counters are hashed `LANES` at a time.
+pub struct Miner<'a, P, H = Md5> {
+    key: &'a str,
+    predicate: P,
+    start: u64,
+    limit: u64,
+    threads: usize,
+    hash: PhantomData<fn() -> H>
+}
+
+impl<'a> Miner<'a, LeadingZeros> {
+    pub fn new(key: &'a str, zeros: usize) -> Self {
+        Self::with_predicate(key, LeadingZeros(zeros))
+    }
+}
+
+impl<'a, P: Predicate> Miner<'a, P> {
+    pub fn with_predicate(key: &'a str, predicate: P) -> Self {
+        let threads = thread::available_parallelism().map(usize::from).unwrap_or(1);
+        Self { key, predicate, start: 1, limit: u64::MAX, threads, hash: PhantomData }
+    }
+}
+
+impl<'a, P: Predicate, H: Hash> Miner<'a, P, H> {
+    pub fn hashed_with<G: Hash>(self) -> Miner<'a, P, G> {
+        Miner { key: self.key, predicate: self.predicate, start: self.start, limit: self.limit, threads: self.threads, hash: PhantomData }
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn start(mut self, start: u64) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    // The hash's state once it has taken in the key.
+    fn prefix(&self) -> H {
+        let mut prefix = H::new();
+        prefix.update(self.key.as_bytes());
+        prefix
+    }
+
+    // Every matching counter in order, found on this thread as they are asked for.
+    pub fn matches(&self) -> Matches<'_, 'a, P, H> {
+        let next = if self.predicate.is_satisfiable(H::LENGTH) { self.start } else { self.limit };
+        Matches { miner: self, prefix: self.prefix(), next, buffer: [0; 20] }
+    }
+
+    // Nothing is found when no counter below the limit matches, or when the
+    // predicate can never match.
+    pub fn mine(&self) -> Option<Mined> {
+        self.mine_with_progress(Duration::MAX, |_| {})
+    }
+
+    // Calls `report` with the hashes tried so far every `interval` until the
+    // search is over.
+    pub fn mine_with_progress<F>(&self, interval: Duration, mut report: F) -> Option<Mined>
+        where F: FnMut(Progress)
+    {
+        if !self.predicate.is_satisfiable(H::LENGTH) {
+            return None;
+        }
+
+        let started = Instant::now();
+        let next_batch = AtomicU64::new(self.start);
+        let best = AtomicU64::new(self.limit);
+        let hashes = AtomicU64::new(0);
+        let progress = || Progress { hashes: hashes.load(Ordering::Relaxed), elapsed: started.elapsed() };
+        let prefix = self.prefix();
+
+        thread::scope(|scope| {
+            // every worker holds a sender, so the channel closes once they have all finished
+            let (finished, done) = mpsc::channel::<()>();
+            for _ in 0..self.threads {
+                let finished = finished.clone();
+                let (prefix, next_batch, best, hashes) = (&prefix, &next_batch, &best, &hashes);
+                scope.spawn(move || {
+                    let _finished = finished;
+                    let mut buffers = [[0; 20]; LANES];
+                    loop {
+                        // saturating, so batches near the top of the range never wrap round to counters below the start
+                        let batch = next_batch.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |batch| Some(batch.saturating_add(BATCH_SIZE))).unwrap();
+                        if batch >= best.load(Ordering::Relaxed) {
+                            return;
+                        }
+
+                        let end = batch.saturating_add(BATCH_SIZE).min(self.limit);
+                        let mut counter = batch;
+                        let mut tried = 0;
+                        while counter < end {
+                            // short of a full set of lanes, the last counter is hashed again to fill them
+                            let lanes = (end - counter).min(LANES as u64) as usize;
+                            let mut starts = [0; LANES];
+                            for (lane, (buffer, start)) in buffers.iter_mut().zip(&mut starts).enumerate() {
+                                *start = buffer.len() - decimal(buffer, counter + lane.min(lanes - 1) as u64).len();
+                            }
+                            let digests = prefix.digest_lanes(std::array::from_fn(|lane| &buffers[lane][starts[lane]..]));
+
+                            match digests[..lanes].iter().position(|digest| self.predicate.matches(digest.as_ref())) {
+                                Some(lane) => {
+                                    tried += lane as u64 + 1;
+                                    best.fetch_min(counter + lane as u64, Ordering::Relaxed);
+                                    break;
+                                },
+                                None => {
+                                    tried += lanes as u64;
+                                    counter += lanes as u64;
+                                }
+                            }
+                        }
+                        hashes.fetch_add(tried, Ordering::Relaxed);
+                    }
+                });
+            }
+            drop(finished);
+
+            while let Err(RecvTimeoutError::Timeout) = done.recv_timeout(interval) {
+                report(progress());
+            }
+        });
+
+        let suffix = best.into_inner();
+        (suffix < self.limit).then(|| Mined { suffix, progress: progress() })
+    }
+}
+
+pub struct Matches<'m, 'a, P, H> {
+    miner: &'m Miner<'a, P, H>,
+    prefix: H,
+    next: u64,
+    buffer: [u8; 20]
+}
+
+impl<P: Predicate, H: Hash> Iterator for Matches<'_, '_, P, H> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.next < self.miner.limit {
+            let counter = self.next;
+            self.next += 1;
+
+            let mut hash = self.prefix.clone();
+            hash.update(decimal(&mut self.buffer, counter));
+            if self.miner.predicate.matches(hash.digest().as_ref()) {
+                return Some(counter);
+            }
         }
-        counter += 1;
+
+        None
     }
 }
 
+fn find_suffix_for(input: &str, zeros: usize) -> u64 {
+    Miner::new(input, zeros).mine().expect("an MD5 digest has room for the zeros").suffix
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    Ok(find_suffix_for(secret_key(input)?, 5))
+}
+
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    Ok(find_suffix_for(secret_key(input)?, 6))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn part_one_examples() {
-        assert_eq!(find_suffix_for("abcdef", "00000"), 609043);
-        assert_eq!(find_suffix_for("pqrstuv", "00000"), 1048970);
+        assert_eq!(find_suffix_for("abcdef", 5), 609043);
+        assert_eq!(find_suffix_for("pqrstuv", 5), 1048970);
+    }
+
+    #[test]
+    fn secret_keys() {
+        assert_eq!(secret_key("ckczppom\n"), Ok("ckczppom"));
+        assert_eq!(secret_key("\n").err().unwrap().to_string(), "expected a secret key");
+        assert_eq!(secret_key("abc def").err().unwrap().to_string(), "expected a single secret key, found 'abc def'");
+    }
+
+    #[test]
+    fn leading_zeros() {
+        assert!(has_leading_zeros(&[0x00, 0x00, 0x0f, 0xff], 5));
+        assert!(!has_leading_zeros(&[0x00, 0x00, 0x1f, 0xff], 5));
+        assert!(has_leading_zeros(&[0x00, 0x00, 0x1f, 0xff], 4));
+        assert!(has_leading_zeros(&[0x12], 0));
+        assert!(has_leading_zeros(&[0x00, 0x00], 4));
+        assert!(!has_leading_zeros(&[0x00, 0x00], 5));
+    }
+
+    #[test]
+    fn decimals() {
+        let mut buffer = [0; 20];
+        assert_eq!(decimal(&mut buffer, 0), b"0");
+        assert_eq!(decimal(&mut buffer, 609043), b"609043");
+        assert_eq!(decimal(&mut buffer, u64::MAX), u64::MAX.to_string().as_bytes());
+    }
+
+    #[test]
+    fn any_thread_count_finds_the_lowest_suffix() {
+        for threads in [1, 2, 3, 8] {
+            let mined = Miner::new("abcdef", 5).threads(threads).mine().unwrap();
+            assert_eq!(mined.suffix, 609043, "{threads} thread(s)");
+            assert!(mined.progress.hashes >= 609043);
+        }
+    }
+
+    #[test]
+    fn any_number_of_zeros() {
+        assert_eq!(Miner::new("abcdef", 0).mine().unwrap().suffix, 1);
+        assert_eq!(Miner::new("abcdef", 0).start(42).mine().unwrap().suffix, 42);
+        assert_eq!(Miner::new("abcdef", 3).mine().unwrap().suffix, 3337);
+        assert_eq!(Miner::new("abcdef", 33).mine(), None);
+        assert_eq!(Miner::new("abcdef", 33).matches().next(), None);
+    }
+
+    #[test]
+    fn limits() {
+        assert_eq!(Miner::new("abcdef", 5).limit(609043).mine(), None);
+        assert_eq!(Miner::new("abcdef", 5).limit(609044).mine().unwrap().suffix, 609043);
+        assert_eq!(Miner::new("abcdef", 5).start(609044).limit(700000).matches().next(), None);
+    }
+
+    // Batches claimed near the top of the counters never wrap round to ones
+    // below the start.
+    #[test]
+    fn counters_near_the_end() {
+        let anything = |_: &[u8]| true;
+        for threads in [1, 4] {
+            let start = u64::MAX - 100;
+            assert_eq!(Miner::with_predicate("abcdef", anything).start(start).threads(threads).mine().unwrap().suffix, start);
+        }
+
+        let nothing = |_: &[u8]| false;
+        let miner = Miner::with_predicate("abcdef", nothing).start(u64::MAX - 3 * BATCH_SIZE - 5).threads(3);
+        let mined = miner.mine_with_progress(Duration::MAX, |_| {});
+        assert_eq!(mined, None);
+        assert_eq!(miner.matches().next(), None);
+
+        let last = |digest: &[u8]| digest == Md5::hash(format!("abcdef{}", u64::MAX - 1).as_bytes());
+        assert_eq!(Miner::with_predicate("abcdef", last).start(u64::MAX - 10_000).mine().unwrap().suffix, u64::MAX - 1);
+    }
+
+    #[test]
+    fn iterates_over_every_match() {
+        let matches : Vec<u64> = Miner::new("abcdef", 3).limit(20000).matches().collect();
+        assert_eq!(matches[0], 3337);
+        assert!(matches.iter().all(|&counter| has_leading_zeros(&Md5::hash(format!("abcdef{counter}").as_bytes()), 3)));
+
+        let resumed : Vec<u64> = Miner::new("abcdef", 3).start(matches[0] + 1).limit(20000).matches().collect();
+        assert_eq!(resumed, matches[1..]);
+    }
+
+    #[test]
+    fn other_hashes_and_predicates() {
+        let miner = Miner::new("abcdef", 4).hashed_with::<Sha256>();
+        let suffix = miner.mine().unwrap().suffix;
+        assert_eq!(miner.matches().next(), Some(suffix));
+        assert!(Sha256::hash(format!("abcdef{suffix}").as_bytes()).starts_with(&[0, 0]));
+
+        let ends_in_zero = |digest: &[u8]| digest.last() == Some(&0);
+        let miner = Miner::with_predicate("abcdef", ends_in_zero).hashed_with::<Sha1>().threads(3);
+        let suffixes : Vec<u64> = miner.matches().take(3).collect();
+        assert_eq!(miner.mine().unwrap().suffix, suffixes[0]);
+        assert!(suffixes.iter().all(|suffix| Sha1::hash(format!("abcdef{suffix}").as_bytes())[19] == 0));
+    }
+
+    #[test]
+    fn reports_progress() {
+        let mut reports = vec![];
+        let mined = Miner::new("pqrstuv", 5).threads(2).mine_with_progress(Duration::from_millis(1), |progress| reports.push(progress));
+
+        assert_eq!(mined.unwrap().suffix, 1048970);
+        assert!(reports.windows(2).all(|pair| pair[0].hashes <= pair[1].hashes));
+        assert!(mined.unwrap().progress.hashes_per_second() > 0.0);
     }
 
     #[test]
     fn part_one() {
-        assert_eq!(find_suffix_for("ckczppom", "00000"), 117946);
+        assert_eq!(find_suffix_for("ckczppom", 5), 117946);
     }
 
     #[test]
     fn part_two() {
-        assert_eq!(find_suffix_for("ckczppom", "000000"), 3938038);
+        assert_eq!(find_suffix_for("ckczppom", 6), 3938038);
     }
 }
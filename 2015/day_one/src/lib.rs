@@ -1,80 +1,230 @@
-fn process_full_instructions(input: &str) -> i32 {
-    let mut floor = 0;
-
-    for char in input.chars() {
-        match char {
-            '(' => floor += 1,
-            ')' => floor -= 1,
-            '\n' => {},
-            x => panic!("Unexpected char: {x}")
-        }
-    }
+use std::io::{BufReader, Read};
+use parsing::{ParseError, ReadError};
 
-    floor
+// Follows the instructions as they arrive, so they never have to be held in
+// memory. Floors only change by one at a time, so every floor between the
+// lowest and the highest has been visited, and the first visits to the floors
+// above and below the start are kept in order.
+#[derive(Clone, Debug, Default)]
+pub struct FloorTracker {
+    floor: i64,
+    moves: usize,
+    first_visits_above: Vec<usize>,
+    first_visits_below: Vec<usize>,
+    series: Option<Vec<i64>>,
+    line: usize,
+    column: usize
 }
 
-fn find_position_of_first_basement_move(input: &str) -> i32 {
-    let mut floor = 0;
+impl FloorTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Also keeps the floor after every move, which takes memory for each one.
+    pub fn with_series(mut self) -> Self {
+        self.series = Some(vec![self.floor]);
+        self
+    }
+
+    // Any reader will do, as it is buffered here.
+    pub fn read<R: Read>(mut self, reader: R) -> Result<Self, ReadError> {
+        parsing::read_chunks(BufReader::new(reader), |bytes| self.feed(bytes))?;
+        Ok(self)
+    }
 
-    for (index, char ) in input.chars().enumerate() {
-        match char {
-            '(' => floor += 1,
-            ')' => {
-                floor -= 1;
-                if floor == -1 {
-                    return (index + 1) as i32;
+    // Instructions can be split anywhere, and errors are reported at their
+    // place in everything fed so far. Line breaks are ignored.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<(), ParseError> {
+        for &byte in bytes {
+            self.column += 1;
+            match byte {
+                b'(' => { self.step(1) },
+                b')' => { self.step(-1) },
+                b'\n' => { self.line += 1; self.column = 0; },
+                b'\r' => {},
+                byte if byte.is_ascii() => {
+                    return Err(ParseError::unexpected_character(byte as char, self.column).with_line(self.line + 1))
+                },
+                byte => {
+                    return Err(ParseError::at_column(self.column, format!("unexpected byte 0x{byte:02x}")).with_line(self.line + 1))
                 }
-            },
-            '\n' => {},
-            x => panic!("Unexpected char: {x}")
+            }
+        }
+
+        Ok(())
+    }
+
+    fn step(&mut self, change: i64) {
+        self.floor += change;
+        self.moves += 1;
+
+        if self.floor > self.first_visits_above.len() as i64 {
+            self.first_visits_above.push(self.moves);
+        } else if -self.floor > self.first_visits_below.len() as i64 {
+            self.first_visits_below.push(self.moves);
+        }
+        if let Some(series) = &mut self.series {
+            series.push(self.floor);
+        }
+    }
+
+    pub fn floor(&self) -> i64 {
+        self.floor
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    pub fn lowest(&self) -> i64 {
+        -(self.first_visits_below.len() as i64)
+    }
+
+    pub fn highest(&self) -> i64 {
+        self.first_visits_above.len() as i64
+    }
+
+    // How many moves it took to first reach a floor, counting from 1.
+    pub fn first_visit(&self, floor: i64) -> Option<usize> {
+        match floor {
+            0 => Some(0),
+            1.. => self.first_visits_above.get(floor as usize - 1).copied(),
+            _ => self.first_visits_below.get(floor.unsigned_abs() as usize - 1).copied()
         }
     }
 
-    panic!("Never entered basement!")
+    // The floor before any moves, then after each one.
+    pub fn series(&self) -> Option<&[i64]> {
+        self.series.as_deref()
+    }
+}
+
+fn process_full_instructions(input: &str) -> Result<i64, ParseError> {
+    Ok(FloorTracker::new().read(input.as_bytes())?.floor())
+}
+
+fn find_position_of_first_basement_move(input: &str) -> Result<usize, ParseError> {
+    FloorTracker::new().read(input.as_bytes())?.first_visit(-1)
+        .ok_or_else(|| ParseError::new("never entered the basement"))
+}
+
+pub fn part_one(input: &str) -> Result<i64, ParseError> {
+    process_full_instructions(input)
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    find_position_of_first_basement_move(input)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use crate::*;
 
     #[test]
     fn matched_pairs_cancel_to_zero() {
-        assert_eq!(process_full_instructions("(())"), 0);
-        assert_eq!(process_full_instructions("()()"), 0);
+        assert_eq!(process_full_instructions("(())").unwrap(), 0);
+        assert_eq!(process_full_instructions("()()").unwrap(), 0);
     }
 
     #[test]
     fn threes() {
-        assert_eq!(process_full_instructions("((("), 3);
-        assert_eq!(process_full_instructions("(()(()("), 3);
-        assert_eq!(process_full_instructions("))((((("), 3);
+        assert_eq!(process_full_instructions("(((").unwrap(), 3);
+        assert_eq!(process_full_instructions("(()(()(").unwrap(), 3);
+        assert_eq!(process_full_instructions("))(((((").unwrap(), 3);
     }
 
     #[test]
     fn negatives() {
-        assert_eq!(process_full_instructions("())"), -1);
-        assert_eq!(process_full_instructions("))("), -1);
+        assert_eq!(process_full_instructions("())").unwrap(), -1);
+        assert_eq!(process_full_instructions("))(").unwrap(), -1);
 
-        assert_eq!(process_full_instructions(")))"), -3);
-        assert_eq!(process_full_instructions(")())())"), -3);
+        assert_eq!(process_full_instructions(")))").unwrap(), -3);
+        assert_eq!(process_full_instructions(")())())").unwrap(), -3);
     }
 
     #[test]
     fn part_one() {
-        let input = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(process_full_instructions(&input), 138);
+        let input = fixtures::input(2015, 1).unwrap();
+        assert_eq!(process_full_instructions(&input).unwrap(), 138);
+    }
+
+    #[test]
+    fn unexpected_characters() {
+        assert_eq!(process_full_instructions("(()\n)x(").err().unwrap().to_string(), "line 2, column 2: unexpected character 'x'");
     }
 
     #[test]
     fn part_two_examples() {
-        assert_eq!(find_position_of_first_basement_move(")"), 1);
-        assert_eq!(find_position_of_first_basement_move("()())"), 5);
+        assert_eq!(find_position_of_first_basement_move(")").unwrap(), 1);
+        assert_eq!(find_position_of_first_basement_move("()())").unwrap(), 5);
+    }
+
+    #[test]
+    fn never_entering_the_basement() {
+        assert_eq!(find_position_of_first_basement_move("(()").err().unwrap().to_string(), "never entered the basement");
+    }
+
+    #[test]
+    fn trajectory() {
+        let tracker = FloorTracker::new().with_series().read("(()))(\n)))((".as_bytes()).unwrap();
+        assert_eq!(tracker.floor(), -1);
+        assert_eq!(tracker.moves(), 11);
+        assert_eq!((tracker.lowest(), tracker.highest()), (-3, 2));
+        assert_eq!(tracker.series(), Some(&[0, 1, 2, 1, 0, -1, 0, -1, -2, -3, -2, -1][..]));
+        assert_eq!(tracker.first_visit(0), Some(0));
+        assert_eq!(tracker.first_visit(2), Some(2));
+        assert_eq!(tracker.first_visit(-1), Some(5));
+        assert_eq!(tracker.first_visit(-2), Some(8));
+        assert_eq!(tracker.first_visit(-3), Some(9));
+        assert_eq!(tracker.first_visit(3), None);
+        assert_eq!(tracker.first_visit(-4), None);
+
+        assert_eq!(FloorTracker::new().read(&b"(("[..]).unwrap().series(), None);
+    }
+
+    #[test]
+    fn feeding_in_pieces() {
+        let input = fixtures::input(2015, 1).unwrap();
+        let whole = FloorTracker::new().read(input.as_bytes()).unwrap();
+        let file = std::fs::File::open(fixtures::InputStore::default().path(2015, 1)).unwrap();
+        assert_eq!(FloorTracker::new().read(file).unwrap().floor(), whole.floor());
+
+        let mut tracker = FloorTracker::new();
+        for piece in input.as_bytes().chunks(7) {
+            tracker.feed(piece).unwrap();
+        }
+        assert_eq!((tracker.floor(), tracker.lowest(), tracker.highest()), (whole.floor(), whole.lowest(), whole.highest()));
+        assert_eq!(tracker.first_visit(-1), Some(1771));
+
+        let mut tracker = FloorTracker::new();
+        tracker.feed(b"((\n(").unwrap();
+        let error = tracker.feed(b")?").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 3: unexpected character '?'");
+        assert_eq!(FloorTracker::new().feed("(é".as_bytes()).err().unwrap().to_string(), "line 1, column 2: unexpected byte 0xc3");
+    }
+
+    // Readers can fail part way through.
+    #[test]
+    fn read_errors() {
+        use std::io;
+
+        struct Broken;
+
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk on fire"))
+            }
+        }
+
+        let error = FloorTracker::new().read(Broken).err().unwrap();
+        assert_eq!(error.to_string(), "could not read input: disk on fire");
+        assert_eq!(ParseError::from(error).to_string(), "could not read input: disk on fire");
     }
 
     #[test]
     fn part_two() {
-        let input = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(find_position_of_first_basement_move(&input), 1771);
+        let input = fixtures::input(2015, 1).unwrap();
+        assert_eq!(find_position_of_first_basement_move(&input).unwrap(), 1771);
     }
 }
@@ -1,110 +1,507 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::{error, fmt};
+use std::str::FromStr;
+use parsing::ParseError;
 
-type Signal = u16;
+// The puzzle's signals are 16 bits wide.
+pub type Signal = u16;
+
+// An unsigned integer a circuit's signals can be made of.
+pub trait Word: Copy + Default + Eq + fmt::Debug + fmt::Display + FromStr {
+    const BITS: u32;
+
+    fn complement(self) -> Self;
+    fn shifted_left(self, shift: u32) -> Self;
+    fn shifted_right(self, shift: u32) -> Self;
+    // Nothing when dividing by zero.
+    fn combine(self, operator: Operator, other: Self) -> Option<Self>;
+}
+
+macro_rules! word {
+    ($($type:ty),*) => {
+        $(
+            impl Word for $type {
+                const BITS: u32 = <$type>::BITS;
+
+                fn complement(self) -> Self {
+                    !self
+                }
+
+                fn shifted_left(self, shift: u32) -> Self {
+                    self << shift
+                }
+
+                fn shifted_right(self, shift: u32) -> Self {
+                    self >> shift
+                }
+
+                fn combine(self, operator: Operator, other: Self) -> Option<Self> {
+                    match operator {
+                        Operator::And => Some(self & other),
+                        Operator::Or => Some(self | other),
+                        Operator::Xor => Some(self ^ other),
+                        Operator::Nand => Some(!(self & other)),
+                        Operator::Nor => Some(!(self | other)),
+                        Operator::Add => Some(self.wrapping_add(other)),
+                        Operator::Subtract => Some(self.wrapping_sub(other)),
+                        Operator::Multiply => Some(self.wrapping_mul(other)),
+                        Operator::Divide => self.checked_div(other),
+                        Operator::Remainder => self.checked_rem(other)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+word!(u8, u16, u32, u64);
+
+// Gates that combine two signals. Arithmetic wraps around at the word width.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Operator {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder
+}
+
+impl FromStr for Operator {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "AND" => Ok(Operator::And),
+            "OR" => Ok(Operator::Or),
+            "XOR" => Ok(Operator::Xor),
+            "NAND" => Ok(Operator::Nand),
+            "NOR" => Ok(Operator::Nor),
+            "ADD" => Ok(Operator::Add),
+            "SUB" => Ok(Operator::Subtract),
+            "MUL" => Ok(Operator::Multiply),
+            "DIV" => Ok(Operator::Divide),
+            "MOD" => Ok(Operator::Remainder),
+            _ => Err(ParseError::at_token(name, name, format!("unknown operator {name}")))
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Operator::And => "AND",
+            Operator::Or => "OR",
+            Operator::Xor => "XOR",
+            Operator::Nand => "NAND",
+            Operator::Nor => "NOR",
+            Operator::Add => "ADD",
+            Operator::Subtract => "SUB",
+            Operator::Multiply => "MUL",
+            Operator::Divide => "DIV",
+            Operator::Remainder => "MOD"
+        };
+        formatter.write_str(name)
+    }
+}
 
 #[derive(Copy, Clone)]
-enum Component<'a> {
-    Value { signal: Signal },
+enum Component<'a, W> {
+    Value { signal: W },
     Source { source: &'a str },
-    AndGate { left: &'a str, right: &'a str },
-    OrGate { left: &'a str, right: &'a str },
+    Gate { operator: Operator, left: &'a str, right: &'a str },
     ComplimentGate { source: &'a str },
-    LeftShiftGate { source: &'a str, shift: Signal },
-    RightShiftGate { source: &'a str, shift: Signal },
+    LeftShiftGate { source: &'a str, shift: u32 },
+    RightShiftGate { source: &'a str, shift: u32 },
+}
+
+// Operands starting with a digit are numbers rather than wires.
+fn is_number(operand: &str) -> bool {
+    operand.starts_with(|symbol: char| symbol.is_ascii_digit())
+}
+
+// Checks that a number operand fits in a word.
+fn operand<'a, W: Word>(input: &str, operand: &'a str) -> Result<&'a str, ParseError> {
+    if is_number(operand) {
+        parsing::number::<W>(input, operand)?;
+    }
+
+    Ok(operand)
+}
+
+fn shift<W: Word>(input: &str, amount: &str) -> Result<u32, ParseError> {
+    match parsing::number(input, amount)? {
+        shift if shift < W::BITS => Ok(shift),
+        shift => Err(ParseError::at_token(input, amount, format!("can't shift {} bit signals by {shift}", W::BITS)))
+    }
+}
+
+fn parse_component<W: Word>(input: &str) -> Result<(&str, Component<'_, W>), ParseError> {
+    let (expression, name) = parsing::split_once(input, " -> ")?;
+    let mut parts = expression.split(" ").collect::<Vec<&str>>();
+    let right = parts.pop().unwrap();
+    let component = match parts[..] {
+        [] if is_number(right) => Component::Value { signal: parsing::number(input, right)? },
+        [] => Component::Source { source: right },
+        ["NOT"] => Component::ComplimentGate { source: operand::<W>(input, right)? },
+        [left, "LSHIFT"] => Component::LeftShiftGate { source: operand::<W>(input, left)?, shift: shift::<W>(input, right)? },
+        [left, "RSHIFT"] => Component::RightShiftGate { source: operand::<W>(input, left)?, shift: shift::<W>(input, right)? },
+        [left, operator] => Component::Gate {
+            operator: operator.parse().map_err(|error: ParseError| error.within(input, operator))?,
+            left: operand::<W>(input, left)?,
+            right: operand::<W>(input, right)?
+        },
+        _ => return Err(ParseError::new(format!("invalid component '{input}'")))
+    };
+
+    Ok((name, component))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CircuitError {
+    UnknownWire(String),
+    // The wire whose gate divides by zero.
+    DivisionByZero(String),
+    // The wires around a feedback loop, each fed by the one after it.
+    Cycle(Vec<String>)
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircuitError::UnknownWire(wire) => write!(formatter, "nothing is connected to wire {wire}"),
+            CircuitError::DivisionByZero(wire) => write!(formatter, "wire {wire} divides by zero"),
+            CircuitError::Cycle(wires) => write!(formatter, "feedback loop through wires {}", wires.join(" <- "))
+        }
+    }
+}
+
+impl error::Error for CircuitError {}
+
+impl From<CircuitError> for ParseError {
+    fn from(error: CircuitError) -> Self {
+        ParseError::new(error.to_string())
+    }
+}
+
+impl<'a, W: Word> Component<'a, W> {
+    // The wires feeding the component, leaving out plain numbers.
+    fn inputs(&self) -> Vec<&'a str> {
+        let operands = match *self {
+            Component::Value { .. } => vec![],
+            Component::Source { source } => vec![source],
+            Component::Gate { left, right, .. } => vec![left, right],
+            Component::ComplimentGate { source } => vec![source],
+            Component::LeftShiftGate { source, .. } => vec![source],
+            Component::RightShiftGate { source, .. } => vec![source],
+        };
+
+        operands.into_iter().filter(|operand| !is_number(operand)).collect()
+    }
+}
+
+// The component as it is written before the arrow.
+impl<W: Word> fmt::Display for Component<'_, W> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Component::Value { signal } => write!(formatter, "{signal}"),
+            Component::Source { source } => write!(formatter, "{source}"),
+            Component::Gate { operator, left, right } => write!(formatter, "{left} {operator} {right}"),
+            Component::ComplimentGate { source } => write!(formatter, "NOT {source}"),
+            Component::LeftShiftGate { source, shift } => write!(formatter, "{source} LSHIFT {shift}"),
+            Component::RightShiftGate { source, shift } => write!(formatter, "{source} RSHIFT {shift}"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Visit {
+    Started,
+    Finished
 }
 
-struct Circuit<'a> {
-    components: HashMap<&'a str, Component<'a>>
+// Signals are worked out in dependency order rather than by recursion, and kept
+// until the circuit is reset or a wire is overridden.
+#[derive(Clone, Default)]
+pub struct Circuit<'a, W = Signal> {
+    components: HashMap<&'a str, Component<'a, W>>,
+    overrides: HashMap<&'a str, W>,
+    signals: HashMap<&'a str, W>
 }
 
 impl<'a> Circuit<'a> {
-    fn new() -> Self {
-        Self { components: HashMap::new() }
-    }
-
-    fn add_component(&mut self, input: &'a str) -> &mut Self {
-        let mut parts = input.split(" ").collect::<Vec<&str>>();
-        let name = parts.pop().unwrap();
-        parts.pop(); // ->
-        let right = parts.pop().unwrap();
-        match parts.len() {
-            0 => {
-                self.components.insert(name, Component::Source { source: right });
-            },
-            1 => {
-                self.components.insert(name, Component::ComplimentGate { source: right });
-            },
-            2 => {
-                let operator = parts.pop().unwrap();
-                let left = parts.pop().unwrap();
-                match operator {
-                    "AND" => { self.components.insert(name, Component::AndGate { left, right }); },
-                    "OR" => { self.components.insert(name, Component::OrGate { left, right }); },
-                    "LSHIFT" => {
-                        self.components.insert(
-                            name,
-                            Component::LeftShiftGate { source: left, shift: right.parse::<Signal>().unwrap() }
-                        );
-                    },
-                    "RSHIFT" => {
-                        self.components.insert(
-                            name,
-                            Component::RightShiftGate { source: left, shift: right.parse::<Signal>().unwrap() }
-                        );
-                    },
-                    x => panic!("Unknown operator: {x}")
+    pub fn new() -> Self {
+        Self::new_generic()
+    }
+
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        Self::parse_generic(input)
+    }
+}
+
+// Circuits of other widths take theirs from the type, as in
+// Circuit::<u32>::parse_generic.
+impl<'a, W: Word> Circuit<'a, W> {
+    pub fn new_generic() -> Self {
+        Self::default()
+    }
+
+    pub fn parse_generic(input: &'a str) -> Result<Self, ParseError> {
+        let mut circuit = Self::new_generic();
+        for (index, line) in input.lines().enumerate() {
+            circuit.add_component(line).map_err(|error| error.with_line(index + 1))?;
+        }
+
+        Ok(circuit)
+    }
+
+    pub fn add_component(&mut self, input: &'a str) -> Result<&mut Self, ParseError> {
+        let (name, component) = parse_component(input)?;
+        self.components.insert(name, component);
+        self.reset();
+
+        Ok(self)
+    }
+
+    // Forgets every signal worked out so far.
+    pub fn reset(&mut self) {
+        self.signals.clear();
+    }
+
+    // Feeds the wire a fixed signal in place of its component, until the
+    // override is removed.
+    pub fn override_wire(&mut self, wire: &'a str, signal: W) {
+        self.overrides.insert(wire, signal);
+        self.reset();
+    }
+
+    pub fn remove_override(&mut self, wire: &str) {
+        if self.overrides.remove(wire).is_some() {
+            self.reset();
+        }
+    }
+
+    fn inputs(&self, wire: &'a str) -> Result<Vec<&'a str>, CircuitError> {
+        if self.overrides.contains_key(wire) {
+            return Ok(vec![]);
+        }
+
+        self.components.get(wire)
+            .map(Component::inputs)
+            .ok_or_else(|| CircuitError::UnknownWire(wire.to_string()))
+    }
+
+    // The wires that still need a signal to work out those given, each one after
+    // all of its inputs.
+    fn evaluation_order(&self, wires: impl IntoIterator<Item = &'a str>) -> Result<Vec<&'a str>, CircuitError> {
+        let mut order = vec![];
+        let mut visits : HashMap<&'a str, Visit> = HashMap::new();
+
+        for root in wires {
+            let mut stack = vec![(root, false)];
+            let mut path = vec![];
+
+            while let Some((wire, inputs_done)) = stack.pop() {
+                if inputs_done {
+                    visits.insert(wire, Visit::Finished);
+                    path.pop();
+                    order.push(wire);
+                    continue;
+                }
+                if self.signals.contains_key(wire) || visits.contains_key(wire) {
+                    continue;
                 }
+
+                visits.insert(wire, Visit::Started);
+                path.push(wire);
+                stack.push((wire, true));
+                for input in self.inputs(wire)? {
+                    match visits.get(input) {
+                        Some(Visit::Started) => {
+                            let start = path.iter().position(|&on_path| on_path == input).unwrap();
+                            return Err(Self::cycle(&path[start..]));
+                        },
+                        Some(Visit::Finished) => {},
+                        None => stack.push((input, false)),
+                    }
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    // Starts the loop from its first wire alphabetically, so the same loop is
+    // always reported the same way.
+    fn cycle(wires: &[&str]) -> CircuitError {
+        let first = (0..wires.len()).min_by_key(|&index| wires[index]).unwrap_or(0);
+        let mut cycle : Vec<String> = wires[first..].iter().chain(&wires[..first]).map(|wire| wire.to_string()).collect();
+        cycle.push(cycle[0].clone());
+
+        CircuitError::Cycle(cycle)
+    }
+
+    // Numbers were checked when the component was added, so they always fit.
+    fn operand(&self, operand: &str) -> W {
+        operand.parse().unwrap_or_else(|_| self.signals[operand])
+    }
+
+    // Only called once all of the wire's inputs have signals.
+    fn compute(&self, wire: &str) -> Result<W, CircuitError> {
+        if let Some(&signal) = self.overrides.get(wire) {
+            return Ok(signal);
+        }
+
+        let signal = match self.components[wire] {
+            Component::Value { signal } => { signal },
+            Component::Source { source } => { self.operand(source) },
+            Component::Gate { operator, left, right } => {
+                self.operand(left).combine(operator, self.operand(right))
+                    .ok_or_else(|| CircuitError::DivisionByZero(wire.to_string()))?
             },
-            _ => panic!("Invalid input: {input}")
+            Component::ComplimentGate { source } => { self.operand(source).complement() },
+            Component::LeftShiftGate { source, shift } => { self.operand(source).shifted_left(shift) },
+            Component::RightShiftGate { source, shift } => { self.operand(source).shifted_right(shift) },
+        };
+
+        Ok(signal)
+    }
+
+    fn evaluate(&mut self, wires: impl IntoIterator<Item = &'a str>) -> Result<(), CircuitError> {
+        for wire in self.evaluation_order(wires)? {
+            let signal = self.compute(wire)?;
+            self.signals.insert(wire, signal);
         }
 
-        self
+        Ok(())
     }
 
-    fn signal_on(&mut self, wire: &'a str) -> Signal {
-        if let Ok(signal) = wire.parse::<Signal>() {
-            return signal;
+    pub fn signal_on(&mut self, wire: &'a str) -> Result<W, CircuitError> {
+        if let Ok(signal) = wire.parse::<W>() {
+            return Ok(signal);
+        }
+
+        self.evaluate([wire])?;
+        Ok(self.signals[wire])
+    }
+
+    // The wires feeding each wire, as they are now, so an overridden wire has none.
+    fn wiring(&self) -> BTreeMap<&'a str, Vec<&'a str>> {
+        let mut wiring : BTreeMap<&'a str, Vec<&'a str>> = self.components.iter()
+            .map(|(&wire, component)| (wire, component.inputs()))
+            .collect();
+        for &wire in self.overrides.keys() {
+            wiring.insert(wire, vec![]);
+        }
+
+        wiring
+    }
+
+    // Every wire reachable from the given one by following the edges.
+    fn reachable(edges: &BTreeMap<&'a str, Vec<&'a str>>, wire: &str) -> BTreeSet<&'a str> {
+        let mut reached = BTreeSet::new();
+        let mut stack : Vec<&'a str> = edges.get(wire).cloned().unwrap_or_default();
+
+        while let Some(next) = stack.pop() {
+            if reached.insert(next) {
+                stack.extend(edges.get(next).into_iter().flatten());
+            }
         }
 
-        let component = self.components.get(wire).unwrap_or_else(|| panic!("Unknown wire: {wire}")).clone();
-        let new_value : Signal =
-            match component {
-                Component::Value { signal } => { return signal },
-                Component::Source { source } => { self.signal_on(source) },
-                Component::AndGate { left, right } => {
-                    self.signal_on(left) & self.signal_on(right)
-                },
-                Component::OrGate { left, right } => {
-                    self.signal_on(left) | self.signal_on(right)
-                },
-                Component::ComplimentGate { source } => {
-                    !self.signal_on(source)
-                },
-                Component::LeftShiftGate { source, shift } => {
-                    self.signal_on(source) << shift
-                },
-                Component::RightShiftGate { source, shift } => {
-                    self.signal_on(source) >> shift
-                },
+        reached
+    }
+
+    // Every wire the given one depends on, however indirectly.
+    pub fn fan_in(&self, wire: &str) -> BTreeSet<&'a str> {
+        Self::reachable(&self.wiring(), wire)
+    }
+
+    // Every wire that depends on the given one, however indirectly.
+    pub fn fan_out(&self, wire: &str) -> BTreeSet<&'a str> {
+        let mut fed : BTreeMap<&'a str, Vec<&'a str>> = BTreeMap::new();
+        for (wire, inputs) in self.wiring() {
+            for input in inputs {
+                fed.entry(input).or_default().push(wire);
+            }
+        }
+
+        Self::reachable(&fed, wire)
+    }
+
+    // The wires whose signals would differ if the given wire were overridden,
+    // with their signals before and after. Only the wire's fan out can change,
+    // but not every wire in it need do so.
+    pub fn override_changes(&mut self, wire: &'a str, signal: W) -> Result<BTreeMap<&'a str, (W, W)>, CircuitError> {
+        let before = self.signals()?;
+        let mut overridden = self.clone();
+        overridden.override_wire(wire, signal);
+        let after = overridden.signals()?;
+
+        Ok(after.into_iter()
+            .filter_map(|(wire, signal)| {
+                let previous = before.get(wire).copied()?;
+                (previous != signal).then_some((wire, (previous, signal)))
+            })
+            .collect())
+    }
+
+    // The wiring as a Graphviz digraph, with an edge from each wire to the ones
+    // it feeds. Overridden wires are drawn as boxes.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph circuit {\n");
+
+        for (wire, inputs) in self.wiring() {
+            let label = match self.overrides.get(wire) {
+                Some(signal) => format!("{wire} = {signal} (overridden)"),
+                None => format!("{wire} = {}", self.components[wire])
             };
+            let shape = if self.overrides.contains_key(wire) { ", shape=box" } else { "" };
+            dot.push_str(&format!("    \"{wire}\" [label=\"{label}\"{shape}];\n"));
 
-        self.components.insert(wire, Component::Value { signal: new_value });
+            for input in inputs {
+                dot.push_str(&format!("    \"{input}\" -> \"{wire}\";\n"));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
 
-        new_value
+    // Every wire's signal, by name.
+    pub fn signals(&mut self) -> Result<BTreeMap<&'a str, W>, CircuitError> {
+        let wires : Vec<&'a str> = self.components.keys().chain(self.overrides.keys()).copied().collect();
+        self.evaluate(wires)?;
+
+        Ok(self.signals.iter().map(|(&wire, &signal)| (wire, signal)).collect())
     }
 }
 
+pub fn part_one(input: &str) -> Result<Signal, ParseError> {
+    Ok(Circuit::parse(input)?.signal_on("a")?)
+}
+
+pub fn part_two(input: &str) -> Result<Signal, ParseError> {
+    let mut circuit = Circuit::parse(input)?;
+    let signal = circuit.signal_on("a")?;
+    circuit.override_wire("b", signal);
+
+    Ok(circuit.signal_on("a")?)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn source() {
         assert_eq!(
             Circuit::new()
-                .add_component("123 -> x")
-                .signal_on("x"),
+                .add_component("123 -> x").unwrap()
+                .signal_on("x").unwrap(),
             123
         );
     }
@@ -113,9 +510,9 @@ mod tests {
     fn not() {
         assert_eq!(
             Circuit::new()
-                .add_component("1 -> x")
-                .add_component("NOT x -> y")
-                .signal_on("y"),
+                .add_component("1 -> x").unwrap()
+                .add_component("NOT x -> y").unwrap()
+                .signal_on("y").unwrap(),
             65534
         );
     }
@@ -124,10 +521,10 @@ mod tests {
     fn double_not() {
         assert_eq!(
             Circuit::new()
-                .add_component("123 -> x")
-                .add_component("NOT x -> y")
-                .add_component("NOT y -> z")
-                .signal_on("z"),
+                .add_component("123 -> x").unwrap()
+                .add_component("NOT x -> y").unwrap()
+                .add_component("NOT y -> z").unwrap()
+                .signal_on("z").unwrap(),
             123
         );
     }
@@ -136,10 +533,10 @@ mod tests {
     fn or() {
         assert_eq!(
             Circuit::new()
-                .add_component("65280 -> x")
-                .add_component("255 -> y")
-                .add_component("x OR y -> z")
-                .signal_on("z"),
+                .add_component("65280 -> x").unwrap()
+                .add_component("255 -> y").unwrap()
+                .add_component("x OR y -> z").unwrap()
+                .signal_on("z").unwrap(),
             65535
         );
     }
@@ -148,10 +545,10 @@ mod tests {
     fn and() {
         assert_eq!(
             Circuit::new()
-                .add_component("65520 -> x")
-                .add_component("4095 -> y")
-                .add_component("x AND y -> z")
-                .signal_on("z"),
+                .add_component("65520 -> x").unwrap()
+                .add_component("4095 -> y").unwrap()
+                .add_component("x AND y -> z").unwrap()
+                .signal_on("z").unwrap(),
             4080
         );
     }
@@ -160,9 +557,9 @@ mod tests {
     fn left_shift() {
         assert_eq!(
             Circuit::new()
-                .add_component("60 -> x")
-                .add_component("x LSHIFT 2 -> z")
-                .signal_on("z"),
+                .add_component("60 -> x").unwrap()
+                .add_component("x LSHIFT 2 -> z").unwrap()
+                .signal_on("z").unwrap(),
             240
         );
     }
@@ -171,9 +568,9 @@ mod tests {
     fn left_shift_full() {
         assert_eq!(
             Circuit::new()
-                .add_component("65535 -> x")
-                .add_component("x LSHIFT 2 -> z")
-                .signal_on("z"),
+                .add_component("65535 -> x").unwrap()
+                .add_component("x LSHIFT 2 -> z").unwrap()
+                .signal_on("z").unwrap(),
             65532
         );
     }
@@ -182,9 +579,9 @@ mod tests {
     fn right_shift() {
         assert_eq!(
             Circuit::new()
-                .add_component("240 -> x")
-                .add_component("x RSHIFT 2 -> z")
-                .signal_on("z"),
+                .add_component("240 -> x").unwrap()
+                .add_component("x RSHIFT 2 -> z").unwrap()
+                .signal_on("z").unwrap(),
             60
         );
     }
@@ -193,57 +590,213 @@ mod tests {
     fn right_shift_full() {
         assert_eq!(
             Circuit::new()
-                .add_component("65535 -> x")
-                .add_component("x RSHIFT 2 -> z")
-                .signal_on("z"),
+                .add_component("65535 -> x").unwrap()
+                .add_component("x RSHIFT 2 -> z").unwrap()
+                .signal_on("z").unwrap(),
             16383
         );
     }
 
     #[test]
     fn part_one_example() {
-        let input = "123 -> x
-456 -> y
-x AND y -> d
-x OR y -> e
-x LSHIFT 2 -> f
-y RSHIFT 2 -> g
-NOT x -> h
-NOT y -> i";
-
-        let mut circuit = Circuit::new();
-        for line in input.lines() {
-            circuit.add_component(line);
-        }
+        let example = fixtures::example("example").unwrap();
+        let mut circuit = Circuit::parse(&example.input).unwrap();
+
+        assert_eq!(circuit.signal_on("d").unwrap(), 72);
+        assert_eq!(circuit.signal_on("e").unwrap(), 507);
+        assert_eq!(circuit.signal_on("f").unwrap(), 492);
+        assert_eq!(circuit.signal_on("g").unwrap(), 114);
+        assert_eq!(circuit.signal_on("h").unwrap(), 65412);
+        assert_eq!(circuit.signal_on("i").unwrap(), 65079);
+        assert_eq!(circuit.signal_on("x").unwrap(), 123);
+        assert_eq!(circuit.signal_on("y").unwrap(), 456);
+    }
+
+    #[test]
+    fn all_signals() {
+        let example = fixtures::example("example").unwrap();
+        let mut circuit = Circuit::parse(&example.input).unwrap();
+
+        assert_eq!(
+            circuit.signals().unwrap().into_iter().collect::<Vec<_>>(),
+            [("d", 72), ("e", 507), ("f", 492), ("g", 114), ("h", 65412), ("i", 65079), ("x", 123), ("y", 456)]
+        );
+    }
+
+    #[test]
+    fn overrides_and_resets() {
+        let example = fixtures::example("example").unwrap();
+        let mut circuit = Circuit::parse(&example.input).unwrap();
+        assert_eq!(circuit.signal_on("d").unwrap(), 72);
+
+        circuit.override_wire("x", 0xff);
+        assert_eq!(circuit.signal_on("d").unwrap(), 456 & 0xff);
+        assert_eq!(circuit.signal_on("h").unwrap(), 0xff00);
+
+        circuit.remove_override("x");
+        assert_eq!(circuit.signal_on("d").unwrap(), 72);
+
+        circuit.reset();
+        assert!(circuit.signals.is_empty());
+        assert_eq!(circuit.signal_on("d").unwrap(), 72);
+    }
 
-        assert_eq!(circuit.signal_on("d"), 72);
-        assert_eq!(circuit.signal_on("e"), 507);
-        assert_eq!(circuit.signal_on("f"), 492);
-        assert_eq!(circuit.signal_on("g"), 114);
-        assert_eq!(circuit.signal_on("h"), 65412);
-        assert_eq!(circuit.signal_on("i"), 65079);
-        assert_eq!(circuit.signal_on("x"), 123);
-        assert_eq!(circuit.signal_on("y"), 456);
+    #[test]
+    fn deep_chains() {
+        let input : String = (0..100_000).map(|index| format!("w{index} -> w{}\n", index + 1)).collect::<String>() + "7 -> w0";
+        let mut circuit = Circuit::parse(&input).unwrap();
+
+        assert_eq!(circuit.signal_on("w100000").unwrap(), 7);
+    }
+
+    #[test]
+    fn cycles() {
+        let mut circuit = Circuit::parse("1 -> a\na AND d -> b\nb -> c\nNOT c -> d\nd OR a -> e").unwrap();
+
+        assert_eq!(circuit.signal_on("a").unwrap(), 1);
+        let error = circuit.signal_on("e").err().unwrap();
+        assert_eq!(error, CircuitError::Cycle(["b", "d", "c", "b"].map(String::from).to_vec()));
+        assert_eq!(error.to_string(), "feedback loop through wires b <- d <- c <- b");
+        assert!(circuit.signals().is_err());
+
+        circuit.override_wire("c", 0);
+        assert_eq!(circuit.signal_on("e").unwrap(), 65535);
+
+        let mut circuit = Circuit::parse("x -> x").unwrap();
+        assert_eq!(circuit.signal_on("x").err().unwrap().to_string(), "feedback loop through wires x <- x");
+    }
+
+    #[test]
+    fn unknown_wires() {
+        let mut circuit = Circuit::parse("x AND y -> z\n1 -> x").unwrap();
+
+        assert_eq!(circuit.signal_on("z").err().unwrap().to_string(), "nothing is connected to wire y");
+        assert_eq!(super::part_one("x AND y -> a\n1 -> x").err().unwrap().to_string(), "nothing is connected to wire y");
+    }
+
+    #[test]
+    fn fan_in_and_out() {
+        let example = fixtures::example("example").unwrap();
+        let mut circuit = Circuit::parse(&example.input).unwrap();
+        circuit.add_component("d OR e -> j").unwrap();
+        circuit.add_component("j RSHIFT 1 -> k").unwrap();
+
+        assert_eq!(circuit.fan_in("k"), BTreeSet::from(["d", "e", "j", "x", "y"]));
+        assert_eq!(circuit.fan_in("x"), BTreeSet::new());
+        assert_eq!(circuit.fan_out("x"), BTreeSet::from(["d", "e", "f", "h", "j", "k"]));
+        assert_eq!(circuit.fan_out("k"), BTreeSet::new());
+
+        circuit.override_wire("j", 1);
+        assert_eq!(circuit.fan_in("k"), BTreeSet::from(["j"]));
+        assert_eq!(circuit.fan_out("x"), BTreeSet::from(["d", "e", "f", "h"]));
+    }
+
+    #[test]
+    fn changes_from_an_override() {
+        let example = fixtures::example("example").unwrap();
+        let mut circuit = Circuit::parse(&example.input).unwrap();
+
+        // e is x OR y, and 123 | 456 == 251 | 456, so it stays the same
+        assert_eq!(
+            circuit.override_changes("x", 251).unwrap().into_iter().collect::<Vec<_>>(),
+            [("d", (72, 200)), ("f", (492, 1004)), ("h", (65412, 65284)), ("x", (123, 251))]
+        );
+        assert!(circuit.fan_out("x").contains("e"));
+        assert_eq!(circuit.override_changes("x", 123).unwrap(), BTreeMap::new());
+        assert_eq!(circuit.signal_on("x").unwrap(), 123);
+    }
+
+    #[test]
+    fn dot() {
+        let mut circuit = Circuit::parse("123 -> x\nx AND y -> d\nNOT x -> h").unwrap();
+        circuit.override_wire("y", 5);
+
+        assert_eq!(
+            circuit.to_dot(),
+            "digraph circuit {\n\
+             \x20   \"d\" [label=\"d = x AND y\"];\n\
+             \x20   \"x\" -> \"d\";\n\
+             \x20   \"y\" -> \"d\";\n\
+             \x20   \"h\" [label=\"h = NOT x\"];\n\
+             \x20   \"x\" -> \"h\";\n\
+             \x20   \"x\" [label=\"x = 123\"];\n\
+             \x20   \"y\" [label=\"y = 5 (overridden)\", shape=box];\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn invalid_components() {
+        let error = Circuit::parse("123 -> x\nx XAND y -> z").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 3: unknown operator XAND");
+
+        let error = Circuit::parse("x LSHIFT two -> z").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 10: invalid number 'two'");
+
+        assert!(Circuit::parse("x AND y").is_err());
+        assert!(Circuit::parse("NOT x y -> z").is_err());
+    }
+
+    #[test]
+    fn more_gates() {
+        let input = "12 -> x\n10 -> y\nx XOR y -> xor\nx NAND y -> nand\nx NOR y -> nor\n\
+            x ADD y -> add\ny SUB x -> sub\nx MUL y -> mul\nx DIV 5 -> div\nx MOD 5 -> mod";
+        let signals = Circuit::parse(input).unwrap().signals().unwrap();
+        assert_eq!(signals["xor"], 6);
+        assert_eq!(signals["nand"], 65527);
+        assert_eq!(signals["nor"], 65521);
+        assert_eq!(signals["add"], 22);
+        assert_eq!(signals["sub"], 65534);
+        assert_eq!(signals["mul"], 120);
+        assert_eq!(signals["div"], 2);
+        assert_eq!(signals["mod"], 2);
+
+        let mut circuit = Circuit::parse("x DIV y -> z\n0 -> y\n1 -> x").unwrap();
+        assert_eq!(circuit.signal_on("z"), Err(CircuitError::DivisionByZero(String::from("z"))));
+        assert_eq!(CircuitError::DivisionByZero(String::from("z")).to_string(), "wire z divides by zero");
+    }
+
+    #[test]
+    fn widths() {
+        let input = "200 -> x\nNOT x -> n\nx ADD x -> d\nx LSHIFT 7 -> l";
+        let signals = Circuit::<u8>::parse_generic(input).unwrap().signals().unwrap();
+        assert_eq!((signals["n"], signals["d"], signals["l"]), (55, 144, 0));
+
+        let signals = Circuit::<u32>::parse_generic(input).unwrap().signals().unwrap();
+        assert_eq!((signals["n"], signals["d"], signals["l"]), (4294967095, 400, 25600));
+
+        let mut circuit = Circuit::<u64>::parse_generic("x LSHIFT 40 -> y\n3 -> x").unwrap();
+        assert_eq!(circuit.signal_on("y").unwrap(), 3 << 40);
+
+        let error = Circuit::<u8>::parse_generic("300 -> x").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 1: invalid number '300'");
+
+        let error = Circuit::<u8>::parse_generic("x AND 256 -> y").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 7: invalid number '256'");
+
+        let error = Circuit::<u8>::parse_generic("x RSHIFT 8 -> y").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 10: can't shift 8 bit signals by 8");
+        assert!(Circuit::<u16>::parse_generic("x RSHIFT 8 -> y").is_ok());
     }
 
     #[test]
     fn part_one() {
-        let input = fs::read_to_string("input.txt").unwrap();
-        let mut circuit = Circuit::new();
-        for line in input.lines() {
-            circuit.add_component(line);
-        }
-        assert_eq!(circuit.signal_on("a"), 16076);
+        let input = fixtures::input(2015, 7).unwrap();
+        let mut circuit = Circuit::parse(&input).unwrap();
+        assert_eq!(circuit.signal_on("a").unwrap(), 16076);
     }
 
     #[test]
     fn part_two() {
-        let input = fs::read_to_string("input.txt").unwrap();
-        let mut circuit = Circuit::new();
-        for line in input.lines() {
-            circuit.add_component(line);
-        }
-        circuit.add_component("16076 -> b");
-        assert_eq!(circuit.signal_on("a"), 2797);
+        let input = fixtures::input(2015, 7).unwrap();
+        let mut circuit = Circuit::parse(&input).unwrap();
+        circuit.add_component("16076 -> b").unwrap();
+        assert_eq!(circuit.signal_on("a").unwrap(), 2797);
+
+        let mut circuit = Circuit::parse(&input).unwrap();
+        circuit.override_wire("b", 16076);
+        assert_eq!(circuit.signal_on("a").unwrap(), 2797);
+        circuit.remove_override("b");
+        assert_eq!(circuit.signal_on("a").unwrap(), 16076);
     }
 }
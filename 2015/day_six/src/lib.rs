@@ -1,55 +1,158 @@
-use crate::Action::{Toggle, TurnOff, TurnOn};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use parsing::ParseError;
+use render::{Color, Frame, Palette, Recorder, Render, Step};
+use crate::Action::{Dim, Invert, Set, Toggle, TurnOff, TurnOn};
+
+const SIZE : usize = 1000;
 
 type BitTransformer = fn(u8, usize) -> u8;
-type ByteTransformer = fn(u8) -> u8;
+// How an action changes a light, given how bright it was.
+type Transformer = fn(&Action, u8) -> u8;
 
+#[derive(Copy, Clone, Debug, PartialEq)]
 enum Action {
     TurnOn,
     TurnOff,
-    Toggle
+    Toggle,
+    Set(u8),
+    Dim(u8),
+    Invert
 }
 
-impl Action {
-    fn new(phrase : &str) -> Self {
+impl FromStr for Action {
+    type Err = ParseError;
+
+    fn from_str(phrase : &str) -> Result<Self, Self::Err> {
         match phrase {
-            "turn on" => TurnOn,
-            "turn off" => TurnOff,
-            "toggle" => Toggle,
-            _ => panic!("Invalid action: {phrase}")
+            "turn on" => Ok(TurnOn),
+            "turn off" => Ok(TurnOff),
+            "toggle" => Ok(Toggle),
+            "invert" => Ok(Invert),
+            _ => Err(ParseError::at_column(1, format!("invalid action '{phrase}'")))
+        }
+    }
+}
+
+impl Action {
+    // Setting and dimming take a number after the second corner, as in
+    // "set 0,0 through 9,9 to 5" or "dim 0,0 through 9,9 by 2".
+    fn parse(input: &str, phrase: &str, argument: &str) -> Result<Self, ParseError> {
+        match (phrase, argument.split_once(' ')) {
+            ("set", Some(("to", level))) => Ok(Set(parsing::number(input, level)?)),
+            ("dim", Some(("by", amount))) => Ok(Dim(parsing::number(input, amount)?)),
+            ("set", _) => Err(ParseError::at_token(input, phrase, "expected 'to <brightness>' after the corners")),
+            ("dim", _) => Err(ParseError::at_token(input, phrase, "expected 'by <amount>' after the corners")),
+            (_, _) if !argument.is_empty() => Err(ParseError::at_token(input, argument, format!("unexpected '{argument}'"))),
+            _ => phrase.parse().map_err(|error: ParseError| error.within(input, phrase))
         }
     }
 
-    fn to_bit_transformer(&self) -> BitTransformer {
+    // A light that is only on or off is on when its brightness would be. An
+    // inverted light is never dark, so inverting turns a switch on.
+    fn to_bit_transformer(self) -> BitTransformer {
         match self {
-            TurnOn => |byte, bit_index| byte | util::bitmask(bit_index),
-            TurnOff => |byte, bit_index| byte & util::inverse_bitmask(bit_index),
-            Toggle => |byte, bit_index| byte ^ util::bitmask(bit_index)
+            TurnOn | Set(1..) | Invert => |byte, bit_index| byte | util::bitmask(bit_index),
+            TurnOff | Set(0) | Dim(1..) => |byte, bit_index| byte & util::inverse_bitmask(bit_index),
+            Toggle => |byte, bit_index| byte ^ util::bitmask(bit_index),
+            Dim(0) => |byte, _| byte
         }
     }
 
-    fn to_byte_transformer(&self) -> ByteTransformer {
+    // The same as the bit transformer, for lights stored a byte each.
+    fn switch(&self, level: u8) -> u8 {
         match self {
-            TurnOn => |byte| byte + 1,
-            TurnOff => |byte| match byte { 0 => 0, _ => byte - 1 },
-            Toggle => |byte| byte + 2
+            TurnOn | Set(1..) | Invert => 1,
+            TurnOff | Set(0) | Dim(1..) => 0,
+            Toggle => level ^ 1,
+            Dim(0) => level
+        }
+    }
+
+    // Inverting mirrors the brightness within what a light can show.
+    fn brightness(&self, level: u8) -> u8 {
+        match *self {
+            TurnOn => level.saturating_add(1),
+            TurnOff => level.saturating_sub(1),
+            Toggle => level.saturating_add(2),
+            Set(brightness) => brightness,
+            Dim(amount) => level.saturating_sub(amount),
+            Invert => u8::MAX - level
         }
     }
 }
 
-struct Point {
-    x: usize,
-    y: usize
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize
 }
 
-impl Point {
-    fn new(comma_pair : &str) -> Self {
-        let parts : Vec<usize> =
-            comma_pair.split(",").map(|part| part.parse::<usize>().unwrap()).collect();
+impl FromStr for Point {
+    type Err = ParseError;
+
+    fn from_str(comma_pair : &str) -> Result<Self, Self::Err> {
+        let (x, y) = parsing::split_once(comma_pair, ",")?;
+
+        Ok(Self {
+            x: parsing::number(comma_pair, x)?,
+            y: parsing::number(comma_pair, y)?
+        })
+    }
+}
 
-        Self {
-            x: parts[0],
-            y: parts[1]
+// Always at least one light wide and high.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Dimensions {
+    width: usize,
+    height: usize
+}
+
+impl Default for Dimensions {
+    fn default() -> Self {
+        Self { width: SIZE, height: SIZE }
+    }
+}
+
+impl fmt::Display for Dimensions {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}x{}", self.width, self.height)
+    }
+}
+
+impl Dimensions {
+    pub fn new(width: usize, height: usize) -> Result<Self, ParseError> {
+        if width == 0 || height == 0 {
+            return Err(ParseError::new(format!("a {width}x{height} grid has no lights")));
         }
+
+        Ok(Self { width, height })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    fn whole(&self) -> Rectangle {
+        Rectangle { from: Point { x: 0, y: 0 }, to: Point { x: self.width - 1, y: self.height - 1 } }
+    }
+
+    fn point(&self, input: &str, comma_pair: &str) -> Result<Point, ParseError> {
+        let point : Point = comma_pair.parse().map_err(|error: ParseError| error.within(input, comma_pair))?;
+        if !self.contains(point) {
+            return Err(ParseError::at_token(input, comma_pair, format!("{comma_pair} is outside of the {self} grid")));
+        }
+
+        Ok(point)
     }
 }
 
@@ -59,15 +162,97 @@ struct Instruction {
     to: Point
 }
 
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(input : &str) -> Result<Self, Self::Err> {
+        Self::parse(input, Dimensions::default())
+    }
+}
+
 impl Instruction {
-    fn new(input : &str) -> Self {
-        let mut parts = input.split(" ").collect::<Vec<&str>>();
-        let to = Point::new(parts.pop().unwrap());
-        parts.pop(); // through
-        let from = Point::new(parts.pop().unwrap());
-        let action = Action::new(parts.join(" ").as_str());
+    fn parse(input: &str, dimensions: Dimensions) -> Result<Self, ParseError> {
+        let (start, end) = parsing::split_once(input, " through ")?;
+        let (action, from) = start.rsplit_once(' ')
+            .ok_or_else(|| ParseError::new(format!("expected an action and a corner in '{input}'")))?;
+        let (to, argument) = end.split_once(' ').unwrap_or((end, ""));
+        let action = Action::parse(input, action, argument)?;
+        let area = Rectangle::new(input, dimensions.point(input, from)?, to, dimensions.point(input, to)?)?;
+
+        Ok(Self { action, from: area.from, to: area.to })
+    }
+
+    fn area(&self) -> Rectangle {
+        Rectangle { from: self.from, to: self.to }
+    }
+}
+
+fn parse_instructions(input: &str, dimensions: Dimensions) -> Result<Vec<Instruction>, ParseError> {
+    input.lines().enumerate()
+        .map(|(index, line)| Instruction::parse(line, dimensions).map_err(|error| error.with_line(index + 1)))
+        .collect()
+}
+
+// Corners are inclusive, as they are in the instructions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rectangle {
+    pub from: Point,
+    pub to: Point
+}
+
+// Written the way instructions give them, as in "0,0 through 9,9", with the
+// top left corner first.
+impl FromStr for Rectangle {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (from, to) = parsing::split_once(text, " through ")?;
+        let from = from.parse().map_err(|error: ParseError| error.within(text, from))?;
+
+        Self::new(text, from, to, to.parse().map_err(|error: ParseError| error.within(text, to))?)
+    }
+}
+
+impl Rectangle {
+    // `to_text` is where the second corner is in the input, for the error.
+    fn new(input: &str, from: Point, to_text: &str, to: Point) -> Result<Self, ParseError> {
+        if to.x < from.x || to.y < from.y {
+            return Err(ParseError::at_token(input, to_text, format!("{to_text} is above or left of the first corner")));
+        }
+
+        Ok(Self { from, to })
+    }
 
-        Self { action, to, from }
+    // Nothing, if the corners were built the wrong way round.
+    pub fn area(&self) -> u64 {
+        let width = (self.to.x + 1).saturating_sub(self.from.x) as u64;
+        let height = (self.to.y + 1).saturating_sub(self.from.y) as u64;
+
+        width * height
+    }
+
+    #[cfg(test)]
+    fn contains(&self, column: usize, row: usize) -> bool {
+        (self.from.x..=self.to.x).contains(&column) && (self.from.y..=self.to.y).contains(&row)
+    }
+
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let from = Point { x: self.from.x.max(other.from.x), y: self.from.y.max(other.from.y) };
+        let to = Point { x: self.to.x.min(other.to.x), y: self.to.y.min(other.to.y) };
+
+        (from.x <= to.x && from.y <= to.y).then_some(Rectangle { from, to })
+    }
+
+    // What is left after cutting out a rectangle inside this one: the full
+    // width above and below it, and whatever is either side of it.
+    fn without(&self, inner: &Rectangle) -> impl Iterator<Item = Rectangle> {
+        let (outer, inner) = (*self, *inner);
+        [
+            (outer.from.y < inner.from.y).then(|| Rectangle { from: outer.from, to: Point { x: outer.to.x, y: inner.from.y - 1 } }),
+            (inner.to.y < outer.to.y).then(|| Rectangle { from: Point { x: outer.from.x, y: inner.to.y + 1 }, to: outer.to }),
+            (outer.from.x < inner.from.x).then(|| Rectangle { from: Point { x: outer.from.x, y: inner.from.y }, to: Point { x: inner.from.x - 1, y: inner.to.y } }),
+            (inner.to.x < outer.to.x).then(|| Rectangle { from: Point { x: inner.to.x + 1, y: inner.from.y }, to: Point { x: outer.to.x, y: inner.to.y } }),
+        ].into_iter().flatten()
     }
 }
 
@@ -79,19 +264,100 @@ mod util {
     pub fn inverse_bitmask(index: usize) -> u8 {
         bitmask(index) ^ 0b1111_1111
     }
+}
+
+// Either kind of grid, so the dense arrays and the sparse one can be swapped.
+trait LightingArray: Step {
+    fn queue(&mut self, instructions: Vec<Instruction>);
+    fn execute(&mut self, instruction: Instruction);
+    // Whether a light is on, or how bright it is. Only used to check the
+    // backends against each other.
+    #[cfg(test)]
+    fn light(&self, column: usize, row: usize) -> u8;
+    // Lights on, or their total brightness, inside an area of the grid.
+    fn total_within(&self, area: Rectangle) -> u64;
+}
 
-    pub fn bits_on(byte : u8) -> u32 {
-        byte.count_ones()
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Backend {
+    // Every light is stored and every instruction touches each light it covers.
+    Dense,
+    // Lights are kept as rectangles that share a state, so an instruction only
+    // touches the rectangles it overlaps.
+    Sparse
+}
+
+impl Backend {
+    fn binary(self, dimensions: Dimensions) -> Box<dyn LightingArray> {
+        match self {
+            Backend::Dense => Box::new(BinaryLightingArray::new(dimensions)),
+            Backend::Sparse => Box::new(SparseLightingArray::binary(dimensions))
+        }
+    }
+
+    fn dimmable(self, dimensions: Dimensions) -> Box<dyn LightingArray> {
+        match self {
+            Backend::Dense => Box::new(DimmableLightingArray::new(dimensions)),
+            Backend::Sparse => Box::new(SparseLightingArray::dimmable(dimensions))
+        }
+    }
+}
+
+// Lights that follow instructions, held by either backend. Switches are only on
+// or off, while dimmable lights have a brightness.
+pub struct Grid {
+    array: Box<dyn LightingArray>,
+    dimensions: Dimensions
+}
+
+impl Grid {
+    pub fn switches(dimensions: Dimensions, backend: Backend) -> Self {
+        Self { array: backend.binary(dimensions), dimensions }
+    }
+
+    pub fn dimmable(dimensions: Dimensions, backend: Backend) -> Self {
+        Self { array: backend.dimmable(dimensions), dimensions }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    // Nothing changes unless every line is a valid instruction for this grid.
+    pub fn follow(&mut self, instructions: &str) -> Result<(), ParseError> {
+        self.array.queue(parse_instructions(instructions, self.dimensions)?);
+        while self.array.step() {}
+
+        Ok(())
+    }
+
+    pub fn total(&self) -> u64 {
+        self.array.total_within(self.dimensions.whole())
+    }
+
+    // Whatever part of the area is outside the grid counts for nothing.
+    pub fn total_within(&self, area: Rectangle) -> u64 {
+        area.intersection(&self.dimensions.whole()).map_or(0, |area| self.array.total_within(area))
     }
 }
 
 struct BinaryLightingArray {
-    lights: [[u8; 125]; 1000]
+    lights: Vec<Vec<u8>>,
+    dimensions: Dimensions,
+    pending: VecDeque<Instruction>
 }
 
 impl BinaryLightingArray {
-    fn new() -> Self {
-        Self { lights: [[0; 125]; 1000] }
+    fn new(dimensions: Dimensions) -> Self {
+        Self { lights: vec![vec![0; dimensions.width.div_ceil(8)]; dimensions.height], dimensions, pending: VecDeque::new() }
+    }
+
+    fn queue(&mut self, instructions: Vec<Instruction>) {
+        self.pending.extend(instructions);
+    }
+
+    fn is_on(&self, column: usize, row: usize) -> bool {
+        self.lights[row][column / 8] & util::bitmask(column % 8) != 0
     }
 
     fn map_bits(&mut self, from: Point, to: Point, transform: BitTransformer) {
@@ -109,93 +375,516 @@ impl BinaryLightingArray {
         self.map_bits(instruction.from, instruction.to, instruction.action.to_bit_transformer())
     }
 
-    fn count_lights_on(&self) -> u32 {
+    fn count_lights_on_within(&self, area: Rectangle) -> u64 {
         let mut count = 0;
 
-        for row in 0..1000 {
-            for byte_index in 0..125 {
-                count += util::bits_on(self.lights[row][byte_index]);
+        for row in area.from.y..=area.to.y {
+            for column in area.from.x..=area.to.x {
+                count += self.is_on(column, row) as u64;
             }
         }
 
         count
     }
+
+    fn count_lights_on(&self) -> u64 {
+        self.lights.iter().flatten().map(|byte| byte.count_ones() as u64).sum()
+    }
 }
 
 struct DimmableLightingArray {
-    lights: [[u8; 1000]; 1000]
+    lights: Vec<Vec<u8>>,
+    dimensions: Dimensions,
+    pending: VecDeque<Instruction>
 }
 
 impl DimmableLightingArray {
-    fn new() -> Self {
-        Self { lights: [[0; 1000]; 1000] }
+    fn new(dimensions: Dimensions) -> Self {
+        Self { lights: vec![vec![0; dimensions.width]; dimensions.height], dimensions, pending: VecDeque::new() }
+    }
+
+    fn queue(&mut self, instructions: Vec<Instruction>) {
+        self.pending.extend(instructions);
     }
 
-    fn map_lights(&mut self, from: Point, to: Point, transform: ByteTransformer) {
+    fn map_lights(&mut self, from: Point, to: Point, action: Action) {
         for row in from.y..=to.y {
             for column in from.x..=to.x {
-                self.lights[row][column] = transform(self.lights[row][column])
+                self.lights[row][column] = action.brightness(self.lights[row][column])
             }
         }
     }
 
     fn execute(&mut self, instruction : Instruction) {
-        self.map_lights(instruction.from, instruction.to, instruction.action.to_byte_transformer())
+        self.map_lights(instruction.from, instruction.to, instruction.action)
     }
 
-    fn total_brightness(&self) -> u32 {
-        let mut sum : u32 = 0;
+    fn total_brightness_within(&self, area: Rectangle) -> u64 {
+        self.lights[area.from.y..=area.to.y].iter()
+            .map(|row| row[area.from.x..=area.to.x].iter().map(|&light| light as u64).sum::<u64>())
+            .sum()
+    }
+
+    fn total_brightness(&self) -> u64 {
+        self.total_brightness_within(self.dimensions.whole())
+    }
+}
+
+// The rectangles never overlap and always cover the whole grid, starting out as
+// one rectangle of lights that are off.
+struct SparseLightingArray {
+    regions: Vec<(Rectangle, u8)>,
+    transformer: Transformer,
+    pending: VecDeque<Instruction>
+}
+
+impl SparseLightingArray {
+    fn new(dimensions: Dimensions, transformer: Transformer) -> Self {
+        Self { regions: vec![(dimensions.whole(), 0)], transformer, pending: VecDeque::new() }
+    }
+
+    fn binary(dimensions: Dimensions) -> Self {
+        Self::new(dimensions, Action::switch)
+    }
 
-        for row in 0..1000 {
-            for byte_index in 0..1000 {
-                sum += self.lights[row][byte_index] as u32;
+    fn dimmable(dimensions: Dimensions) -> Self {
+        Self::new(dimensions, Action::brightness)
+    }
+
+    // Rectangles the instruction only partly covers are split so the covered
+    // part can change on its own. When every covered part ends up the same, as
+    // it does after turning lights on or off, they are joined back together.
+    fn map_regions(&mut self, area: Rectangle, action: Action) {
+        let mut covered = vec![];
+        for index in 0..self.regions.len() {
+            let (region, value) = self.regions[index];
+            if let Some(overlap) = region.intersection(&area) {
+                self.regions[index] = (overlap, (self.transformer)(&action, value));
+                self.regions.extend(region.without(&overlap).map(|rest| (rest, value)));
+                covered.push(index);
             }
         }
 
-        sum
+        // Removing from the back keeps the other indices valid.
+        let covered : Vec<(Rectangle, u8)> = covered.into_iter().rev().map(|index| self.regions.swap_remove(index)).collect();
+        match covered.first() {
+            Some(&(_, value)) if covered.iter().all(|&(_, other)| other == value) => { self.regions.push((area, value)) },
+            _ => { self.regions.extend(covered) }
+        }
     }
 }
 
+impl Step for SparseLightingArray {
+    // Follows one instruction.
+    fn step(&mut self) -> bool {
+        match self.pending.pop_front() {
+            Some(instruction) => { self.execute(instruction); true },
+            None => false
+        }
+    }
+}
+
+impl LightingArray for SparseLightingArray {
+    fn queue(&mut self, instructions: Vec<Instruction>) {
+        self.pending.extend(instructions);
+    }
+
+    fn execute(&mut self, instruction: Instruction) {
+        self.map_regions(instruction.area(), instruction.action)
+    }
+
+    #[cfg(test)]
+    fn light(&self, column: usize, row: usize) -> u8 {
+        self.regions.iter().find(|(region, _)| region.contains(column, row)).map_or(0, |&(_, value)| value)
+    }
+
+    fn total_within(&self, area: Rectangle) -> u64 {
+        self.regions.iter()
+            .filter_map(|(region, value)| region.intersection(&area).map(|overlap| overlap.area() * *value as u64))
+            .sum()
+    }
+}
+
+impl LightingArray for BinaryLightingArray {
+    fn queue(&mut self, instructions: Vec<Instruction>) {
+        self.queue(instructions)
+    }
+
+    fn execute(&mut self, instruction: Instruction) {
+        self.execute(instruction)
+    }
+
+    #[cfg(test)]
+    fn light(&self, column: usize, row: usize) -> u8 {
+        self.is_on(column, row) as u8
+    }
+
+    fn total_within(&self, area: Rectangle) -> u64 {
+        match area == self.dimensions.whole() {
+            true => self.count_lights_on(),
+            false => self.count_lights_on_within(area)
+        }
+    }
+}
+
+impl LightingArray for DimmableLightingArray {
+    fn queue(&mut self, instructions: Vec<Instruction>) {
+        self.queue(instructions)
+    }
+
+    fn execute(&mut self, instruction: Instruction) {
+        self.execute(instruction)
+    }
+
+    #[cfg(test)]
+    fn light(&self, column: usize, row: usize) -> u8 {
+        self.lights[row][column]
+    }
+
+    fn total_within(&self, area: Rectangle) -> u64 {
+        self.total_brightness_within(area)
+    }
+}
+
+impl Step for BinaryLightingArray {
+    // Follows one instruction.
+    fn step(&mut self) -> bool {
+        match self.pending.pop_front() {
+            Some(instruction) => { self.execute(instruction); true },
+            None => false
+        }
+    }
+}
+
+impl Render for BinaryLightingArray {
+    fn render(&self) -> Frame {
+        Frame::from_fn(self.dimensions.width, self.dimensions.height, |column, row| if self.is_on(column, row) { '#' } else { '.' })
+            .with_caption(format!("{} light(s) on", self.count_lights_on()))
+    }
+
+    fn palette(&self) -> Palette {
+        Palette::new()
+            .with('#', Color::new(255, 220, 120))
+            .with('.', Color::new(20, 20, 40))
+    }
+}
+
+impl Step for DimmableLightingArray {
+    // Follows one instruction.
+    fn step(&mut self) -> bool {
+        match self.pending.pop_front() {
+            Some(instruction) => { self.execute(instruction); true },
+            None => false
+        }
+    }
+}
+
+// Brightness is shown as a base 36 digit, so anything brighter than 35 looks
+// the same as 35.
+const MAX_SHOWN_BRIGHTNESS : u32 = 35;
+
+impl Render for DimmableLightingArray {
+    fn render(&self) -> Frame {
+        Frame::from_fn(self.dimensions.width, self.dimensions.height, |column, row| {
+            char::from_digit((self.lights[row][column] as u32).min(MAX_SHOWN_BRIGHTNESS), 36).unwrap()
+        }).with_caption(format!("total brightness {}", self.total_brightness()))
+    }
+
+    // Off is black, and each step of brightness is a little lighter.
+    fn palette(&self) -> Palette {
+        (0..=MAX_SHOWN_BRIGHTNESS).fold(Palette::new(), |palette, brightness| {
+            let level = (brightness * 255 / MAX_SHOWN_BRIGHTNESS) as u8;
+            palette.with(char::from_digit(brightness, 36).unwrap(), Color::new(level, level, level))
+        })
+    }
+}
+
+pub fn animate_part_one(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let dimensions = Dimensions::default();
+    let mut array = BinaryLightingArray::new(dimensions);
+    array.queue(parse_instructions(input, dimensions)?);
+    recorder.run(&mut array);
+
+    Ok(())
+}
+
+pub fn animate_part_two(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let dimensions = Dimensions::default();
+    let mut array = DimmableLightingArray::new(dimensions);
+    array.queue(parse_instructions(input, dimensions)?);
+    recorder.run(&mut array);
+
+    Ok(())
+}
+
+pub fn lights_on(input: &str, backend: Backend) -> Result<u64, ParseError> {
+    let mut grid = Grid::switches(Dimensions::default(), backend);
+    grid.follow(input)?;
+
+    Ok(grid.total())
+}
+
+pub fn total_brightness(input: &str, backend: Backend) -> Result<u64, ParseError> {
+    let mut grid = Grid::dimmable(Dimensions::default(), backend);
+    grid.follow(input)?;
+
+    Ok(grid.total())
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    lights_on(input, Backend::Sparse)
+}
+
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    total_brightness(input, Backend::Sparse)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use parsing::parse_lines;
     use super::*;
 
     #[test]
     fn part_one_examples() {
-        let mut array = BinaryLightingArray::new();
-        array.execute(Instruction::new("turn on 0,0 through 999,999"));
+        let mut array = BinaryLightingArray::new(Dimensions::default());
+        array.execute("turn on 0,0 through 999,999".parse().unwrap());
         assert_eq!(array.count_lights_on(), 1_000_000);
-        array.execute(Instruction::new("toggle 0,0 through 999,0"));
+        array.execute("toggle 0,0 through 999,0".parse().unwrap());
         assert_eq!(array.count_lights_on(), 999_000);
-        array.execute(Instruction::new("turn off 499,499 through 500,500"));
+        array.execute("turn off 499,499 through 500,500".parse().unwrap());
         assert_eq!(array.count_lights_on(), 998_996);
     }
 
     #[test]
     fn part_one() {
-        let mut array = BinaryLightingArray::new();
-        for line in fs::read_to_string("input.txt").unwrap().lines().filter(|line| !line.is_empty()) {
-            array.execute(Instruction::new(line));
+        let mut array = BinaryLightingArray::new(Dimensions::default());
+        for instruction in parse_lines(&fixtures::input(2015, 6).unwrap()).unwrap() {
+            array.execute(instruction);
         }
         assert_eq!(array.count_lights_on(), 543903);
     }
 
+    #[test]
+    fn invalid_instructions() {
+        let error = parse_lines::<Instruction>("toggle 0,0 through 9,9\nturn up 0,0 through 9,9").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: invalid action 'turn up'");
+
+        let error = "turn on 0,0 through 9,x".parse::<Instruction>().err().unwrap();
+        assert_eq!(error.to_string(), "column 23: invalid number 'x'");
+
+        let error = "turn off 0,0 through 1000,0".parse::<Instruction>().err().unwrap();
+        assert_eq!(error.to_string(), "column 22: 1000,0 is outside of the 1000x1000 grid");
+
+        assert!("turn on 0,0 until 9,9".parse::<Instruction>().is_err());
+    }
+
     #[test]
     fn part_two_examples() {
-        let mut array = DimmableLightingArray::new();
-        array.execute(Instruction::new("turn on 0,0 through 0,0"));
+        let mut array = DimmableLightingArray::new(Dimensions::default());
+        array.execute("turn on 0,0 through 0,0".parse().unwrap());
         assert_eq!(array.total_brightness(), 1);
-        array.execute(Instruction::new("toggle 0,0 through 999,999"));
+        array.execute("toggle 0,0 through 999,999".parse().unwrap());
         assert_eq!(array.total_brightness(), 2_000_001);
     }
 
     #[test]
     fn part_two() {
-        let mut array = DimmableLightingArray::new();
-        for line in fs::read_to_string("input.txt").unwrap().lines().filter(|line| !line.is_empty()) {
-            array.execute(Instruction::new(line));
+        let mut array = DimmableLightingArray::new(Dimensions::default());
+        for instruction in parse_lines(&fixtures::input(2015, 6).unwrap()).unwrap() {
+            array.execute(instruction);
         }
         assert_eq!(array.total_brightness(), 14_687_245);
     }
+
+    #[test]
+    fn rectangles() {
+        let outer = Rectangle { from: Point { x: 0, y: 0 }, to: Point { x: 9, y: 9 } };
+        let inner = Rectangle { from: Point { x: 2, y: 3 }, to: Point { x: 4, y: 5 } };
+        let rest : Vec<Rectangle> = outer.without(&inner).collect();
+        assert_eq!(rest.len(), 4);
+        assert_eq!(rest.iter().map(Rectangle::area).sum::<u64>() + inner.area(), outer.area());
+
+        assert_eq!(outer.without(&outer).count(), 0);
+        assert_eq!(outer.intersection(&inner), Some(inner));
+        let beside = Rectangle { from: Point { x: 5, y: 0 }, to: Point { x: 7, y: 9 } };
+        assert_eq!(inner.intersection(&beside), None);
+    }
+
+    #[test]
+    fn sparse_examples() {
+        let mut array = SparseLightingArray::binary(Dimensions::default());
+        array.execute("turn on 0,0 through 999,999".parse().unwrap());
+        array.execute("toggle 0,0 through 999,0".parse().unwrap());
+        array.execute("turn off 499,499 through 500,500".parse().unwrap());
+        assert_eq!(array.total_within(Dimensions::default().whole()), 998_996);
+        assert_eq!((array.light(0, 0), array.light(499, 500), array.light(501, 500)), (0, 0, 1));
+
+        let mut array = SparseLightingArray::dimmable(Dimensions::default());
+        array.execute("turn on 0,0 through 0,0".parse().unwrap());
+        array.execute("toggle 0,0 through 999,999".parse().unwrap());
+        assert_eq!(array.total_within(Dimensions::default().whole()), 2_000_001);
+        assert_eq!((array.light(0, 0), array.light(999, 999)), (3, 2));
+    }
+
+    // Random instructions over a small grid, so they overlap a lot.
+    #[test]
+    fn sparse_matches_dense() {
+        let mut random = StdRng::seed_from_u64(2015);
+        let dimensions = Dimensions::new(60, 45).unwrap();
+        let whole = dimensions.whole();
+
+        for _ in 0..20 {
+            let mut arrays = [
+                Backend::Dense.binary(dimensions),
+                Backend::Sparse.binary(dimensions),
+                Backend::Dense.dimmable(dimensions),
+                Backend::Sparse.dimmable(dimensions)
+            ];
+            for _ in 0..50 {
+                let (x, y) = (random.gen_range(0..50), random.gen_range(0..35));
+                let (width, height) = (random.gen_range(0..10), random.gen_range(0..10));
+                let corners = format!("{x},{y} through {},{}", x + width, y + height);
+                let instruction = match random.gen_range(0..6) {
+                    0 => format!("turn on {corners}"),
+                    1 => format!("turn off {corners}"),
+                    2 => format!("toggle {corners}"),
+                    3 => format!("invert {corners}"),
+                    4 => format!("set {corners} to {}", random.gen_range(0..4)),
+                    _ => format!("dim {corners} by {}", random.gen_range(0..3))
+                };
+                for array in arrays.iter_mut() {
+                    array.execute(Instruction::parse(&instruction, dimensions).unwrap());
+                }
+            }
+
+            for row in 0..dimensions.height {
+                for column in 0..dimensions.width {
+                    assert_eq!(arrays[0].light(column, row), arrays[1].light(column, row), "{column},{row}");
+                    assert_eq!(arrays[2].light(column, row), arrays[3].light(column, row), "{column},{row}");
+                }
+            }
+            for area in [whole, "3,4 through 20,30".parse().unwrap(), "59,0 through 59,44".parse().unwrap()] {
+                assert_eq!(arrays[0].total_within(area), arrays[1].total_within(area));
+                assert_eq!(arrays[2].total_within(area), arrays[3].total_within(area));
+            }
+        }
+    }
+
+    #[test]
+    fn more_actions() {
+        let instructions = "turn on 0,0 through 3,3\nset 1,1 through 2,2 to 5\ndim 0,0 through 1,3 by 2\ninvert 3,0 through 3,3";
+        for backend in [Backend::Dense, Backend::Sparse] {
+            let mut grid = Grid::dimmable(Dimensions::new(4, 4).unwrap(), backend);
+            grid.follow(instructions).unwrap();
+            // Columns 0 to 3 end up as 0, 3, 5 and 254 in rows 1 and 2, and
+            // 0, 0, 1 and 254 in rows 0 and 3.
+            assert_eq!(grid.total(), 2 * (3 + 5 + 254) + 2 * (1 + 254), "{backend:?}");
+
+            let mut grid = Grid::switches(Dimensions::new(4, 4).unwrap(), backend);
+            grid.follow(instructions).unwrap();
+            // Dimming turns the first two columns off, and the last one stays
+            // on since inverting never leaves a light dark.
+            assert_eq!(grid.total(), 8, "{backend:?}");
+
+            let mut grid = Grid::switches(Dimensions::new(4, 4).unwrap(), backend);
+            grid.follow("invert 0,0 through 1,3\ntoggle 1,0 through 2,3").unwrap();
+            assert_eq!(grid.total(), 8, "{backend:?}");
+        }
+
+        let error = parse_instructions("set 0,0 through 1,1 to 256", Dimensions::default()).err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 24: invalid number '256'");
+
+        let error = parse_instructions("toggle 0,0 through 1,1\ndim 0,0 through 1,1", Dimensions::default()).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: expected 'by <amount>' after the corners");
+
+        let error = "set 0,0 through 1,1 by 2".parse::<Instruction>().err().unwrap();
+        assert_eq!(error.to_string(), "column 1: expected 'to <brightness>' after the corners");
+
+        let error = "toggle 0,0 through 1,1 twice".parse::<Instruction>().err().unwrap();
+        assert_eq!(error.to_string(), "column 24: unexpected 'twice'");
+    }
+
+    #[test]
+    fn dimensions() {
+        let dimensions = Dimensions::new(20, 5).unwrap();
+        for backend in [Backend::Dense, Backend::Sparse] {
+            let mut grid = Grid::switches(dimensions, backend);
+            grid.follow("turn on 0,0 through 19,4\ntoggle 5,1 through 9,3").unwrap();
+            assert_eq!(grid.total(), 100 - 15, "{backend:?}");
+
+            let error = grid.follow("turn off 0,0 through 9,9").err().unwrap();
+            assert_eq!(error.to_string(), "line 1, column 22: 9,9 is outside of the 20x5 grid");
+            assert_eq!(grid.total(), 85);
+        }
+
+        let mut grid = Grid::dimmable(Dimensions::new(1_000_000, 1_000_000).unwrap(), Backend::Sparse);
+        grid.follow("set 0,0 through 999999,999999 to 255\ndim 0,0 through 499999,999999 by 5").unwrap();
+        assert_eq!(grid.total(), 1_000_000 * (500_000 * 255 + 500_000 * 250));
+        assert_eq!(grid.dimensions().to_string(), "1000000x1000000");
+        assert_eq!((grid.dimensions().width(), grid.dimensions().height()), (1_000_000, 1_000_000));
+
+        assert_eq!(Dimensions::new(0, 5).err().unwrap().to_string(), "a 0x5 grid has no lights");
+        assert!(Dimensions::new(5, 0).is_err());
+    }
+
+    #[test]
+    fn queries() {
+        for backend in [Backend::Dense, Backend::Sparse] {
+            let mut grid = Grid::dimmable(Dimensions::new(10, 10).unwrap(), backend);
+            grid.follow("turn on 0,0 through 9,9\ntoggle 2,2 through 5,5\nset 9,9 through 9,9 to 7").unwrap();
+
+            assert_eq!(grid.total(), 100 + 2 * 16 + 6, "{backend:?}");
+            assert_eq!(grid.total_within("2,2 through 5,5".parse().unwrap()), 16 * 3);
+            assert_eq!(grid.total_within("0,0 through 2,2".parse().unwrap()), 9 + 2);
+            assert_eq!(grid.total_within("9,9 through 9,9".parse().unwrap()), 7);
+            // Only the part inside the grid counts.
+            assert_eq!(grid.total_within("8,8 through 20,20".parse().unwrap()), 3 + 7);
+            assert_eq!(grid.total_within("10,0 through 20,20".parse().unwrap()), 0);
+
+            let mut grid = Grid::switches(Dimensions::new(10, 10).unwrap(), backend);
+            grid.follow("turn on 0,0 through 4,9").unwrap();
+            assert_eq!(grid.total_within("3,0 through 6,0".parse().unwrap()), 2);
+        }
+
+        let error = "0,0 through 4,x".parse::<Rectangle>().err().unwrap();
+        assert_eq!(error.to_string(), "column 15: invalid number 'x'");
+
+        let error = "5,0 through 4,9".parse::<Rectangle>().err().unwrap();
+        assert_eq!(error.to_string(), "column 13: 4,9 is above or left of the first corner");
+        let error = "turn on 0,5 through 9,4".parse::<Instruction>().err().unwrap();
+        assert_eq!(error.to_string(), "column 21: 9,4 is above or left of the first corner");
+        assert_eq!(Rectangle { from: Point { x: 5, y: 0 }, to: Point { x: 4, y: 9 } }.area(), 0);
+    }
+
+    #[test]
+    fn backends_agree() {
+        let input = fixtures::input(2015, 6).unwrap();
+        for backend in [Backend::Dense, Backend::Sparse] {
+            assert_eq!(lights_on(&input, backend).unwrap(), 543903, "{backend:?}");
+            assert_eq!(total_brightness(&input, backend).unwrap(), 14_687_245, "{backend:?}");
+        }
+    }
+
+    #[test]
+    fn animate() {
+        let input = "turn on 0,0 through 1,1\ntoggle 1,1 through 2,2";
+        let mut recorder = Recorder::new();
+        animate_part_one(input, &mut recorder).unwrap();
+
+        let frames = recorder.frames();
+        assert_eq!(frames.len(), 3);
+        assert_eq!((frames[2].width(), frames[2].height()), (SIZE, SIZE));
+        assert_eq!(frames[2].caption(), Some("6 light(s) on"));
+        assert_eq!(frames[2].rows().take(3).map(|row| String::from_iter(&row[..3])).collect::<Vec<_>>(), ["##.", "#.#", ".##"]);
+
+        let mut recorder = Recorder::new();
+        animate_part_two(input, &mut recorder).unwrap();
+
+        let frames = recorder.frames();
+        assert_eq!(frames[2].caption(), Some("total brightness 12"));
+        assert_eq!(frames[2].rows().take(3).map(|row| String::from_iter(&row[..3])).collect::<Vec<_>>(), ["110", "132", "022"]);
+        assert_eq!(recorder.palette().color('z'), Some(Color::new(255, 255, 255)));
+    }
 }
@@ -1,119 +1,182 @@
-#[derive(PartialEq, Clone, Debug)]
-struct House {
-    row: i32,
-    column: i32
+use std::collections::{BTreeSet, HashMap, HashSet};
+use grid::{BoundingBox, Direction, Point};
+use parsing::ParseError;
+
+pub type House = Point<i32>;
+
+// Every agent starts at the origin, and they take turns following the
+// directions. Each stop is a present delivered, including the first one.
+pub struct SantaRoute {
+    stops: Vec<Vec<House>>,
 }
 
-impl House {
-    fn origin() -> Self {
-        Self { row: 0, column: 0}
+impl SantaRoute {
+    fn parse_directions(input : &str) -> Result<Vec<Direction>, ParseError> {
+        input.chars().enumerate().map(|(column, instruction)| {
+            Direction::from_arrow(instruction).ok_or_else(|| ParseError::unexpected_character(instruction, column + 1))
+        }).collect()
     }
 
-    fn up(&self) -> Self {
-        Self { row: self.row + 1, column: self.column }
-    }
+    pub fn build(input : &str, agents: usize) -> Result<Self, ParseError> {
+        if agents == 0 {
+            return Err(ParseError::new("a route needs at least one agent"));
+        }
+        let mut stops : Vec<Vec<House>> = vec![vec![House::origin()]; agents];
 
-    fn down(&self) -> Self {
-        Self { row: self.row - 1, column: self.column }
+        for (index, direction) in SantaRoute::parse_directions(input)?.into_iter().enumerate() {
+            let agent = &mut stops[index % agents];
+            let current_house = agent[agent.len() - 1];
+            agent.push(current_house.step(direction));
+        }
+
+        Ok(Self { stops })
     }
 
-    fn left(&self) -> Self {
-        Self { row: self.row, column: self.column + 1 }
+    fn build_part_one(input : &str) -> Result<Self, ParseError> {
+        Self::build(input, 1)
     }
 
-    fn right(&self) -> Self {
-        Self { row: self.row, column: self.column - 1 }
+    fn build_part_two(input : &str) -> Result<Self, ParseError> {
+        Self::build(input, 2)
     }
-}
 
-struct SantaRoute {
-    stops: Vec<House>,
-}
+    pub fn agents(&self) -> usize {
+        self.stops.len()
+    }
 
-impl SantaRoute {
-    fn interpret_instruction(instruction : char, house: House) -> House {
-        match instruction {
-            '^' => house.up(),
-            'v' | 'V' => house.down(),
-            '<' => house.left(),
-            '>' => house.right(),
-            unexpected_character => panic!("Unsupported direction: '{unexpected_character}'")
-        }
+    fn all_stops(&self) -> impl Iterator<Item = &House> {
+        self.stops.iter().flatten()
     }
 
-    fn build_part_one(input : &str) -> Self {
-        let mut current_house = House::origin();
-        let mut stops : Vec<House> = vec![current_house.clone()];
+    pub fn total_visited_houses(&self) -> u32 {
+        let unique_stops : HashSet<&House> = self.all_stops().collect();
 
-        for instruction in input.chars() {
-            current_house = SantaRoute::interpret_instruction(instruction, current_house);
-            stops.push(current_house.clone());
-        }
+        unique_stops.len() as u32
+    }
+
+    // Agents are numbered from 0, and there's nothing for one beyond the last.
+    pub fn houses_visited_by(&self, agent: usize) -> Option<HashSet<House>> {
+        self.stops.get(agent).map(|stops| stops.iter().copied().collect())
+    }
 
-        Self { stops }
-    }
-
-    fn build_part_two(input : &str) -> Self {
-        let mut santa_current_house = House::origin();
-        let mut robosanta_current_house = House::origin();
-        let mut stops : Vec<House> = vec![santa_current_house.clone()];
-
-        for (index, instruction) in input.chars().enumerate() {
-            match index % 2 {
-                0 => {
-                    santa_current_house = SantaRoute::interpret_instruction(instruction, santa_current_house);
-                    stops.push(santa_current_house.clone());
-                },
-                1 => {
-                    robosanta_current_house = SantaRoute::interpret_instruction(instruction, robosanta_current_house);
-                    stops.push(robosanta_current_house.clone());
-                },
-                _ => panic!("not possible")
+    // Houses more than one agent delivered to.
+    pub fn shared_houses(&self) -> BTreeSet<House> {
+        let mut agents_at : HashMap<House, usize> = HashMap::new();
+        for stops in &self.stops {
+            for &house in stops.iter().collect::<HashSet<_>>() {
+                *agents_at.entry(house).or_default() += 1;
             }
         }
 
-        Self { stops }
+        agents_at.into_iter().filter(|&(_, agents)| agents > 1).map(|(house, _)| house).collect()
     }
 
-    fn total_visited_houses(&self) -> u32 {
-        let mut unique_stops : Vec<House> = vec![];
+    pub fn presents_at(&self, house: &House) -> usize {
+        self.all_stops().filter(|stop| *stop == house).count()
+    }
 
-        for house in self.stops.iter() {
-            if !unique_stops.contains(house) {
-                unique_stops.push(house.clone());
-            }
+    // The house with the most presents, and how many it got. Ties go to the
+    // smallest house, so the answer doesn't depend on hashing order.
+    pub fn most_visited_house(&self) -> (House, usize) {
+        let mut presents : HashMap<House, usize> = HashMap::new();
+        for &house in self.all_stops() {
+            *presents.entry(house).or_default() += 1;
         }
 
-        unique_stops.len() as u32
+        presents.into_iter()
+            .max_by(|(house, count), (other_house, other_count)| count.cmp(other_count).then(other_house.cmp(house)))
+            .expect("every route starts with a stop")
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox<i32> {
+        BoundingBox::containing(self.all_stops().copied()).expect("every route starts with a stop")
+    }
+
+    pub fn bounding_box_of(&self, agent: usize) -> Option<BoundingBox<i32>> {
+        self.stops.get(agent).and_then(|stops| BoundingBox::containing(stops.iter().copied()))
     }
 }
 
+pub fn houses_visited(input: &str, agents: usize) -> Result<u32, ParseError> {
+    Ok(SantaRoute::build(input.trim(), agents)?.total_visited_houses())
+}
+
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    Ok(SantaRoute::build_part_one(input.trim())?.total_visited_houses())
+}
+
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    Ok(SantaRoute::build_part_two(input.trim())?.total_visited_houses())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_examples() {
-        assert_eq!(SantaRoute::build_part_one(">").total_visited_houses(), 2);
-        assert_eq!(SantaRoute::build_part_one("^>v<").total_visited_houses(), 4);
-        assert_eq!(SantaRoute::build_part_one("^v^v^v^v^v").total_visited_houses(), 2);
+        assert_eq!(SantaRoute::build_part_one(">").unwrap().total_visited_houses(), 2);
+        assert_eq!(SantaRoute::build_part_one("^>v<").unwrap().total_visited_houses(), 4);
+        assert_eq!(SantaRoute::build_part_one("^v^v^v^v^v").unwrap().total_visited_houses(), 2);
     }
 
     #[test]
     fn part_one() {
-        assert_eq!(SantaRoute::build_part_one(fs::read_to_string("input.txt").unwrap().as_str()).total_visited_houses(), 2572);
+        assert_eq!(SantaRoute::build_part_one(fixtures::input(2015, 3).unwrap().as_str()).unwrap().total_visited_houses(), 2572);
+    }
+
+    #[test]
+    fn unsupported_directions() {
+        assert_eq!(SantaRoute::build_part_one("^>x<").err().unwrap().to_string(), "column 3: unexpected character 'x'");
     }
 
     #[test]
     fn part_two_examples() {
-        assert_eq!(SantaRoute::build_part_two("^v").total_visited_houses(), 3);
-        assert_eq!(SantaRoute::build_part_two("^>v<").total_visited_houses(), 3);
-        assert_eq!(SantaRoute::build_part_two("^v^v^v^v^v").total_visited_houses(), 11);
+        assert_eq!(SantaRoute::build_part_two("^v").unwrap().total_visited_houses(), 3);
+        assert_eq!(SantaRoute::build_part_two("^>v<").unwrap().total_visited_houses(), 3);
+        assert_eq!(SantaRoute::build_part_two("^v^v^v^v^v").unwrap().total_visited_houses(), 11);
+    }
+
+    #[test]
+    fn more_agents() {
+        let route = SantaRoute::build("^>v<^>", 3).unwrap();
+        assert_eq!(route.agents(), 3);
+        // The first agent goes up then left, the second right then up and the
+        // third down then right.
+        assert_eq!(route.houses_visited_by(0).unwrap(), HashSet::from([House::new(0, 0), House::new(0, -1), House::new(-1, -1)]));
+        assert_eq!(route.houses_visited_by(2).unwrap(), HashSet::from([House::new(0, 0), House::new(0, 1), House::new(1, 1)]));
+        assert_eq!(route.total_visited_houses(), 7);
+        assert_eq!(route.shared_houses(), BTreeSet::from([House::new(0, 0)]));
+        assert_eq!(route.most_visited_house(), (House::origin(), 3));
+        assert_eq!(route.bounding_box(), BoundingBox::new(House::new(-1, -1), House::new(1, 1)));
+        assert_eq!(route.bounding_box_of(1), Some(BoundingBox::new(House::new(0, -1), House::new(1, 0))));
+        assert_eq!(route.houses_visited_by(3), None);
+        assert_eq!(route.bounding_box_of(3), None);
+
+        // With one direction each, nobody moves twice.
+        assert_eq!(houses_visited("^>v<", 4).unwrap(), 5);
+        assert_eq!(houses_visited("^v^v^v^v^v", 2).unwrap(), super::part_two("^v^v^v^v^v").unwrap());
+    }
+
+    #[test]
+    fn no_agents() {
+        assert_eq!(houses_visited("^>v<", 0).err().unwrap().to_string(), "a route needs at least one agent");
+        assert!(SantaRoute::build("", 0).is_err());
+    }
+
+    #[test]
+    fn most_visited() {
+        let route = SantaRoute::build("^v^v^v>", 1).unwrap();
+        assert_eq!(route.most_visited_house(), (House::origin(), 4));
+        assert_eq!(route.presents_at(&House::new(0, -1)), 3);
+        assert!(route.shared_houses().is_empty());
+
+        // Both houses get two presents, and the smaller one wins.
+        assert_eq!(SantaRoute::build("><>", 1).unwrap().most_visited_house(), (House::origin(), 2));
     }
 
     #[test]
     fn part_two() {
-        assert_eq!(SantaRoute::build_part_two(fs::read_to_string("input.txt").unwrap().as_str()).total_visited_houses(), 2631);
+        assert_eq!(SantaRoute::build_part_two(fixtures::input(2015, 3).unwrap().as_str()).unwrap().total_visited_houses(), 2631);
     }
 }
@@ -1,81 +1,334 @@
-struct Present {
-    length: u32,
-    width: u32,
-    height: u32,
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+use parsing::{parse_lines, ParseError};
+
+// Lines are either LxWxH for a box, "cube S" or "cylinder DxH" with the
+// diameter first.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Present {
+    Box { length: u32, width: u32, height: u32 },
+    Cube { side: u32 },
+    Cylinder { diameter: u32, height: u32 }
 }
 
-impl Present {
-    fn new(input: &str) -> Self {
-        let parts : Vec<u32> = input.split("x")
-            .map(|part| part.parse::<u32>().unwrap())
-            .collect();
-
-        Self {
-            length: parts[0],
-            width: parts[1],
-            height: parts[2],
+fn dimensions<const N: usize>(input: &str, text: &str, expected: &str) -> Result<[u32; N], ParseError> {
+    let parts : Vec<&str> = text.split("x").collect();
+    if parts.len() != N {
+        return Err(ParseError::new(format!("expected {expected}, found '{input}'")));
+    }
+
+    let mut dimensions = [0; N];
+    for (dimension, part) in dimensions.iter_mut().zip(parts) {
+        *dimension = parsing::number(input, part)?;
+    }
+
+    Ok(dimensions)
+}
+
+impl FromStr for Present {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let present = match input.split_once(' ') {
+            Some(("cube", side)) => Self::Cube { side: parsing::number(input, side)? },
+            Some(("cylinder", size)) => {
+                let [diameter, height] = dimensions(input, size, "cylinder DxH")?;
+                Self::Cylinder { diameter, height }
+            },
+            Some((shape, _)) => return Err(ParseError::at_column(1, format!("unknown shape '{shape}'"))),
+            None => {
+                let [length, width, height] = dimensions(input, input, "LxWxH")?;
+                Self::Box { length, width, height }
+            }
+        };
+        if !present.fits() {
+            return Err(ParseError::new(format!("'{input}' is too large to wrap")));
         }
+
+        Ok(present)
+    }
+}
+
+// Presents whose paper, ribbon or volume won't fit in a u64 are rejected when
+// they are parsed, and the methods below panic on one built by hand.
+const TOO_LARGE : &str = "present is too large to wrap";
+
+impl Present {
+    // Rounded up, since paper and ribbon come in whole feet.
+    fn circumference(diameter: u32) -> u64 {
+        (PI * diameter as f64).ceil() as u64
     }
 
-    fn volume(&self) -> u32 {
-        self.width * self.length * self.height
+    fn checked_volume(&self) -> Option<u64> {
+        match *self {
+            Present::Box { length, width, height } => (length as u64).checked_mul(width as u64)?.checked_mul(height as u64),
+            Present::Cube { side } => (side as u64).checked_mul(side as u64)?.checked_mul(side as u64),
+            Present::Cylinder { diameter, height } => {
+                let volume = (PI * diameter as f64 * diameter as f64 * height as f64 / 4.0).ceil();
+                u64::try_from(volume as u128).ok()
+            }
+        }
     }
 
-    fn side_areas(&self) -> Vec<u32> {
-        vec![
-            self.length * self.width,
-            self.width * self.height,
-            self.length * self.height
+    fn side_areas(length: u64, width: u64, height: u64) -> [u64; 3] {
+        [
+            length * width,
+            width * height,
+            length * height
         ]
     }
 
-    fn side_perimeters(&self) -> Vec<u32> {
-        vec![
-            2 * (self.length + self.width),
-            2 * (self.width + self.height),
-            2 * (self.length + self.height)
+    fn side_perimeters(length: u64, width: u64, height: u64) -> [u64; 3] {
+        [
+            2 * (length + width),
+            2 * (width + height),
+            2 * (length + height)
         ]
     }
 
-    fn total_wrapping_paper(&self) -> u32 {
-        let side_areas = self.side_areas();
+    fn box_wrapping_paper(length: u32, width: u32, height: u32) -> Option<u64> {
+        let side_areas = Self::side_areas(length as u64, width as u64, height as u64);
+
+        let smallest_side = *side_areas.iter().min().unwrap();
+        side_areas.iter().try_fold(smallest_side, |total, area| total.checked_add(area.checked_mul(2)?))
+    }
+
+    fn box_ribbon(length: u32, width: u32, height: u32) -> u64 {
+        *Self::side_perimeters(length as u64, width as u64, height as u64).iter().min().unwrap()
+    }
+
+    // A cube is wrapped like any other box, while a cylinder takes a sheet
+    // around its side and a square over each end.
+    fn checked_wrapping_paper(&self) -> Option<u64> {
+        match *self {
+            Present::Box { length, width, height } => Self::box_wrapping_paper(length, width, height),
+            Present::Cube { side } => Self::box_wrapping_paper(side, side, side),
+            Present::Cylinder { diameter, height } => {
+                let side = Self::circumference(diameter).checked_mul(height as u64)?;
+                let ends = (diameter as u64 * diameter as u64).checked_mul(2)?;
+                side.checked_add(ends)
+            }
+        }
+    }
+
+    fn checked_total_ribbon(&self) -> Option<u64> {
+        self.ribbon().checked_add(self.checked_volume()?)
+    }
+
+    fn fits(&self) -> bool {
+        self.checked_wrapping_paper().is_some() && self.checked_total_ribbon().is_some()
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.checked_volume().expect(TOO_LARGE)
+    }
+
+    pub fn total_wrapping_paper(&self) -> u64 {
+        self.checked_wrapping_paper().expect(TOO_LARGE)
+    }
+
+    // Ribbon goes the shortest way around, either round a cylinder or along it.
+    pub fn ribbon(&self) -> u64 {
+        match *self {
+            Present::Box { length, width, height } => Self::box_ribbon(length, width, height),
+            Present::Cube { side } => Self::box_ribbon(side, side, side),
+            Present::Cylinder { diameter, height } => Self::circumference(diameter).min(2 * (diameter as u64 + height as u64))
+        }
+    }
+
+    // The bow takes as many feet of ribbon as the present has cubic feet.
+    pub fn bow(&self) -> u64 {
+        self.volume()
+    }
+
+    pub fn total_ribbon(&self) -> u64 {
+        self.checked_total_ribbon().expect(TOO_LARGE)
+    }
+
+    pub fn shape(&self) -> &'static str {
+        match self {
+            Present::Box { .. } => "box",
+            Present::Cube { .. } => "cube",
+            Present::Cylinder { .. } => "cylinder"
+        }
+    }
+
+    pub fn size_class(&self) -> SizeClass {
+        match self.volume() {
+            volume if volume <= SMALL_VOLUME => SizeClass::Small,
+            volume if volume <= MEDIUM_VOLUME => SizeClass::Medium,
+            _ => SizeClass::Large
+        }
+    }
+}
+
+// The largest volumes, in cubic feet, of small and medium presents.
+const SMALL_VOLUME : u64 = 64;
+const MEDIUM_VOLUME : u64 = 1000;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum SizeClass {
+    Small,
+    Medium,
+    Large
+}
+
+impl fmt::Display for SizeClass {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SizeClass::Small => "small",
+            SizeClass::Medium => "medium",
+            SizeClass::Large => "large"
+        };
+        formatter.write_str(name)
+    }
+}
 
-        let base_area : u32 = side_areas.iter().map(|area| area * 2).sum();
-        let smallest_side = side_areas.iter().min().unwrap();
+// Paper is in square feet, ribbon and bows in feet.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Materials {
+    pub presents: u64,
+    pub paper: u64,
+    pub ribbon: u64,
+    pub bows: u64
+}
 
-        base_area + smallest_side
+impl Materials {
+    // A manifest is only built once its totals are known to fit, and every
+    // other sum is part of those.
+    fn add(&mut self, present: &Present) {
+        self.presents += 1;
+        self.paper += present.total_wrapping_paper();
+        self.ribbon += present.ribbon();
+        self.bows += present.bow();
     }
 
-    fn total_ribbon(&self) -> u32 {
-        let smallest_perimeter : u32 = *self.side_perimeters().iter().min().unwrap();
+    fn checked_add(self, present: &Present) -> Option<Self> {
+        let materials = Self {
+            presents: self.presents + 1,
+            paper: self.paper.checked_add(present.total_wrapping_paper())?,
+            ribbon: self.ribbon.checked_add(present.ribbon())?,
+            bows: self.bows.checked_add(present.bow())?
+        };
+        materials.ribbon.checked_add(materials.bows)?;
 
-        smallest_perimeter + self.volume()
+        Some(materials)
     }
+
+    pub fn total_ribbon(&self) -> u64 {
+        self.ribbon + self.bows
+    }
+}
+
+// Everything ordered, one present per line.
+pub struct Manifest {
+    presents: Vec<Present>
+}
+
+impl FromStr for Manifest {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let presents : Vec<Present> = parse_lines(input)?;
+        if presents.iter().try_fold(Materials::default(), Materials::checked_add).is_none() {
+            return Err(ParseError::new("the manifest needs more materials than can be counted"));
+        }
+
+        Ok(Self { presents })
+    }
+}
+
+impl Manifest {
+    pub fn totals(&self) -> Materials {
+        let mut totals = Materials::default();
+        for present in &self.presents {
+            totals.add(present);
+        }
+
+        totals
+    }
+
+    pub fn by_size_class(&self) -> BTreeMap<SizeClass, Materials> {
+        let mut classes : BTreeMap<SizeClass, Materials> = BTreeMap::new();
+        for present in &self.presents {
+            classes.entry(present.size_class()).or_default().add(present);
+        }
+
+        classes
+    }
+
+    // Fills as few crates as it can by going through the presents largest
+    // first and putting each in the first crate with room left, judging room
+    // by volume alone. None if some present is too big for any crate.
+    pub fn pack(&self, crate_volume: u64) -> Option<Vec<Vec<Present>>> {
+        let mut presents = self.presents.clone();
+        presents.sort_by_key(|present| std::cmp::Reverse(present.volume()));
+
+        let mut crates : Vec<(u64, Vec<Present>)> = vec![];
+        for present in presents {
+            let volume = present.volume();
+            if volume > crate_volume {
+                return None;
+            }
+
+            match crates.iter_mut().find(|(room, _)| *room >= volume) {
+                Some((room, packed)) => {
+                    *room -= volume;
+                    packed.push(present);
+                },
+                None => crates.push((crate_volume - volume, vec![present]))
+            }
+        }
+
+        Some(crates.into_iter().map(|(_, packed)| packed).collect())
+    }
+
+    // A row for each shape and size class that was ordered, then the totals.
+    pub fn bill_of_materials(&self) -> String {
+        let mut rows : BTreeMap<(&str, SizeClass), Materials> = BTreeMap::new();
+        for present in &self.presents {
+            rows.entry((present.shape(), present.size_class())).or_default().add(present);
+        }
+
+        let mut csv = String::from("shape,size,presents,paper,ribbon,bows\n");
+        for ((shape, size), materials) in rows {
+            csv += &format!("{shape},{size},{},{},{},{}\n", materials.presents, materials.paper, materials.ribbon, materials.bows);
+        }
+        let totals = self.totals();
+        csv += &format!("total,,{},{},{},{}\n", totals.presents, totals.paper, totals.ribbon, totals.bows);
+
+        csv
+    }
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    Ok(input.parse::<Manifest>()?.totals().paper)
+}
+
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    Ok(input.parse::<Manifest>()?.totals().total_ribbon())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
-    use crate::Present;
+    use super::*;
 
     fn presents() -> Vec<Present> {
-        fs::read_to_string("input.txt")
-            .unwrap()
-            .lines()
-            .map(|line| Present::new(line))
-            .collect()
+        parse_lines(&fixtures::input(2015, 2).unwrap()).unwrap()
     }
 
     #[test]
     fn part_one_examples() {
-        assert_eq!(Present::new("2x3x4").total_wrapping_paper(), 58);
-        assert_eq!(Present::new("1x1x10").total_wrapping_paper(), 43);
+        assert_eq!("2x3x4".parse::<Present>().unwrap().total_wrapping_paper(), 58);
+        assert_eq!("1x1x10".parse::<Present>().unwrap().total_wrapping_paper(), 43);
     }
 
     #[test]
     fn part_one() {
 
-        let total_paper : u32 =
+        let total_paper : u64 =
             presents()
                 .iter()
                 .map(|present| present.total_wrapping_paper())
@@ -84,15 +337,97 @@ mod tests {
         assert_eq!(total_paper, 1586300);
     }
 
+    #[test]
+    fn invalid_presents() {
+        assert_eq!(parse_lines::<Present>("2x3x4\n1x1").err().unwrap().to_string(), "line 2: expected LxWxH, found '1x1'");
+        assert_eq!(parse_lines::<Present>("2x3xfour").err().unwrap().to_string(), "line 1, column 5: invalid number 'four'");
+    }
+
     #[test]
     fn part_two_examples() {
-        assert_eq!(Present::new("2x3x4").total_ribbon(), 34);
-        assert_eq!(Present::new("1x1x10").total_ribbon(), 14);
+        assert_eq!("2x3x4".parse::<Present>().unwrap().total_ribbon(), 34);
+        assert_eq!("1x1x10".parse::<Present>().unwrap().total_ribbon(), 14);
+    }
+
+    #[test]
+    fn other_shapes() {
+        let cube : Present = "cube 3".parse().unwrap();
+        assert_eq!(cube, Present::Cube { side: 3 });
+        assert_eq!(cube.total_wrapping_paper(), "3x3x3".parse::<Present>().unwrap().total_wrapping_paper());
+        assert_eq!((cube.ribbon(), cube.bow()), (12, 27));
+
+        // Round the side is 13 feet, which is shorter than along it.
+        let cylinder : Present = "cylinder 4x10".parse().unwrap();
+        assert_eq!(cylinder.total_wrapping_paper(), 13 * 10 + 2 * 16);
+        assert_eq!((cylinder.ribbon(), cylinder.bow()), (13, 126));
+        assert_eq!("cylinder 10x2".parse::<Present>().unwrap().ribbon(), 24);
+
+        assert_eq!("sphere 3".parse::<Present>().err().unwrap().to_string(), "column 1: unknown shape 'sphere'");
+        assert_eq!("cylinder 4".parse::<Present>().err().unwrap().to_string(), "expected cylinder DxH, found 'cylinder 4'");
+        assert_eq!("cube three".parse::<Present>().err().unwrap().to_string(), "column 6: invalid number 'three'");
+    }
+
+    #[test]
+    fn manifest() {
+        let manifest : Manifest = "2x3x4\n1x1x10\ncube 3\ncylinder 4x10\n10x10x11".parse().unwrap();
+        let totals = manifest.totals();
+        assert_eq!(totals, Materials { presents: 5, paper: 58 + 43 + 63 + 162 + 740, ribbon: 10 + 4 + 12 + 13 + 40, bows: 24 + 10 + 27 + 126 + 1100 });
+        assert_eq!(totals.total_ribbon(), 34 + 14 + 39 + 139 + 1140);
+
+        let classes = manifest.by_size_class();
+        assert_eq!(classes.keys().collect::<Vec<_>>(), [&SizeClass::Small, &SizeClass::Medium, &SizeClass::Large]);
+        assert_eq!(classes[&SizeClass::Small].presents, 3);
+        assert_eq!(classes[&SizeClass::Medium], Materials { presents: 1, paper: 162, ribbon: 13, bows: 126 });
+        assert_eq!(classes[&SizeClass::Large].bows, 1100);
+
+        assert_eq!(manifest.bill_of_materials(), "\
+            shape,size,presents,paper,ribbon,bows\n\
+            box,small,2,101,14,34\n\
+            box,large,1,740,40,1100\n\
+            cube,small,1,63,12,27\n\
+            cylinder,medium,1,162,13,126\n\
+            total,,5,1066,79,1287\n");
+    }
+
+    #[test]
+    fn large_presents() {
+        let cube : Present = "2000x2000x2000".parse().unwrap();
+        assert_eq!((cube.volume(), cube.total_wrapping_paper(), cube.ribbon()), (8_000_000_000, 28_000_000, 8000));
+
+        let cylinder : Present = "cylinder 2000x5000".parse().unwrap();
+        assert_eq!(cylinder.volume(), 15_707_963_268);
+
+        let manifest : Manifest = "cylinder 2000x5000\n2000x2000x2000".parse().unwrap();
+        assert_eq!(manifest.totals().bows, 23_707_963_268);
+        assert_eq!(manifest.pack(16_000_000_000).unwrap().len(), 2);
+
+        let error = "4000000000x4000000000x4000000000".parse::<Present>().err().unwrap();
+        assert_eq!(error.to_string(), "'4000000000x4000000000x4000000000' is too large to wrap");
+        assert!("cylinder 4000000000x4000000000".parse::<Present>().is_err());
+        assert!("cube 2000000".parse::<Present>().is_ok());
+        let error = "cube 2000000\n".repeat(3).parse::<Manifest>().err().unwrap();
+        assert_eq!(error.to_string(), "the manifest needs more materials than can be counted");
+    }
+
+    #[test]
+    fn packing() {
+        // Volumes of 24, 10, 27, 126 and 1100.
+        let manifest : Manifest = "2x3x4\n1x1x10\ncube 3\ncylinder 4x10\n10x10x11".parse().unwrap();
+        assert_eq!(manifest.pack(1000), None);
+
+        let crates = manifest.pack(1150).unwrap();
+        assert_eq!(crates, vec![
+            vec!["10x10x11".parse().unwrap(), "cube 3".parse().unwrap(), "1x1x10".parse().unwrap()],
+            vec!["cylinder 4x10".parse().unwrap(), "2x3x4".parse().unwrap()]
+        ]);
+
+        assert_eq!(manifest.pack(2000).unwrap().len(), 1);
+        assert_eq!("".parse::<Manifest>().unwrap().pack(1), Some(vec![]));
     }
 
     #[test]
     fn part_two() {
-        let total_ribbon : u32 =
+        let total_ribbon : u64 =
             presents()
                 .iter()
                 .map(|present| present.total_ribbon())
@@ -1,5 +1,7 @@
 use std::cmp;
 use std::ops::{Range, RangeInclusive};
+use parsing::ParseError;
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
 type Tree = u8;
 type ScenicScore = u32;
@@ -47,16 +49,35 @@ impl TreeScore {
 }
 
 struct ForestMap {
-    rows: Vec<Vec<Tree>>
+    rows: Vec<Vec<Tree>>,
+    surveyed_rows: usize
 }
 
 impl ForestMap {
-    fn new(input: &str) -> Self {
-        Self {
-            rows: input.lines().map(
-                |line| line.bytes().map(|digit| (digit - ('0' as u8))).collect::<Vec<Tree>>()
-            ).collect()
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut rows : Vec<Vec<Tree>> = vec![];
+
+        for (index, line) in input.lines().enumerate() {
+            let row = line.chars().enumerate().map(|(column, digit)| {
+                digit.to_digit(10)
+                    .map(|height| height as Tree)
+                    .ok_or_else(|| ParseError::unexpected_character(digit, column + 1).with_line(index + 1))
+            }).collect::<Result<Vec<Tree>, ParseError>>()?;
+
+            if let Some(first_row) = rows.first() {
+                if row.len() != first_row.len() {
+                    let message = format!("expected {} trees, found {}", first_row.len(), row.len());
+                    return Err(ParseError::new(message).with_line(index + 1));
+                }
+            }
+            rows.push(row);
+        }
+
+        if rows.is_empty() {
+            return Err(ParseError::new("expected a map of tree heights"));
         }
+
+        Ok(Self { rows, surveyed_rows: 0 })
     }
 
     fn most_scenic_score(&self) -> ScenicScore {
@@ -129,7 +150,7 @@ impl ForestMap {
     }
 
     fn tree_at(&self, target_row: usize, target_column: usize) -> Tree {
-        *self.rows.iter().nth(target_row).unwrap().iter().nth(target_column).unwrap()
+        self.rows[target_row][target_column]
     }
 
     fn tree_visibility(&self, target_row: usize, target_column: usize) -> TreeVisibility {
@@ -314,15 +335,69 @@ impl ForestMap {
     }
 }
 
+impl Step for ForestMap {
+    // Surveys the next row of trees for visibility from outside the grid.
+    fn step(&mut self) -> bool {
+        let advanced = self.surveyed_rows < self.rows.len();
+        if advanced {
+            self.surveyed_rows += 1;
+        }
+
+        advanced
+    }
+}
+
+impl Render for ForestMap {
+    // Surveyed rows only show the trees visible from outside the grid, the
+    // rest still show every tree's height.
+    fn render(&self) -> Frame {
+        let mut visible = 0;
+        let frame = Frame::from_fn(self.last_column_index() + 1, self.rows.len(), |column, row| {
+            let tree = char::from_digit(self.tree_at(row, column) as u32, 10).unwrap();
+            if row >= self.surveyed_rows {
+                tree
+            } else if self.tree_is_visible(row, column) {
+                visible += 1;
+                tree
+            } else {
+                '.'
+            }
+        });
+
+        frame.with_caption(format!("{visible} visible tree(s) in {} of {} row(s)", self.surveyed_rows, self.rows.len()))
+    }
+
+    fn palette(&self) -> Palette {
+        (0..10u8).fold(Palette::new().with('.', Color::new(60, 45, 30)), |palette, height| {
+            let shade = 80 + height * 17;
+            palette.with((b'0' + height) as char, Color::new(shade / 4, shade, shade / 4))
+        })
+    }
+}
+
+// Records the visible trees being picked out one row at a time.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    recorder.run(&mut ForestMap::new(input)?);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(ForestMap::new(input)?.count_visible_trees())
+}
+
+pub fn part_two(input: &str) -> Result<ScenicScore, ParseError> {
+    Ok(ForestMap::new(input)?.most_scenic_score())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
         assert_eq!(
-            ForestMap::new(fs::read_to_string("example_input.txt").unwrap().as_str()).count_visible_trees(),
+            ForestMap::new(fixtures::example("example").unwrap().input.as_str()).unwrap().count_visible_trees(),
             21
         );
     }
@@ -330,15 +405,32 @@ mod tests {
     #[test]
     fn part_one() {
         assert_eq!(
-            ForestMap::new(fs::read_to_string("input.txt").unwrap().as_str()).count_visible_trees(),
+            ForestMap::new(fixtures::input(2022, 8).unwrap().as_str()).unwrap().count_visible_trees(),
             1715
         );
     }
 
+    #[test]
+    fn animate_example() {
+        let mut recorder = Recorder::new();
+        animate(fixtures::example("example").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 6);
+        assert_eq!(recorder.frames()[0].to_string(), "0 visible tree(s) in 0 of 5 row(s)\n30373\n25512\n65332\n33549\n35390\n");
+        assert_eq!(recorder.frames()[5].to_string(), "21 visible tree(s) in 5 of 5 row(s)\n30373\n255.2\n65.32\n3.5.9\n35390\n");
+    }
+
+    #[test]
+    fn invalid_maps() {
+        assert_eq!(ForestMap::new("303\n2x5").err().unwrap().to_string(), "line 2, column 2: unexpected character 'x'");
+        assert_eq!(ForestMap::new("303\n25").err().unwrap().to_string(), "line 2: expected 3 trees, found 2");
+        assert_eq!(ForestMap::new("").err().unwrap().to_string(), "expected a map of tree heights");
+    }
+
     #[test]
     fn part_two_example() {
         assert_eq!(
-            ForestMap::new(fs::read_to_string("example_input.txt").unwrap().as_str()).most_scenic_score(),
+            ForestMap::new(fixtures::example("example").unwrap().input.as_str()).unwrap().most_scenic_score(),
             8
         );
     }
@@ -346,7 +438,7 @@ mod tests {
     #[test]
     fn part_two() {
         assert_eq!(
-            ForestMap::new(fs::read_to_string("input.txt").unwrap().as_str()).most_scenic_score(),
+            ForestMap::new(fixtures::input(2022, 8).unwrap().as_str()).unwrap().most_scenic_score(),
             374400
         );
     }
@@ -1,5 +1,7 @@
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use parsing::{parse_lines, ParseError};
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 enum Material {
@@ -15,13 +17,18 @@ struct Point {
     z: i32
 }
 
-impl Point {
-    fn new(input: &str) -> Self {
-        let parts : Vec<i32> = input.split(",").map(|number| number.parse::<i32>().unwrap()).collect();
+impl FromStr for Point {
+    type Err = ParseError;
 
-        Self { x: parts[0], y: parts[1], z: parts[2] }
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.split(",").collect::<Vec<&str>>()[..] {
+            [x, y, z] => Ok(Self { x: parsing::number(input, x)?, y: parsing::number(input, y)?, z: parsing::number(input, z)? }),
+            _ => Err(ParseError::new(format!("expected x,y,z, found '{input}'")))
+        }
     }
+}
 
+impl Point {
     fn neighbors(&self) -> Vec<Point> {
         vec![self.up(), self.down(), self.left(), self.right(), self.forward(), self.back()]
     }
@@ -58,15 +65,19 @@ struct DropletScan {
 }
 
 impl DropletScan {
-    fn new(input: &str) -> Self {
-        let points : HashMap<Point, Material> = input.lines().map(|line| (Point::new(line), Material::Rock)).collect();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let points : HashMap<Point, Material> = parse_lines(input)?.into_iter().map(|point| (point, Material::Rock)).collect();
+        if points.is_empty() {
+            return Err(ParseError::new("expected at least one cube"));
+        }
+
         let (bottom_left_back, top_right_forward) = Self::dimensions(&points);
 
         let mut droplet = Self { points, top_right_forward, bottom_left_back };
 
         droplet.populate_air_and_steam();
 
-        droplet
+        Ok(droplet)
     }
 
     fn total_surface_area(&self) -> usize {
@@ -174,15 +185,22 @@ impl DropletScan {
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(DropletScan::new(input)?.total_surface_area())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(DropletScan::new(input)?.total_exterior_surface_area())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
         assert_eq!(
-            DropletScan::new(fs::read_to_string("example_input.txt").unwrap().as_str()).total_surface_area(),
+            DropletScan::new(fixtures::example("example").unwrap().input.as_str()).unwrap().total_surface_area(),
             64
         );
     }
@@ -190,15 +208,21 @@ mod tests {
     #[test]
     fn part_one() {
         assert_eq!(
-            DropletScan::new(fs::read_to_string("input.txt").unwrap().as_str()).total_surface_area(),
+            DropletScan::new(fixtures::input(2022, 18).unwrap().as_str()).unwrap().total_surface_area(),
             4282
         );
     }
 
+    #[test]
+    fn invalid_cubes() {
+        assert_eq!(DropletScan::new("2,2,2\n1,2").err().unwrap().to_string(), "line 2: expected x,y,z, found '1,2'");
+        assert_eq!(DropletScan::new("2,2,2\n1,2,z").err().unwrap().to_string(), "line 2, column 5: invalid number 'z'");
+    }
+
     #[test]
     fn part_two_example() {
         assert_eq!(
-            DropletScan::new(fs::read_to_string("example_input.txt").unwrap().as_str()).total_exterior_surface_area(),
+            DropletScan::new(fixtures::example("example").unwrap().input.as_str()).unwrap().total_exterior_surface_area(),
             58
         );
     }
@@ -206,7 +230,7 @@ mod tests {
     #[test]
     fn part_two() {
         assert_eq!(
-            DropletScan::new(fs::read_to_string("input.txt").unwrap().as_str()).total_exterior_surface_area(),
+            DropletScan::new(fixtures::input(2022, 18).unwrap().as_str()).unwrap().total_exterior_surface_area(),
             2452
         );
     }
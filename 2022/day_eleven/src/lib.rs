@@ -1,4 +1,5 @@
 use std::borrow::BorrowMut;
+use parsing::ParseError;
 
 type Worry = u64;
 
@@ -13,11 +14,29 @@ struct Troop {
 }
 
 impl Troop {
-    fn new(input: &str, worry_reducer: Worry) -> Self {
-        let monkeys : Vec<Monkey> = input.split("\n\n").map(|note| Monkey::new(note, worry_reducer)).collect();
-        let common_multiple = monkeys.iter().map(|monkey| monkey.divisor ).reduce(|multiple, divisor| multiple * divisor ).unwrap();
+    fn new(input: &str, worry_reducer: Worry) -> Result<Self, ParseError> {
+        let mut monkeys : Vec<Monkey> = vec![];
+        let mut lines_read = 0;
 
-        Self { monkeys, common_multiple }
+        for note in input.split("\n\n") {
+            monkeys.push(Monkey::new(note, worry_reducer).map_err(|error| error.after_lines(lines_read))?);
+            lines_read += note.lines().count() + 1;
+        }
+
+        if monkeys.len() < 2 {
+            return Err(ParseError::new("expected at least two monkeys"));
+        }
+        for (index, monkey) in monkeys.iter().enumerate() {
+            for target in [monkey.next_monkeys.0, monkey.next_monkeys.1] {
+                if target >= monkeys.len() || target == index {
+                    return Err(ParseError::new(format!("monkey {index} can't throw to monkey {target}")));
+                }
+            }
+        }
+
+        let common_multiple = monkeys.iter().map(|monkey| monkey.divisor ).product();
+
+        Ok(Self { monkeys, common_multiple })
     }
 
     fn run_rounds(&mut self, rounds: usize) {
@@ -52,20 +71,12 @@ enum Operation {
 }
 
 impl Operation {
-    fn new(input: &str) -> Self {
-        let parts : Vec<&str> = input.split("new = old ").last().unwrap().split(" ").collect();
-        if parts[1] == "old" {
-            match parts[0] {
-                "*" => { return Operation::Square },
-                _ => panic!("Unknown operation: {input}")
-            }
-        }
-        let number = parts[1].parse::<Worry>().unwrap();
-
-        match parts[0] {
-            "*" => Operation::Multiply(number),
-            "+" => Operation::Add(number),
-            _ => panic!("Unknown operation: {input}")
+    fn new(line: &str, expression: &str) -> Result<Self, ParseError> {
+        match expression.split(" ").collect::<Vec<&str>>()[..] {
+            ["old", "*", "old"] => Ok(Operation::Square),
+            ["old", "*", number] => Ok(Operation::Multiply(parsing::number(line, number)?)),
+            ["old", "+", number] => Ok(Operation::Add(parsing::number(line, number)?)),
+            _ => Err(ParseError::at_token(line, expression, format!("unknown operation '{expression}'")))
         }
     }
 }
@@ -80,17 +91,40 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn new(input: &str, worry_reducer: Worry) -> Self {
-        let mut all_lines = input.lines().map(str::trim);
-        all_lines.next(); // Ignore initial "monkey N:"
-        let lines : Vec<&str> = all_lines.map(|line| line.split(": ").last().unwrap()).collect();
-        let items = lines[0].split(", ").map(|item| item.parse::<Worry>().unwrap()).collect();
-        let operation= Operation::new(lines[1]);
-        let divisor = lines[2].split("divisible by ").last().unwrap().parse::<Worry>().unwrap();
-        let monkey_one = lines[3].split(" monkey ").last().unwrap().parse::<usize>().unwrap();
-        let monkey_two = lines[4].split(" monkey ").last().unwrap().parse::<usize>().unwrap();
+    // Lines are numbered from the start of the note, since notes get parsed one at a time.
+    fn field<'a>(lines: &[&'a str], index: usize, prefix: &str) -> Result<(&'a str, &'a str), ParseError> {
+        let line = *lines.get(index).ok_or_else(|| ParseError::new(format!("expected '{prefix}'")).with_line(index + 1))?;
+        let trimmed = line.trim();
+        let value = parsing::strip_prefix(trimmed, prefix).map_err(|error| error.within(line, trimmed).with_line(index + 1))?;
+
+        Ok((line, value))
+    }
+
+    fn new(input: &str, worry_reducer: Worry) -> Result<Self, ParseError> {
+        let lines : Vec<&str> = input.lines().collect();
+        Self::field(&lines, 0, "Monkey ")?; // Ignore initial "monkey N:"
+
+        let (line, items) = Self::field(&lines, 1, "Starting items: ")?;
+        let items = items.split(", ")
+            .map(|item| parsing::number(line, item))
+            .collect::<Result<_, _>>()
+            .map_err(|error| error.with_line(2))?;
+
+        let (line, operation) = Self::field(&lines, 2, "Operation: new = ")?;
+        let operation = Operation::new(line, operation).map_err(|error| error.with_line(3))?;
 
-        Self { inspections: 0, worry_reducer, items, operation, divisor, next_monkeys: (monkey_one, monkey_two) }
+        let (line, divisor_token) = Self::field(&lines, 3, "Test: divisible by ")?;
+        let divisor : Worry = parsing::number(line, divisor_token).map_err(|error| error.with_line(4))?;
+        if divisor == 0 {
+            return Err(ParseError::at_token(line, divisor_token, "can't test divisibility by 0").with_line(4));
+        }
+
+        let (line, monkey_one) = Self::field(&lines, 4, "If true: throw to monkey ")?;
+        let monkey_one = parsing::number(line, monkey_one).map_err(|error| error.with_line(5))?;
+        let (line, monkey_two) = Self::field(&lines, 5, "If false: throw to monkey ")?;
+        let monkey_two = parsing::number(line, monkey_two).map_err(|error| error.with_line(6))?;
+
+        Ok(Self { inspections: 0, worry_reducer, items, operation, divisor, next_monkeys: (monkey_one, monkey_two) })
     }
 
     fn run_round(&mut self, common_multiple: Worry) -> Vec<Toss> {
@@ -104,8 +138,8 @@ impl Monkey {
 
     fn toss_for_item(&self, original_item: Worry, common_multiple: Worry) -> Toss {
         let mut item = self.increase_worry(original_item, common_multiple);
-        if self.worry_reducer > 0 {
-            item /= self.worry_reducer;
+        if let Some(reduced_item) = item.checked_div(self.worry_reducer) {
+            item = reduced_item;
         }
 
         let target = self.target_for(item);
@@ -128,7 +162,7 @@ impl Monkey {
     }
 
     fn target_for(&self, item: Worry) -> usize {
-        if (item % self.divisor) == 0 {
+        if item.is_multiple_of(self.divisor) {
             self.next_monkeys.0
         } else {
             self.next_monkeys.1
@@ -140,14 +174,27 @@ impl Monkey {
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut troop = Troop::new(input, 3)?;
+    troop.run_rounds(20);
+
+    Ok(troop.monkey_business())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let mut troop = Troop::new(input, 0)?;
+    troop.run_rounds(10_000);
+
+    Ok(troop.monkey_business())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let mut troop = Troop::new(fs::read_to_string("example_input.txt").unwrap().as_str(), 3);
+        let mut troop = Troop::new(fixtures::example("example").unwrap().input.as_str(), 3).unwrap();
         troop.run_rounds(20);
         assert_eq!(
             troop.monkey_business(),
@@ -157,7 +204,7 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let mut troop = Troop::new(fs::read_to_string("input.txt").unwrap().as_str(), 3);
+        let mut troop = Troop::new(fixtures::input(2022, 11).unwrap().as_str(), 3).unwrap();
         troop.run_rounds(20);
         assert_eq!(
             troop.monkey_business(),
@@ -165,9 +212,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn invalid_notes() {
+        let example = fixtures::example("example").unwrap().input;
+
+        let error = Troop::new(&example.replace("old * 19", "old / 19"), 3).err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 20: unknown operation 'old / 19'");
+
+        let error = Troop::new(&example.replace("79, 60, 97", "79, sixty, 97"), 3).err().unwrap();
+        assert_eq!(error.to_string(), "line 16, column 23: invalid number 'sixty'");
+
+        let error = Troop::new(&example.replace("If false: throw to monkey 1", "If false: throw to monkey 4"), 3).err().unwrap();
+        assert_eq!(error.to_string(), "monkey 3 can't throw to monkey 4");
+    }
+
     #[test]
     fn part_two_example() {
-        let mut troop = Troop::new(fs::read_to_string("example_input.txt").unwrap().as_str(), 0);
+        let mut troop = Troop::new(fixtures::example("example").unwrap().input.as_str(), 0).unwrap();
         troop.run_rounds(20);
         assert_eq!(troop.monkey_business(), 10197);
         troop.run_rounds(980);
@@ -178,7 +239,7 @@ mod tests {
 
     #[test]
     fn part_two() {
-        let mut troop = Troop::new(fs::read_to_string("input.txt").unwrap().as_str(), 0);
+        let mut troop = Troop::new(fixtures::input(2022, 11).unwrap().as_str(), 0).unwrap();
         troop.run_rounds(10_000);
         assert_eq!(troop.monkey_business(), 14_636_993_466);
     }
@@ -1,5 +1,7 @@
-use std::cmp::{max, min};
 use std::ops::RangeInclusive;
+use std::str::FromStr;
+use grid::BoundingBox;
+use parsing::{parse_lines, ParseError};
 
 type NumberRange = RangeInclusive<i32>;
 
@@ -42,23 +44,14 @@ fn apply_masks_to_range(initial: NumberRange, masks: Vec<NumberRange>) -> Vec<Nu
     uncovered_ranges
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct Point {
-    x:  i32,
-    y: i32
-}
-
-impl Point {
-    fn new(input: &str) -> Self {
-        let values : Vec<i32> =
-            input.split(", ").map(|half| half.rsplit("=").next().unwrap().parse::<i32>().unwrap()).collect();
+type Point = grid::Point<i32>;
 
-        Self { x: values[0], y: values[1] }
-    }
+fn parse_point(line: &str, input: &str) -> Result<Point, ParseError> {
+    let (x, y) = parsing::split_once(input, ", ")?;
+    let x = parsing::strip_prefix(x, "x=").map_err(|error| error.within(line, x))?;
+    let y = parsing::strip_prefix(y, "y=").map_err(|error| error.within(line, y))?;
 
-    fn manhattan_distance_to(&self, other: &Point) -> i32 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
-    }
+    Ok(Point::new(parsing::number(line, x)?, parsing::number(line, y)?))
 }
 
 struct Sensor {
@@ -71,14 +64,20 @@ struct Sensor {
     right: i32
 }
 
-impl Sensor {
-    fn new(input: &str) -> Self {
-        let halves : Vec<Point> =
-            input.split(": ").map(|half| Point::new(half.rsplit("at ").next().unwrap())).collect();
+impl FromStr for Sensor {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let location = parsing::strip_prefix(input, "Sensor at ")?;
+        let (location, nearest_beacon) = parsing::split_once(location, ": closest beacon is at ")?;
 
-        let location = halves[0];
-        let nearest_beacon = halves[1];
-        let distance_to_beacon = location.manhattan_distance_to(&nearest_beacon);
+        Ok(Self::new(parse_point(input, location)?, parse_point(input, nearest_beacon)?))
+    }
+}
+
+impl Sensor {
+    fn new(location: Point, nearest_beacon: Point) -> Self {
+        let distance_to_beacon = location.manhattan_distance(&nearest_beacon);
         let top = location.y - distance_to_beacon;
         let bottom = location.y + distance_to_beacon;
         let left = location.x - distance_to_beacon;
@@ -88,11 +87,11 @@ impl Sensor {
     }
 
     fn covers(&self, point: &Point) -> bool {
-        self.location.manhattan_distance_to(point) <= self.distance_to_beacon
+        self.location.manhattan_distance(point) <= self.distance_to_beacon
     }
 
-    fn dimensions(&self) -> (Point, Point) {
-        (Point { x: self.left, y: self.top }, Point { x: self.right, y: self.bottom })
+    fn dimensions(&self) -> BoundingBox<i32> {
+        BoundingBox::new(Point::new(self.left, self.top), Point::new(self.right, self.bottom))
     }
 
     fn coverage_at_row(&self, row: i32) -> Option<NumberRange> {
@@ -110,6 +109,7 @@ impl Sensor {
     }
 }
 
+#[allow(dead_code)]
 enum MapReading {
     Sensor(usize),
     Beacon(usize),
@@ -122,8 +122,8 @@ struct BeaconSensorArray {
 }
 
 impl BeaconSensorArray {
-    fn new(input: &str) -> Self {
-        Self { sensors: input.lines().map(Sensor::new).collect() }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        Ok(Self { sensors: parse_lines(input)? })
     }
 
     fn distress_beacon_frequency(&self, max: i32) -> usize {
@@ -139,19 +139,17 @@ impl BeaconSensorArray {
     }
 
     fn check_row_for_distress_beacon(&self, row: i32, full_range: NumberRange) -> Option<i32> {
-        self.find_solitary_uncovered_range_for_row(row, full_range).and_then(|target_range| {
-            Some(*target_range.start())
-        })
+        self.find_solitary_uncovered_range_for_row(row, full_range).map(|target_range| *target_range.start())
     }
 
     fn find_solitary_uncovered_range_for_row(&self, row: i32, full_range: NumberRange) -> Option<NumberRange> {
-        self.find_possible_uncovered_ranges_for_row(row, full_range).and_then(|uncovered_ranges| {
+        self.find_possible_uncovered_ranges_for_row(row, full_range).map(|uncovered_ranges| {
             let target_range = uncovered_ranges.first().unwrap();
             if target_range.start() != target_range.end() {
                 panic!("Too large of range!");
             }
 
-            Some(target_range.to_owned())
+            target_range.to_owned()
         })
     }
 
@@ -162,8 +160,7 @@ impl BeaconSensorArray {
         }
 
         if uncovered_ranges.len() > 1 {
-            dbg!(&uncovered_ranges);
-            panic!("Too many ranges!");
+            panic!("Too many ranges: {:?}", uncovered_ranges);
         }
 
         Some(uncovered_ranges)
@@ -178,15 +175,15 @@ impl BeaconSensorArray {
     }
 
     fn sensor_coverage_ranges_for_row(&self, row: i32) -> Vec<NumberRange> {
-        self.sensors.iter().filter_map(|sensor| sensor.coverage_at_row(row as i32)).collect()
+        self.sensors.iter().filter_map(|sensor| sensor.coverage_at_row(row)).collect()
     }
 
     fn beacon_exclusions_at_row(&self, row: i32) -> usize {
         let dimensions = self.dimensions();
         let mut count = 0;
 
-        for column in dimensions.0.x..=dimensions.1.x {
-            if matches!(self.sensor_coverage_at(&Point { x: column, y: row }), MapReading::CoveredBySensor(_)) {
+        for column in dimensions.columns() {
+            if matches!(self.sensor_coverage_at(&Point::new(column, row)), MapReading::CoveredBySensor(_)) {
                 count += 1;
             }
         }
@@ -194,22 +191,14 @@ impl BeaconSensorArray {
         count
     }
 
-    fn dimensions(&self) -> (Point, Point) {
-        let mut min_x : i32 = 0;
-        let mut min_y : i32 = 0;
-        let mut max_x : i32 = 0;
-        let mut max_y : i32 = 0;
+    fn dimensions(&self) -> BoundingBox<i32> {
+        let mut bounds = BoundingBox::around(Point::origin());
 
         for sensor in self.sensors.iter() {
-            let (top_left, bottom_right) = sensor.dimensions();
-
-            min_y = min(min_y, top_left.y);
-            max_y = max(max_y, bottom_right.y);
-            min_x = min(min_x, top_left.x);
-            max_x = max(max_x, bottom_right.x);
+            bounds = bounds.union(&sensor.dimensions());
         }
 
-        ( Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y } )
+        bounds
     }
 
     fn sensor_coverage_at(&self, point: &Point) -> MapReading {
@@ -230,19 +219,20 @@ impl BeaconSensorArray {
         MapReading::Open
     }
 
+    #[allow(dead_code)]
     fn print(&self) {
-        let (top_left, bottom_right) = self.dimensions();
+        let bounds = self.dimensions();
 
         print!("    ");
-        for column in top_left.x..=bottom_right.x {
+        for column in bounds.columns() {
             print!("{:^3} ", column);
         }
         println!();
 
-        for row in top_left.y..=bottom_right.y {
+        for row in bounds.rows() {
             print!("{:>3} ", row);
-            for column in top_left.x..=bottom_right.x {
-                let point = Point { x: column, y: row };
+            for column in bounds.columns() {
+                let point = Point::new(column, row);
                 let mut has_printed = false;
                 for (index, sensor) in self.sensors.iter().enumerate() {
                     if sensor.nearest_beacon == point {
@@ -274,34 +264,53 @@ impl BeaconSensorArray {
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(BeaconSensorArray::new(input)?.beacon_exclusions_at_row(2_000_000))
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(BeaconSensorArray::new(input)?.distress_beacon_frequency(4_000_000))
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let array = BeaconSensorArray::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let array = BeaconSensorArray::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         array.print();
         assert_eq!(array.beacon_exclusions_at_row(10), 26);
     }
 
     #[test]
     fn part_one() {
-        let array = BeaconSensorArray::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let array = BeaconSensorArray::new(fixtures::input(2022, 15).unwrap().as_str()).unwrap();
         assert_eq!(array.beacon_exclusions_at_row(2_000_000), 5_256_611);
     }
 
+    #[test]
+    fn invalid_sensors() {
+        let error = BeaconSensorArray::new("Sensor at x=2, y=18: closest beacon is at x=-2, y=15\nSensor at x=9, y=1_6: closest beacon is at x=10, y=16").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 18: invalid number '1_6'");
+
+        let error = BeaconSensorArray::new("Sensor at x=2, z=18: closest beacon is at x=-2, y=15").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 16: expected 'y='");
+
+        let error = BeaconSensorArray::new("Beacon at x=2, y=18").err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 1: expected 'Sensor at '");
+    }
+
     #[test]
     fn part_two_example() {
-        let array = BeaconSensorArray::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let array = BeaconSensorArray::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         array.print();
         assert_eq!(array.distress_beacon_frequency(20), 56_000_011);
     }
 
     #[test]
     fn part_two() {
-        let array = BeaconSensorArray::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let array = BeaconSensorArray::new(fixtures::input(2022, 15).unwrap().as_str()).unwrap();
         assert_eq!(array.distress_beacon_frequency(4_000_000), 13_337_919_186_981);
     }
 }
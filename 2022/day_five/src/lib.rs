@@ -1,4 +1,6 @@
 use std::iter;
+use std::str::FromStr;
+use parsing::ParseError;
 
 #[derive(Clone,Debug)]
 struct MoveStep {
@@ -7,19 +9,26 @@ struct MoveStep {
     to: usize
 }
 
-impl MoveStep {
-    fn new(line: &str) -> Self {
-        let parts: Vec<&str> = line.trim().split(" ").collect();
-
-        MoveStep {
-            count: parts[1].parse::<usize>().unwrap(),
-            from: parts[3].parse::<usize>().unwrap(),
-            to: parts[5].parse::<usize>().unwrap()
+impl FromStr for MoveStep {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        match line.trim().split(" ").collect::<Vec<&str>>()[..] {
+            ["move", count, "from", from, "to", to] => {
+                Ok(MoveStep {
+                    count: parsing::number(line, count)?,
+                    from: parsing::number(line, from)?,
+                    to: parsing::number(line, to)?
+                })
+            },
+            _ => Err(ParseError::new(format!("expected 'move N from A to B', found '{line}'")))
         }
     }
+}
 
-    fn iter(&self) -> iter::Take<iter::Repeat<()>> {
-        std::iter::repeat(()).take(self.count)
+impl MoveStep {
+    fn iter(&self) -> iter::RepeatN<()> {
+        iter::repeat_n((), self.count)
     }
 }
 
@@ -30,38 +39,36 @@ struct Yard {
 }
 
 impl Yard {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut yard = Self { stacks: vec![], move_plan: vec![] };
 
-        let mut lines = input.lines();
-        let mut line : &str;
+        let mut lines = input.lines().enumerate();
 
         // read in stacks
         loop {
-            line = lines.next().unwrap();
+            let (index, line) = lines.next().ok_or_else(|| ParseError::new("expected a row of stack numbers"))?;
 
-            if line[0..=1].eq(" 1") {
+            if line.starts_with(" 1") {
                 break;
             }
-            // line = line.trim();
-            let mut stack_index = 0;
+            if !line.is_ascii() {
+                return Err(ParseError::new(format!("expected a row of crates, found '{line}'")).with_line(index + 1));
+            }
 
-            while !line.is_empty() {
+            for (stack_index, column) in (0..line.len()).step_by(4).enumerate() {
                 if yard.stacks.len() <= stack_index {
                     yard.stacks.push(vec![]);
                 }
 
-                let stack_name : &str = &line[0..=2];
-                if !stack_name.eq("   ") {
-                    yard.stacks[stack_index].push(stack_name.chars().nth(1).unwrap())
-                }
-                stack_index += 1;
-
-                if line.len() == 3 {
-                    break;
+                let stack_name : &str = &line[column..(column + 3).min(line.len())];
+                match stack_name.as_bytes() {
+                    b"   " => {},
+                    [b'[', shipping_crate, b']'] => yard.stacks[stack_index].push(*shipping_crate as char),
+                    _ => {
+                        let message = format!("expected a crate, found '{stack_name}'");
+                        return Err(ParseError::at_token(line, stack_name, message).with_line(index + 1));
+                    }
                 }
-                let old_line = line;
-                line = &old_line[4..];
             }
         }
 
@@ -69,21 +76,18 @@ impl Yard {
         lines.next();
 
         // read in moves
-        loop {
-            let possible_line = lines.next();
-            if possible_line.is_none() {
-                break;
-            }
-
-            line = possible_line.unwrap();
-            if line.is_empty() {
-                break;
+        for (index, line) in lines.take_while(|(_index, line)| !line.is_empty()) {
+            let move_step : MoveStep = line.parse().map_err(|error: ParseError| error.with_line(index + 1))?;
+            for stack in [move_step.from, move_step.to] {
+                if !(1..=yard.stacks.len()).contains(&stack) {
+                    return Err(ParseError::new(format!("there is no stack {stack}")).with_line(index + 1));
+                }
             }
 
-            yard.move_plan.push(MoveStep::new(line))
+            yard.move_plan.push(move_step)
         }
 
-        yard
+        Ok(yard)
     }
 
     fn reverse_stacks(&mut self) {
@@ -133,7 +137,7 @@ impl Yard {
     }
 
     fn top_crates(&self) -> String {
-        String::from_iter(self.stacks.iter().map(|stack| stack.iter().rev().next().unwrap()))
+        String::from_iter(self.stacks.iter().map(|stack| stack.last().unwrap()))
     }
 
     // fn print(&self) {
@@ -165,15 +169,22 @@ impl Yard {
     // }
 }
 
+pub fn part_one(input: &str) -> Result<String, ParseError> {
+    Ok(Yard::new(input)?.after_following_plan(Yard::follow_part_one_plan).top_crates())
+}
+
+pub fn part_two(input: &str) -> Result<String, ParseError> {
+    Ok(Yard::new(input)?.after_following_plan(Yard::follow_part_two_plan).top_crates())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs::read_to_string;
     use super::*;
 
     #[test]
     fn part_one_example() {
         assert_eq!(
-            Yard::new(read_to_string("example_input.txt").unwrap().as_str())
+            Yard::new(fixtures::example("example").unwrap().input.as_str()).unwrap()
                 .after_following_plan(Yard::follow_part_one_plan)
                 .top_crates(),
             "CMZ"
@@ -183,17 +194,31 @@ mod tests {
     #[test]
     fn part_one() {
         assert_eq!(
-            Yard::new(read_to_string("input.txt").unwrap().as_str())
+            Yard::new(fixtures::input(2022, 5).unwrap().as_str()).unwrap()
                 .after_following_plan(Yard::follow_part_one_plan)
                 .top_crates(),
             "TPGVQPFDH"
         );
     }
 
+    #[test]
+    fn invalid_yards() {
+        let error = Yard::new("    [D]\n[N] {C}\n 1   2").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 5: expected a crate, found '{C}'");
+
+        let error = Yard::new("[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 1 from 1 to 3").err().unwrap();
+        assert_eq!(error.to_string(), "line 5: there is no stack 3");
+
+        let error = Yard::new("[N] [C]\n 1   2\n\nmove one from 2 to 1").err().unwrap();
+        assert_eq!(error.to_string(), "line 4, column 6: invalid number 'one'");
+
+        assert!(Yard::new("[N] [C]").is_err());
+    }
+
     #[test]
     fn part_two_example() {
         assert_eq!(
-            Yard::new(read_to_string("example_input.txt").unwrap().as_str())
+            Yard::new(fixtures::example("example").unwrap().input.as_str()).unwrap()
                 .after_following_plan(Yard::follow_part_two_plan)
                 .top_crates(),
             "MCD"
@@ -203,7 +228,7 @@ mod tests {
     #[test]
     fn part_two() {
         assert_eq!(
-            Yard::new(read_to_string("input.txt").unwrap().as_str())
+            Yard::new(fixtures::input(2022, 5).unwrap().as_str()).unwrap()
                 .after_following_plan(Yard::follow_part_two_plan)
                 .top_crates(),
             "DMRDFRHHH"
@@ -1,9 +1,11 @@
 use std::ops::Range;
+use std::str::FromStr;
+use parsing::{parse_lines, ParseError};
 
-fn parse_number_range(input: &str) -> Range<u32> {
-    let mut numbers = input.split("-").map(|number| number.parse::<u32>().unwrap());
+fn parse_number_range(line: &str, input: &str) -> Result<Range<u32>, ParseError> {
+    let (start, end) = parsing::split_once(input, "-")?;
 
-    Range { start: numbers.next().unwrap(), end: numbers.next().unwrap() }
+    Ok(Range { start: parsing::number(line, start)?, end: parsing::number(line, end)? })
 }
 
 struct PairAssignment {
@@ -11,13 +13,17 @@ struct PairAssignment {
     right: Range<u32>
 }
 
-impl PairAssignment {
-    fn new(input: &str) -> Self {
-        let mut ranges = input.split(",").map(parse_number_range);
+impl FromStr for PairAssignment {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (left, right) = parsing::split_once(input, ",")?;
 
-        Self { left: ranges.next().unwrap(), right: ranges.next().unwrap() }
+        Ok(Self { left: parse_number_range(input, left)?, right: parse_number_range(input, right)? })
     }
+}
 
+impl PairAssignment {
     fn is_at_least_partial_overlap(&self) -> bool {
         self.left_crosses_into_right() || self.right_crosses_into_left()
     }
@@ -48,8 +54,8 @@ struct Schedule {
 }
 
 impl Schedule {
-    fn new(input: &str) -> Self {
-        Self { assigned_pairs: input.lines().map(PairAssignment::new).collect() }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        Ok(Self { assigned_pairs: parse_lines(input)? })
     }
 
     fn count_total_overlaps(&self) -> usize {
@@ -61,19 +67,22 @@ impl Schedule {
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(Schedule::new(input)?.count_total_overlaps())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(Schedule::new(input)?.count_partial_overlaps())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
-    fn read_input(file_name: &str) -> String {
-        fs::read_to_string(file_name).expect("File not found.")
-    }
-
     #[test]
     fn part_one_example() {
         assert_eq!(
-            Schedule::new(read_input("example_input.txt").as_str()).count_total_overlaps(),
+            Schedule::new(fixtures::example("example").unwrap().input.as_str()).unwrap().count_total_overlaps(),
             2
         );
     }
@@ -81,15 +90,21 @@ mod tests {
     #[test]
     fn part_one() {
         assert_eq!(
-            Schedule::new(read_input("input.txt").as_str()).count_total_overlaps(),
+            Schedule::new(fixtures::input(2022, 4).unwrap().as_str()).unwrap().count_total_overlaps(),
             599
         );
     }
 
+    #[test]
+    fn invalid_assignments() {
+        assert_eq!(Schedule::new("2-4,6-8\n2-3,4_5").err().unwrap().to_string(), "line 2: expected '-' in '4_5'");
+        assert_eq!(Schedule::new("2-4,6-x").err().unwrap().to_string(), "line 1, column 7: invalid number 'x'");
+    }
+
     #[test]
     fn part_two_example() {
         assert_eq!(
-            Schedule::new(read_input("example_input.txt").as_str()).count_partial_overlaps(),
+            Schedule::new(fixtures::example("example").unwrap().input.as_str()).unwrap().count_partial_overlaps(),
             4
         );
     }
@@ -97,7 +112,7 @@ mod tests {
     #[test]
     fn part_two() {
         assert_eq!(
-            Schedule::new(read_input("input.txt").as_str()).count_partial_overlaps(),
+            Schedule::new(fixtures::input(2022, 4).unwrap().as_str()).unwrap().count_partial_overlaps(),
             928
         );
     }
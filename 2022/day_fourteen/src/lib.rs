@@ -1,124 +1,84 @@
-use std::cmp::{max, min};
-use std::collections::HashMap;
+use std::cmp::max;
+use std::str::FromStr;
+use grid::{BoundingBox, SparseGrid};
+use parsing::{parse_lines, ParseError};
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
 enum Regolith {
     Sand,
     Rock
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-struct Point {
-    x:  i32,
-    y: i32
-}
-
-impl Point {
-    fn new(input: &str) -> Self {
-        let parts : Vec<i32> = input.split(",").map(|number| number.parse::<i32>().unwrap()).collect();
+type Point = grid::Point<i32>;
 
-        Self { x: parts[0], y: parts[1] }
-    }
-
-    fn down(&self) -> Self {
-        Self { x: self.x, y: self.y + 1 }
-    }
+fn parse_point(line: &str, input: &str) -> Result<Point, ParseError> {
+    let (x, y) = parsing::split_once(input, ",")?;
 
-    fn left(&self) -> Self {
-        Self { x: self.x - 1, y: self.y }
-    }
+    Ok(Point::new(parsing::number(line, x)?, parsing::number(line, y)?))
+}
 
-    fn right(&self) -> Self {
-        Self { x: self.x + 1, y: self.y }
-    }
+struct Path {
+    vertices: Vec<Point>
+}
 
-    fn is_above(&self, other: &Point) -> bool {
-        self.y < other.y
-    }
+impl FromStr for Path {
+    type Err = ParseError;
 
-    fn is_below(&self, other: &Point) -> bool {
-        self.y > other.y
-    }
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let vertices = input.split(" -> ").map(|vertex| parse_point(input, vertex)).collect::<Result<Vec<Point>, _>>()?;
 
-    fn is_left_of(&self, other: &Point) -> bool {
-        self.x < other.x
-    }
+        if let Some(segment) = vertices.windows(2).find(|segment| segment[0].x != segment[1].x && segment[0].y != segment[1].y) {
+            return Err(ParseError::new(format!("path from {} to {} isn't horizontal or vertical", segment[0], segment[1])));
+        }
 
-    #[allow(dead_code)]
-    fn is_right_of(&self, other: &Point) -> bool {
-        self.x > other.x
+        Ok(Self { vertices })
     }
 }
 
-struct Path {
-    vertices: Vec<Point>
-}
-
 impl Path {
-    fn new(input: &str) -> Self {
-        Self { vertices: input.split(" -> ").map(Point::new).collect() }
-    }
-
     fn points(&self) -> Vec<Point> {
-        self.vertices.windows(2).map(|segment_points| {
+        self.vertices.windows(2).flat_map(|segment_points| {
             Self::points_between(&segment_points[0], &segment_points[1])
-        }).flatten().collect()
+        }).collect()
     }
 
     fn points_between(start: &Point, end: &Point) -> Vec<Point> {
-        if start.is_above(end) {
-            (start.y..=end.y).map(|y| Point { x: start.x, y }).collect()
-        } else if start.is_below(end) {
-            (end.y..=start.y).map(|y| Point { x: start.x, y }).collect()
-        } else if start.is_left_of(end) {
-            (start.x..=end.x).map(|x| Point { x, y: start.y }).collect()
-        } else {
-            (end.x..=start.x).map(|x| Point { x, y: start.y }).collect()
-        }
+        BoundingBox::containing([*start, *end]).unwrap().points().collect()
     }
 }
 
 struct CaveSystem {
-    stable_points: HashMap<Point, Regolith>
+    stable_points: SparseGrid<Regolith>,
+    floor: Option<i32>
 }
 
 impl CaveSystem {
-    fn new(input: &str) -> Self {
-        let mut stable_points : HashMap<Point, Regolith>  = HashMap::new();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut stable_points : SparseGrid<Regolith> = SparseGrid::new();
 
-        for path in input.lines().map(Path::new) {
+        for path in parse_lines::<Path>(input)? {
             for point in path.points().iter() {
-                stable_points.insert(point.clone(), Regolith::Rock);
+                stable_points.insert(*point, Regolith::Rock);
             }
         }
 
-        Self { stable_points }
+        if stable_points.is_empty() {
+            return Err(ParseError::new("expected at least one rock path"));
+        }
+
+        Ok(Self { stable_points, floor: None })
     }
 
     fn sand_origin() -> Point {
-        Point { x: 500, y: 0 }
+        Point::new(500, 0)
     }
 
     fn fill_with_sand(&mut self, stop_at_floor: bool) {
-        let floor;
         if stop_at_floor {
-            floor = Some(self.floor());
-        } else {
-            floor = None;
+            self.floor = Some(self.floor());
         }
 
-        loop {
-            match self.drop_one_sand(floor) {
-                Some(point) => {
-                    self.stable_points.insert(point, Regolith::Sand);
-                    if point.eq(&Self::sand_origin()) {
-                        return;
-                    }
-                },
-                None => {
-                    return;
-                }
-            }
-        }
+        while self.step() {}
     }
 
     fn regolith_at(&self, point: &Point, optional_floor: Option<i32>) -> Option<&Regolith> {
@@ -157,7 +117,7 @@ impl CaveSystem {
                     }
                 },
                 None => {
-                    if matches!(optional_floor, None) && next_sand.is_below(&self.dimensions().1) {
+                    if optional_floor.is_none() && next_sand.y > self.dimensions().max.y {
                         return None; // going to fall forever, abort!
                     }
                     sand = next_sand; // move down
@@ -170,20 +130,13 @@ impl CaveSystem {
         self.stable_points.values().filter(|regolith| matches!(regolith, Regolith::Sand)).count()
     }
 
-    fn dimensions(&self) -> (Point, Point) {
-        let mut min_x : i32 = 600;
-        let mut min_y : i32 = 0;
-        let mut max_x : i32 = 0;
-        let mut max_y : i32 = 0;
-
-        for &point in self.stable_points.keys() {
-            min_y = min(min_y, point.y);
-            max_y = max(max_y, point.y);
-            min_x = min(min_x, point.x);
-            max_x = max(max_x, point.x);
+    fn dimensions(&self) -> BoundingBox<i32> {
+        let mut bounds = BoundingBox::around(Self::sand_origin());
+        if let Some(stable_bounds) = self.stable_points.bounds() {
+            bounds = bounds.union(&stable_bounds);
         }
 
-        ( Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y } )
+        bounds
     }
 
     fn floor(&self) -> i32 {
@@ -200,35 +153,80 @@ impl CaveSystem {
 
     #[allow(dead_code)]
     fn print(&self) {
-        let (top_left, bottom_right) = self.dimensions();
+        println!("{}", self.render());
+    }
+}
 
-        for row in top_left.y..=(bottom_right.y + 1) {
-            for column in (top_left.x - 1)..=(bottom_right.x + 1) {
-                if row == 0 && column == 500 {
-                    print!("+");
-                    continue;
-                }
-                let point = Point { x: column, y: row };
-                match self.stable_points.get(&point) {
-                    Some(Regolith::Rock) => { print!("#") },
-                    Some(Regolith::Sand) => { print!("o") },
-                    None => { print!(".") }
-                }
-            }
-            println!();
+impl Step for CaveSystem {
+    // Drops one unit of sand, until sand either falls into the abyss or blocks the source.
+    fn step(&mut self) -> bool {
+        if self.stable_points.contains(&Self::sand_origin()) {
+            return false;
+        }
+
+        match self.drop_one_sand(self.floor) {
+            Some(point) => {
+                self.stable_points.insert(point, Regolith::Sand);
+                true
+            },
+            None => false
         }
-        println!();
     }
 }
 
+impl Render for CaveSystem {
+    fn render(&self) -> Frame {
+        let bounds = self.dimensions();
+        let origin = Point::new(bounds.min.x - 1, bounds.min.y);
+
+        Frame::from_fn((bounds.width() + 2) as usize, (bounds.height() + 1) as usize, |column, row| {
+            let point = origin + Point::new(column as i32, row as i32);
+            match self.regolith_at(&point, self.floor) {
+                Some(Regolith::Rock) => '#',
+                Some(Regolith::Sand) => 'o',
+                None if point == Self::sand_origin() => '+',
+                None => '.'
+            }
+        }).with_origin(origin.x, origin.y)
+    }
+
+    fn palette(&self) -> Palette {
+        Palette::new()
+            .with('#', Color::new(128, 128, 128))
+            .with('o', Color::new(230, 190, 90))
+            .with('+', Color::new(220, 60, 40))
+            .with('.', Color::new(50, 50, 50))
+    }
+}
+
+// Records the cave filling up with sand until it starts to fall into the abyss.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    recorder.run(&mut CaveSystem::new(input)?);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut cave_system = CaveSystem::new(input)?;
+    cave_system.fill_with_sand(false);
+
+    Ok(cave_system.units_of_sand())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let mut cave_system = CaveSystem::new(input)?;
+    cave_system.fill_with_sand(true);
+
+    Ok(cave_system.units_of_sand())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let mut cave_system = CaveSystem::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut cave_system = CaveSystem::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         cave_system.fill_with_sand(false);
         cave_system.print();
         assert_eq!(cave_system.units_of_sand(), 24);
@@ -236,15 +234,60 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let mut cave_system = CaveSystem::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut cave_system = CaveSystem::new(fixtures::input(2022, 14).unwrap().as_str()).unwrap();
         cave_system.fill_with_sand(false);
         cave_system.print();
         assert_eq!(cave_system.units_of_sand(), 745);
     }
 
+    #[test]
+    fn render_example() {
+        let mut cave_system = CaveSystem::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
+        cave_system.fill_with_sand(false);
+
+        assert_eq!(
+            cave_system.render().to_string(),
+            concat!(
+                ".......+....\n",
+                "............\n",
+                ".......o....\n",
+                "......ooo...\n",
+                ".....#ooo##.\n",
+                "....o#ooo#..\n",
+                "...###ooo#..\n",
+                ".....oooo#..\n",
+                "..o.ooooo#..\n",
+                ".#########..\n",
+                "............\n"
+            )
+        );
+    }
+
+    #[test]
+    fn animate_example() {
+        let mut recorder = Recorder::new();
+        animate(fixtures::example("example").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 25);
+        assert_eq!(recorder.frames()[0].get(7, 0), Some('+'));
+        assert_eq!(recorder.frames()[1].get(7, 8), Some('o'));
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let error = CaveSystem::new("498,4 -> 498,6\n503,4 -> 502,x").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 14: invalid number 'x'");
+
+        let error = CaveSystem::new("498,4 -> 496,6").err().unwrap();
+        assert_eq!(error.to_string(), "line 1: path from 498,4 to 496,6 isn't horizontal or vertical");
+
+        let error = CaveSystem::new("498;4 -> 498,6").err().unwrap();
+        assert_eq!(error.to_string(), "line 1: expected ',' in '498;4'");
+    }
+
     #[test]
     fn part_two_example() {
-        let mut cave_system = CaveSystem::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut cave_system = CaveSystem::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         cave_system.fill_with_sand(true);
         cave_system.print();
         assert_eq!(cave_system.units_of_sand(), 93);
@@ -252,7 +295,7 @@ mod tests {
 
     #[test]
     fn part_two() {
-        let mut cave_system = CaveSystem::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut cave_system = CaveSystem::new(fixtures::input(2022, 14).unwrap().as_str()).unwrap();
         cave_system.fill_with_sand(true);
         cave_system.print();
         assert_eq!(cave_system.units_of_sand(), 27551);
@@ -1,49 +1,39 @@
-use std::cmp::{max, min};
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::str::FromStr;
+use grid::{BoundingBox, Direction};
+use parsing::{parse_lines, ParseError};
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
-#[derive(Eq,PartialEq,Copy,Clone,Debug,Hash)]
-struct Point {
-    x: i32,
-    y: i32
-}
-
-impl Point {
-    fn origin() -> Self { Self { x: 0, y: 0 } }
-
-    #[allow(dead_code)]
-    fn is_origin(&self) -> bool {
-        self.x == 0 && self.y == 0
-    }
-
-    fn up(&self, delta: usize) -> Self {
-        Self { x: self.x, y: self.y + (delta as i32) }
-    }
+type Point = grid::Point<i32>;
 
-    fn down(&self, delta: usize) -> Self {
-        Self { x: self.x, y: self.y - (delta as i32) }
-    }
+struct Motion {
+    direction: Direction,
+    distance: usize
+}
 
-    fn left(&self, delta: usize) -> Self {
-        Self { x: self.x - (delta as i32), y: self.y }
-    }
+impl FromStr for Motion {
+    type Err = ParseError;
 
-    fn right(&self, delta: usize) -> Self {
-        Self { x: self.x + (delta as i32), y: self.y }
-    }
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (direction, distance) = parsing::split_once(line, " ")?;
+        let direction = match direction.chars().collect::<Vec<char>>()[..] {
+            [letter] => Direction::from_letter(letter),
+            _ => None
+        }.ok_or_else(|| ParseError::at_column(1, format!("unknown direction {direction}")))?;
 
-    fn delta(&self, other: &Point) -> Self {
-        Self { x: self.x - other.x, y: self.y - other.y }
+        Ok(Self { direction, distance: parsing::number(line, distance)? })
     }
 }
 
 struct RopeBridge {
     head_positions: Vec<Point>,
-    tail_positions: Vec<Vec<Point>>
+    tail_positions: Vec<Vec<Point>>,
+    pending_steps: VecDeque<Direction>
 }
 
 impl RopeBridge {
     fn new(size: usize) -> Self {
-        Self { head_positions: vec![Point::origin()], tail_positions: vec![vec![Point::origin()]; size] }
+        Self { head_positions: vec![Point::origin()], tail_positions: vec![vec![Point::origin()]; size], pending_steps: VecDeque::new() }
     }
 
     fn total_tail_positions(&self) -> usize {
@@ -66,44 +56,28 @@ impl RopeBridge {
         self.tail_positions.last().unwrap().last().unwrap()
     }
 
-    fn run_sequence(&mut self, input: &str) {
-        self.print();
-
-        for line in input.lines() {
-            let parts : Vec<&str> = line.split(" ").collect();
-            let delta = parts[1].parse::<usize>().unwrap();
-
-            // println!("{line}");
-            for _ in 0..delta {
-                match parts[0] {
-                    "U" => { self.move_head_up(1); },
-                    "D" => { self.move_head_down(1); },
-                    "L" => { self.move_head_left(1); },
-                    "R" => { self.move_head_right(1); },
-                    _ => panic!("Malformed line: {}", line)
-                }
-                self.print();
-            }
-        }
-    }
+    fn run_sequence(&mut self, input: &str) -> Result<(), ParseError> {
+        self.plan_sequence(input)?;
+        while self.step() {}
 
-    fn move_head_up(&mut self, delta: usize) {
-        self.head_positions.push(self.head().up(delta));
-        self.update_tail_positions();
+        Ok(())
     }
 
-    fn move_head_down(&mut self, delta: usize) {
-        self.head_positions.push(self.head().down(delta));
-        self.update_tail_positions();
-    }
+    // Queues up the head's moves, one step at a time, without making any of them.
+    fn plan_sequence(&mut self, input: &str) -> Result<(), ParseError> {
+        let motions : Vec<Motion> = parse_lines(input)?;
 
-    fn move_head_left(&mut self, delta: usize) {
-        self.head_positions.push(self.head().left(delta));
-        self.update_tail_positions();
+        for motion in motions {
+            for _ in 0..motion.distance {
+                self.pending_steps.push_back(motion.direction);
+            }
+        }
+
+        Ok(())
     }
 
-    fn move_head_right(&mut self, delta: usize) {
-        self.head_positions.push(self.head().right(delta));
+    fn move_head(&mut self, direction: Direction) {
+        self.head_positions.push(self.head().step(direction));
         self.update_tail_positions();
     }
 
@@ -114,27 +88,13 @@ impl RopeBridge {
         for (index, knot_positions) in self.tail_positions.iter_mut().enumerate() {
             old_knot_position = *knot_positions.last().unwrap();
 
-            // dbg!(old_knot_position);
-            // dbg!(new_head_position.delta(&old_knot_position));
+            let delta = new_head_position - old_knot_position;
             new_knot_position =
-                match new_head_position.delta(&old_knot_position) {
-                    Point { x: 0, y: 0 }
-                    | Point { x: 1, y: 0 } | Point { x: -1, y: 0 } | Point { x: 0, y: 1 } | Point { x: 0, y: -1 }
-                    | Point { x: 1, y: 1 } | Point { x: 1, y: -1 } | Point { x: -1, y: 1 } | Point { x: -1, y: -1 } => { old_knot_position.clone() },
-                    Point { x: 0, y: 2 } => { old_knot_position.up(1) },
-                    Point { x: 0, y: -2 } => { old_knot_position.down(1) },
-                    Point { x: 2, y: 0 } => { old_knot_position.right(1) },
-                    Point { x: -2, y: 0 } => { old_knot_position.left(1) },
-                    Point { x: 1, y: 2 } | Point { x: 2, y: 1 } | Point { x: 2, y: 2 } => { old_knot_position.up(1).right(1) },
-                    Point { x: 1, y: -2 } | Point { x: 2, y: -1 } | Point { x: 2, y: -2 } => { old_knot_position.down(1).right(1) },
-                    Point { x: -1, y: 2 } | Point { x: -2, y: 1 } | Point { x: -2, y: 2 } => { old_knot_position.up(1).left(1) },
-                    Point { x: -1, y: -2 } | Point { x: -2, y: -1 } | Point { x: -2, y: -2 } => { old_knot_position.down(1).left(1) },
-                    delta => {
-                        self.print();
-                        dbg!(index + 1);
-                        dbg!(new_head_position);
-                        dbg!(old_knot_position);
-                        panic!("Unexpected delta: {:?}", delta)
+                match (delta.x.abs(), delta.y.abs()) {
+                    (0..=1, 0..=1) => { old_knot_position },
+                    (0..=2, 0..=2) => { old_knot_position + Point::new(delta.x.signum(), delta.y.signum()) },
+                    _ => {
+                        panic!("Unexpected delta for knot {}: {:?} from {:?} to {:?}", index + 1, delta, old_knot_position, new_head_position)
                     }
                 };
             knot_positions.push(new_knot_position);
@@ -143,83 +103,127 @@ impl RopeBridge {
         }
     }
 
-    #[allow(dead_code)]
-    fn dimensions(&self) -> (Point, Point) {
-        let mut min_x = 0;
-        let mut min_y = 0;
-        let mut max_x = 0;
-        let mut max_y = 0;
-
-        for point in self.head_positions.iter() {
-            min_y = min(min_y, point.y);
-            max_y = max(max_y, point.y);
-            min_x = min(min_x, point.x);
-            max_x = max(max_x, point.x);
+    fn dimensions(&self) -> BoundingBox<i32> {
+        let mut bounds = BoundingBox::around(Point::origin());
+
+        for &point in self.head_positions.iter().chain(self.tail_positions.iter().flatten()) {
+            bounds.include(point);
         }
 
-        for knot_positions in self.tail_positions.iter() {
-            for point in knot_positions.iter() {
-                min_y = min(min_y, point.y);
-                max_y = max(max_y, point.y);
-                min_x = min(min_x, point.x);
-                max_x = max(max_x, point.x);
+        bounds.expand(1)
+    }
+
+    // Everywhere the rope has been, unless that is too big to take in, in
+    // which case a window that follows the head around.
+    fn view(&self) -> BoundingBox<i32> {
+        let bounds = self.dimensions();
+        let head = self.head();
+        let span = |min: i32, max: i32, center: i32, size: i32| {
+            if max - min < size {
+                (min, max)
+            } else {
+                (center - size / 2, center - size / 2 + size - 1)
             }
+        };
+        let (min_x, max_x) = span(bounds.min.x, bounds.max.x, head.x, VIEW_WIDTH);
+        let (min_y, max_y) = span(bounds.min.y, bounds.max.y, head.y, VIEW_HEIGHT);
+
+        BoundingBox::new(Point::new(min_x, min_y), Point::new(max_x, max_y))
+    }
+
+    fn knot_symbol(&self, index: usize) -> char {
+        if self.tail_positions.len() == 1 {
+            'T'
+        } else {
+            char::from_digit(index as u32 + 1, 36).unwrap()
+        }
+    }
+}
+
+const VIEW_WIDTH : i32 = 60;
+const VIEW_HEIGHT : i32 = 30;
+
+impl Step for RopeBridge {
+    // Moves the head one step, finishing once every planned motion is done.
+    fn step(&mut self) -> bool {
+        match self.pending_steps.pop_front() {
+            Some(direction) => {
+                self.move_head(direction);
+                true
+            },
+            None => false
         }
+    }
+}
 
-        ( Point { x: min_x - 1, y: min_y - 1 }, Point { x: max_x + 1, y: max_y + 1 } )
+impl Render for RopeBridge {
+    // Knots cover each other in order from the head, and anything covers the
+    // start 's' and the places the tail has visited '#'.
+    fn render(&self) -> Frame {
+        let view = self.view();
+        let knots : Vec<Point> = self.tail_positions.iter().map(|positions| *positions.last().unwrap()).collect();
+        let visited : HashSet<&Point> = self.tail_positions.last().unwrap().iter().filter(|point| view.contains(point)).collect();
+
+        Frame::from_fn(view.width() as usize, view.height() as usize, |column, row| {
+            let point = view.min + Point::new(column as i32, row as i32);
+            if point == *self.head() {
+                'H'
+            } else if let Some(index) = knots.iter().position(|knot| *knot == point) {
+                self.knot_symbol(index)
+            } else if point == Point::origin() {
+                's'
+            } else if visited.contains(&point) {
+                '#'
+            } else {
+                '.'
+            }
+        }).with_origin(view.min.x, view.min.y)
     }
 
-    #[allow(dead_code)]
-    fn print(&self) {
-        // let (bottom_left, top_right) = self.dimensions();
-        //
-        // for row in (bottom_left.y..=top_right.y).rev() {
-        //     for column in bottom_left.x..=top_right.x {
-        //         // dbg!(row, column);
-        //
-        //         let point = Point { x: column, y: row };
-        //         if self.head().eq(&point) {
-        //             print!("H")
-        //         } else {
-        //             let mut has_printed = false;
-        //             for (index, knot_position) in self.tail_positions.iter().map(|knot_positions| knot_positions.last().unwrap()).enumerate() {
-        //                 // dbg!(knot_position);
-        //
-        //                 if point.eq(knot_position) {
-        //                     if self.tail_positions.len() == 1 {
-        //                         print!("T");
-        //                     } else {
-        //                         print!("{}", index + 1);
-        //                     }
-        //                     has_printed = true;
-        //
-        //                     break;
-        //                 }
-        //             }
-        //             if !has_printed {
-        //                 if point.is_origin() {
-        //                     print!("s")
-        //                 } else {
-        //                     print!(".")
-        //                 }
-        //             }
-        //         }
-        //     }
-        //     println!();
-        // }
-        // println!();
+    fn palette(&self) -> Palette {
+        let knot = Color::new(230, 190, 90);
+
+        let palette = Palette::new()
+            .with('H', Color::new(240, 80, 60))
+            .with('s', Color::new(100, 160, 240))
+            .with('#', Color::new(90, 90, 90))
+            .with('.', Color::new(40, 40, 40));
+
+        (0..self.tail_positions.len()).fold(palette, |palette, index| palette.with(self.knot_symbol(index), knot))
     }
 }
 
+// Records each step of the ten knot rope from part two.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let mut bridge = RopeBridge::new(9);
+    bridge.plan_sequence(input)?;
+    recorder.run(&mut bridge);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut bridge = RopeBridge::new(1);
+    bridge.run_sequence(input)?;
+
+    Ok(bridge.total_tail_positions())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let mut bridge = RopeBridge::new(9);
+    bridge.run_sequence(input)?;
+
+    Ok(bridge.total_tail_positions())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs::read_to_string;
     use super::*;
 
     #[test]
     fn part_one_example() {
         let mut bridge = RopeBridge::new(1);
-        bridge.run_sequence(read_to_string("example_input.txt").unwrap().as_str());
+        bridge.run_sequence(fixtures::example("example").unwrap().input.as_str()).unwrap();
 
         assert_eq!(
             bridge.total_tail_positions(),
@@ -230,7 +234,7 @@ mod tests {
     #[test]
     fn part_one() {
         let mut bridge = RopeBridge::new(1);
-        bridge.run_sequence(read_to_string("input.txt").unwrap().as_str());
+        bridge.run_sequence(fixtures::input(2022, 9).unwrap().as_str()).unwrap();
 
         assert_eq!(
             bridge.total_tail_positions(),
@@ -238,10 +242,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn animate_example() {
+        let mut recorder = Recorder::new();
+        animate(fixtures::example("example").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 25);
+        assert_eq!(recorder.frames()[0].to_string(), "...\n.H.\n...\n");
+        assert_eq!(recorder.frames()[2].to_string(), ".....\n.21H.\n.....\n");
+        assert_eq!(
+            recorder.frames()[24].to_string(),
+            concat!(
+                "........\n",
+                "........\n",
+                "........\n",
+                "..1H3...\n",
+                "..5.....\n",
+                ".6......\n",
+                "........\n"
+            )
+        );
+    }
+
+    #[test]
+    fn render_follows_the_head() {
+        let mut bridge = RopeBridge::new(1);
+        bridge.run_sequence("R 100").unwrap();
+        let frame = bridge.render();
+
+        assert_eq!((frame.width(), frame.height()), (VIEW_WIDTH as usize, 3));
+        assert_eq!(frame.get(VIEW_WIDTH as usize / 2, 1), Some('H'));
+        assert_eq!(frame.get(VIEW_WIDTH as usize / 2 - 1, 1), Some('T'));
+        assert_eq!(frame.get(0, 1), Some('#'));
+    }
+
+    #[test]
+    fn invalid_motions() {
+        let mut bridge = RopeBridge::new(1);
+        assert_eq!(bridge.run_sequence("R 4\nX 2").err().unwrap().to_string(), "line 2, column 1: unknown direction X");
+        assert_eq!(bridge.run_sequence("R 4\nU -2").err().unwrap().to_string(), "line 2, column 3: invalid number '-2'");
+    }
+
     #[test]
     fn part_two_example() {
         let mut bridge = RopeBridge::new(9);
-        bridge.run_sequence(read_to_string("example_two_input.txt").unwrap().as_str());
+        bridge.run_sequence(fixtures::example("example_two").unwrap().input.as_str()).unwrap();
 
         assert_eq!(
             bridge.total_tail_positions(),
@@ -252,7 +297,7 @@ mod tests {
     #[test]
     fn part_two() {
         let mut bridge = RopeBridge::new(9);
-        bridge.run_sequence(read_to_string("input.txt").unwrap().as_str());
+        bridge.run_sequence(fixtures::input(2022, 9).unwrap().as_str()).unwrap();
 
         assert_eq!(
             bridge.total_tail_positions(),
@@ -32,13 +32,12 @@ impl BlueprintSet {
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
         assert_eq!(
-            BlueprintSet::new(fs::read_to_string("example_input.txt").unwrap().as_str()).total_quality_level(),
+            BlueprintSet::new(fixtures::example("example").unwrap().input.as_str()).total_quality_level(),
             33
         );
     }
@@ -1,135 +1,335 @@
-use std::cmp::Ordering;
+use std::fmt;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+use parsing::{ParseError, ReadError};
 
 type Snack = u32;
+// Totals are wider than snacks, as an elf can carry any number of them.
+type Calories = u64;
 
-struct Expedition {
+// Elves are kept in the order they were listed, each as a summary of their
+// snacks rather than the snacks themselves.
+#[derive(Clone, Debug)]
+pub struct Expedition {
     elves: Vec<Elf>
 }
 
 impl Expedition {
-    fn new(input: &str) -> Self {
-        let mut elves = vec![];
-        let mut elf = Elf::new();
-
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let mut builder = ExpeditionBuilder::new();
         for line in input.lines() {
-            if line.is_empty() {
-                let previous_elf = elf;
-                elf = Elf::new();
-                elves.push(previous_elf)
-            }
-            else {
-                elf.add_snack(line.parse::<Snack>().unwrap())
+            builder.line(line)?;
+        }
+
+        builder.finish()
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, ReadError> {
+        Ok(ExpeditionBuilder::new().read(reader)?.finish()?)
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    // The first one listed wins a tie.
+    pub fn top_elf(&self) -> &Elf {
+        self.elves.iter().rev().max_by_key(|elf| elf.total_calories()).unwrap()
+    }
+
+    // Keeps only the best `count` elves seen so far on a heap, so this takes
+    // O(n log count) rather than sorting everyone. The result is ordered from
+    // most calories to fewest, with ties in the order the elves were listed.
+    // It is a list rather than an expedition, since it can be empty.
+    pub fn top_elves(&self, count: usize) -> Vec<Elf> {
+        let mut heap : BinaryHeap<Reverse<(Calories, Reverse<usize>)>> = BinaryHeap::with_capacity(count + 1);
+        for (index, elf) in self.elves.iter().enumerate() {
+            heap.push(Reverse((elf.total_calories(), Reverse(index))));
+            if heap.len() > count {
+                heap.pop();
             }
         }
 
-        if !elf.is_empty() {
-            elves.push(elf)
+        heap.into_sorted_vec().into_iter().map(|Reverse((_, Reverse(index)))| self.elves[index].clone()).collect()
+    }
+
+    pub fn total_calories(&self) -> Calories {
+        self.elves.iter().map(Elf::total_calories).sum()
+    }
+
+    // The smallest total at least `percent` of the elves carry no more than,
+    // by the nearest rank. None above 100 percent.
+    pub fn percentile(&self, percent: u8) -> Option<Calories> {
+        if percent > 100 {
+            return None;
         }
 
-        elves.sort();
+        let rank = (percent as usize * self.elves.len()).div_ceil(100).max(1);
+        let mut totals = self.totals();
+        Some(*totals.select_nth_unstable(rank - 1).1)
+    }
 
-        Self { elves }
+    // Halfway between the middle two totals when there's an even number of elves.
+    pub fn median(&self) -> f64 {
+        let mut totals = self.totals();
+        let count = totals.len();
+        let (lower, &mut upper, _) = totals.select_nth_unstable(count / 2);
+        if count.is_multiple_of(2) {
+            (*lower.iter().max().unwrap() as f64 + upper as f64) / 2.0
+        } else {
+            upper as f64
+        }
     }
 
-    fn top_elf(&self) -> &Elf {
-        self.elves.first().unwrap()
+    // How many elves carry totals in each `width` calories, from the bucket
+    // holding the smallest total to the one holding the largest. Buckets with
+    // no elves in between are still listed.
+    pub fn histogram(&self, width: Calories) -> Vec<Bucket> {
+        assert!(width > 0, "buckets must be at least one calorie wide");
+
+        let (lowest, highest) = self.elves.iter().map(Elf::total_calories)
+            .fold((Calories::MAX, 0), |(lowest, highest), total| (lowest.min(total), highest.max(total)));
+        let (first, last) = (lowest / width, highest / width);
+        let mut buckets : Vec<Bucket> = (first..=last)
+            .map(|bucket| Bucket { start: bucket * width, end: bucket.saturating_mul(width).saturating_add(width), elves: 0 })
+            .collect();
+        for elf in &self.elves {
+            buckets[(elf.total_calories() / width - first) as usize].elves += 1;
+        }
+
+        buckets
     }
 
-    fn top_elves(&self, count: usize) -> Self {
-        Self { elves: self.elves.as_slice()[0..count].to_vec() }
+    fn totals(&self) -> Vec<Calories> {
+        self.elves.iter().map(Elf::total_calories).collect()
     }
+}
 
-    fn total_calories(&self) -> Snack {
-        self.elves.iter().map(Elf::total_calories).sum()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Bucket {
+    pub start: Calories,
+    pub end: Calories,
+    pub elves: usize
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}..{}: {}", self.start, self.end, self.elves)
+    }
+}
+
+// Builds an expedition a line at a time, keeping only the running summary of
+// the elf being listed.
+#[derive(Clone, Debug, Default)]
+pub struct ExpeditionBuilder {
+    elves: Vec<Elf>,
+    elf: Option<Elf>,
+    line: usize
+}
+
+impl ExpeditionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read<R: BufRead>(mut self, reader: R) -> Result<Self, ReadError> {
+        parsing::read_lines(reader, |line| self.line(line))?;
+        Ok(self)
+    }
+
+    // A blank line ends the elf being listed.
+    pub fn line(&mut self, line: &str) -> Result<(), ParseError> {
+        self.line += 1;
+        if line.is_empty() {
+            self.end_elf();
+        } else {
+            let snack = parsing::number(line, line).map_err(|error| error.with_line(self.line))?;
+            let number = self.elves.len() + 1;
+            self.elf.get_or_insert_with(|| Elf::new(number)).add_snack(snack);
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Expedition, ParseError> {
+        self.end_elf();
+        if self.elves.is_empty() {
+            return Err(ParseError::new("expected at least one elf"));
+        }
+
+        Ok(Expedition { elves: self.elves })
+    }
+
+    fn end_elf(&mut self) {
+        if let Some(elf) = self.elf.take() {
+            self.elves.push(elf);
+        }
     }
 }
 
-#[derive(Clone)]
-struct Elf {
-    snacks: Vec<Snack>
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Elf {
+    number: usize,
+    snacks: usize,
+    total: Calories,
+    smallest: Snack,
+    largest: Snack
 }
 
 impl Elf {
-    fn new() -> Self {
-        Self { snacks: vec![] }
+    fn new(number: usize) -> Self {
+        Self { number, snacks: 0, total: 0, smallest: Snack::MAX, largest: 0 }
     }
 
     fn add_snack(&mut self, snack: Snack) {
-        self.snacks.push(snack)
+        self.snacks += 1;
+        self.total += snack as Calories;
+        self.smallest = self.smallest.min(snack);
+        self.largest = self.largest.max(snack);
     }
 
-    fn is_empty(&self) -> bool {
-        self.snacks.is_empty()
+    // Where the elf was listed, counting from 1.
+    pub fn number(&self) -> usize {
+        self.number
     }
 
-    fn total_calories(&self) -> Snack {
-        self.snacks.iter().sum()
+    pub fn snacks(&self) -> usize {
+        self.snacks
     }
-}
 
-impl Eq for Elf {}
+    pub fn total_calories(&self) -> Calories {
+        self.total
+    }
 
-impl PartialEq<Self> for Elf {
-    fn eq(&self, other: &Self) -> bool {
-        self.total_calories() == other.total_calories()
+    pub fn smallest_snack(&self) -> Snack {
+        self.smallest
     }
-}
 
-impl PartialOrd<Self> for Elf {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(other.cmp(self))
+    pub fn largest_snack(&self) -> Snack {
+        self.largest
     }
-}
 
-impl Ord for Elf {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.total_calories().cmp(&other.total_calories())
+    pub fn mean_snack(&self) -> f64 {
+        self.total as f64 / self.snacks as f64
     }
 }
 
+pub fn part_one(input: &str) -> Result<Calories, ParseError> {
+    Ok(Expedition::new(input)?.top_elf().total_calories())
+}
+
+pub fn part_two(input: &str) -> Result<Calories, ParseError> {
+    Ok(Expedition::new(input)?.top_elves(3).iter().map(Elf::total_calories).sum())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use crate::*;
 
-    fn example_input() -> &'static str {
-        "1000
-2000
-3000
-
-4000
-
-5000
-6000
-
-7000
-8000
-9000
-
-10000"
+    fn example_input() -> String {
+        fixtures::example("example").unwrap().input
     }
 
     fn full_input() -> String {
-        fs::read_to_string("input.txt").unwrap()
+        fixtures::input(2022, 1).unwrap()
     }
 
     #[test]
     fn part_one_example() {
-        assert_eq!(Expedition::new(example_input()).top_elf().total_calories(), 24_000);
+        assert_eq!(Expedition::new(&example_input()).unwrap().top_elf().total_calories(), 24_000);
     }
 
     #[test]
     fn part_one() {
-        assert_eq!(Expedition::new(full_input().as_str()).top_elf().total_calories(), 68_775);
+        assert_eq!(Expedition::new(full_input().as_str()).unwrap().top_elf().total_calories(), 68_775);
+    }
+
+    #[test]
+    fn invalid_snacks() {
+        assert_eq!(Expedition::new("1000\n\n20OO").err().unwrap().to_string(), "line 3, column 1: invalid number '20OO'");
+        assert_eq!(Expedition::new("\n\n").err().unwrap().to_string(), "expected at least one elf");
     }
 
     #[test]
     fn part_two_example() {
-        assert_eq!(Expedition::new(example_input()).top_elves(3).total_calories(), 45_000);
+        assert_eq!(Expedition::new(&example_input()).unwrap().top_elves(3).iter().map(Elf::total_calories).sum::<u64>(), 45_000);
     }
 
     #[test]
     fn part_two() {
-        assert_eq!(Expedition::new(full_input().as_str()).top_elves(3).total_calories(), 202_585);
+        assert_eq!(Expedition::new(full_input().as_str()).unwrap().top_elves(3).iter().map(Elf::total_calories).sum::<u64>(), 202_585);
+    }
+
+    #[test]
+    fn top_elves() {
+        let expedition = Expedition::new(&example_input()).unwrap();
+        let numbers = |elves: Vec<Elf>| elves.iter().map(Elf::number).collect::<Vec<_>>();
+        assert_eq!(numbers(expedition.top_elves(3)), vec![4, 3, 5]);
+        assert!(expedition.top_elves(0).is_empty());
+        assert_eq!(numbers(expedition.top_elves(10)), vec![4, 3, 5, 1, 2]);
+
+        let tied = Expedition::new("5\n\n7\n\n5\n\n7").unwrap();
+        assert_eq!(tied.top_elf().number(), 2);
+        assert_eq!(numbers(tied.top_elves(3)), vec![2, 4, 1]);
+    }
+
+    #[test]
+    fn percentiles() {
+        let expedition = Expedition::new(&example_input()).unwrap();
+        assert_eq!(expedition.median(), 10_000.0);
+        assert_eq!(expedition.percentile(0), Some(4_000));
+        assert_eq!(expedition.percentile(20), Some(4_000));
+        assert_eq!(expedition.percentile(21), Some(6_000));
+        assert_eq!(expedition.percentile(90), Some(24_000));
+        assert_eq!(expedition.percentile(100), Some(24_000));
+        assert_eq!(expedition.percentile(101), None);
+
+        assert_eq!(Expedition::new("1\n\n4\n\n2\n\n8").unwrap().median(), 3.0);
+        assert_eq!(Expedition::new("7").unwrap().median(), 7.0);
+    }
+
+    #[test]
+    fn histogram() {
+        let buckets = Expedition::new(&example_input()).unwrap().histogram(5_000);
+        let counts : Vec<_> = buckets.iter().map(|bucket| (bucket.start, bucket.elves)).collect();
+        assert_eq!(counts, vec![(0, 1), (5_000, 1), (10_000, 2), (15_000, 0), (20_000, 1)]);
+        assert_eq!(buckets[2].to_string(), "10000..15000: 2");
+
+        assert_eq!(Expedition::new("1\n\n2").unwrap().histogram(1_000), vec![Bucket { start: 0, end: 1_000, elves: 2 }]);
+    }
+
+    #[test]
+    fn snack_statistics() {
+        let expedition = Expedition::new(&example_input()).unwrap();
+        let elf = &expedition.elves()[0];
+        assert_eq!((elf.number(), elf.snacks(), elf.total_calories()), (1, 3, 6_000));
+        assert_eq!((elf.smallest_snack(), elf.largest_snack(), elf.mean_snack()), (1_000, 3_000, 2_000.0));
+
+        let elf = &expedition.elves()[2];
+        assert_eq!((elf.snacks(), elf.smallest_snack(), elf.largest_snack(), elf.mean_snack()), (2, 5_000, 6_000, 5_500.0));
+
+        // Totals can go past what a single snack can hold.
+        let expedition = Expedition::new("4294967295\n4294967295\n\n1").unwrap();
+        assert_eq!(expedition.top_elf().total_calories(), 8_589_934_590);
+        assert_eq!(expedition.total_calories(), 8_589_934_591);
+    }
+
+    #[test]
+    fn streamed_inventory() {
+        let input = full_input();
+        let streamed = Expedition::read(std::io::BufReader::with_capacity(5, input.replace('\n', "\r\n").as_bytes())).unwrap();
+        assert_eq!(streamed.elves(), Expedition::new(&input).unwrap().elves());
+
+        let mut builder = ExpeditionBuilder::new();
+        for line in ["100", "", "", "200", "300"] {
+            builder.line(line).unwrap();
+        }
+        let expedition = builder.finish().unwrap();
+        assert_eq!(expedition.elves().len(), 2);
+        assert_eq!(expedition.top_elf().number(), 2);
+
+        let error = Expedition::read("1\n\nx".as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 1: invalid number 'x'");
     }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use parsing::ParseError;
 
 #[derive(Debug)]
 struct FileEntry {
@@ -30,10 +31,13 @@ impl DirectoryEntry {
         }
     }
 
-    fn extract_parent_path(path: &String) -> String {
+    fn extract_parent_path(path: &str) -> String {
         let mut segments : Vec<&str> = path.split("/").collect();
         segments.pop();
-        segments.join("/")
+        match segments.join("/") {
+            parent if parent.is_empty() => String::from("/"),
+            parent => parent
+        }
     }
 
     fn root() -> Self {
@@ -79,11 +83,11 @@ impl FileSystem {
         }
     }
 
-    fn from_bash_session(input: &str) -> Self {
+    fn from_bash_session(input: &str) -> Result<Self, ParseError> {
         let mut session = BashSession::new();
-        session.run_commands(input);
+        session.run_commands(input)?;
 
-        session.file_system
+        Ok(session.file_system)
     }
 
     fn add_directory(&mut self, parent_directory: &str, new_directory: &str) {
@@ -106,7 +110,7 @@ impl FileSystem {
         let mut size = directory.total_file_size();
 
         for child_dir in directory.children.iter() {
-            size += self.directory_size(DirectoryEntry::build_path(&path_string, &child_dir).as_str())
+            size += self.directory_size(DirectoryEntry::build_path(&path_string, child_dir).as_str())
         }
 
         size
@@ -124,7 +128,7 @@ impl FileSystem {
     fn smaller_folders(&self) -> Vec<usize> {
         self.directory_sizes().iter()
             .filter(|size| **size < 100000)
-            .map(|size| *size)
+            .copied()
             .collect()
     }
 
@@ -168,29 +172,33 @@ impl BashSession {
         }
     }
 
-    fn run_commands(&mut self, input: &str) {
-        let mut lines = input.lines();
+    fn run_commands(&mut self, input: &str) -> Result<(), ParseError> {
+        for (index, line) in input.lines().enumerate() {
+            self.run_command(line).map_err(|error| error.with_line(index + 1))?;
+        }
 
-        while let Some(line) = lines.next() {
-            let parts : Vec<&str> = line.split(" ").collect();
-            match parts[0] {
-                "$" => {
-                    match parts[1] {
-                        "ls" => {},
-                        "cd" => {
-                            self.change_directory(parts[2]);
-                        },
-                        _ => panic!("unknown command: {line}")
-                    }
-                },
-                "dir" => {
-                    self.add_directory(parts[1]);
-                },
-                _ => {
-                    self.add_file(parts[1], parts[0].parse::<usize>().unwrap());
-                }
-            }
+        Ok(())
+    }
+
+    fn run_command(&mut self, line: &str) -> Result<(), ParseError> {
+        match line.split(" ").collect::<Vec<&str>>()[..] {
+            ["$", "ls"] => {},
+            ["$", "cd", directory] => {
+                self.change_directory(directory)?;
+            },
+            ["$", command, ..] => {
+                return Err(ParseError::at_token(line, command, format!("unknown command {command}")));
+            },
+            ["dir", directory] => {
+                self.add_directory(directory);
+            },
+            [size, file_name] => {
+                self.add_file(file_name, parsing::number(line, size)?);
+            },
+            _ => return Err(ParseError::new(format!("unexpected output '{line}'")))
         }
+
+        Ok(())
     }
 
     fn add_directory(&mut self, directory: &str) {
@@ -201,7 +209,7 @@ impl BashSession {
         self.file_system.add_file(&self.current_directory, file_name, file_size)
     }
 
-    fn change_directory(&mut self, directory: &str) {
+    fn change_directory(&mut self, directory: &str) -> Result<(), ParseError> {
         self.current_directory =
             match directory {
                 ".." => {
@@ -209,39 +217,64 @@ impl BashSession {
                 },
                 "/"=> { String::from("/") }
                 _ => {
-                    DirectoryEntry::build_path(&self.current_directory, &directory.to_string())
+                    let path = DirectoryEntry::build_path(&self.current_directory, &directory.to_string());
+                    if !self.file_system.root.contains_key(&path) {
+                        return Err(ParseError::new(format!("no directory {directory} in {}", self.current_directory)));
+                    }
+                    path
                 }
             };
+
+        Ok(())
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(FileSystem::from_bash_session(input)?.smaller_folders().iter().sum())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(FileSystem::from_bash_session(input)?.smallest_folder_size_to_delete())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_examples() {
-        let file_system = FileSystem::from_bash_session(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let file_system = FileSystem::from_bash_session(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(file_system.smaller_folders().iter().sum::<usize>(), 95437);
     }
 
     #[test]
     fn part_one() {
-        let file_system = FileSystem::from_bash_session(fs::read_to_string("input.txt").unwrap().as_str());
+        let file_system = FileSystem::from_bash_session(fixtures::input(2022, 7).unwrap().as_str()).unwrap();
         assert_eq!(file_system.smaller_folders().iter().sum::<usize>(), 1555642);
     }
 
+    #[test]
+    fn invalid_sessions() {
+        let error = FileSystem::from_bash_session("$ cd /\n$ ls\ndir a\n$ rm a").err().unwrap();
+        assert_eq!(error.to_string(), "line 4, column 3: unknown command rm");
+
+        let error = FileSystem::from_bash_session("$ cd /\n$ ls\ndir a\n$ cd b").err().unwrap();
+        assert_eq!(error.to_string(), "line 4: no directory b in /");
+
+        let error = FileSystem::from_bash_session("$ ls\n12k b.txt").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: invalid number '12k'");
+    }
+
     #[test]
     fn part_two_example() {
-        let file_system = FileSystem::from_bash_session(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let file_system = FileSystem::from_bash_session(fixtures::example("example").unwrap().input.as_str()).unwrap();
 
         assert_eq!(file_system.smallest_folder_size_to_delete(), 24933642);
     }
 
     #[test]
     fn part_two() {
-        let file_system = FileSystem::from_bash_session(fs::read_to_string("input.txt").unwrap().as_str());
+        let file_system = FileSystem::from_bash_session(fixtures::input(2022, 7).unwrap().as_str()).unwrap();
 
         assert_eq!(file_system.smallest_folder_size_to_delete(), 5974547);
     }
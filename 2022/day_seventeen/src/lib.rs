@@ -1,25 +1,7 @@
-use std::iter::Cycle;
-use std::slice::Iter;
+use parsing::ParseError;
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
-#[derive(Clone, Debug)]
-struct Point {
-    x: usize,
-    y: usize
-}
-
-impl Point {
-    fn down(&self) -> Self {
-        Self { x: self.x, y: self.y - 1 }
-    }
-
-    fn left(&self) -> Self {
-        Self { x: self.x - 1, y: self.y }
-    }
-
-    fn right(&self) -> Self {
-        Self { x: self.x + 1, y: self.y }
-    }
-}
+type Point = grid::Point<usize>;
 
 enum Shape {
     Dash(Vec<Vec<char>>),
@@ -30,17 +12,14 @@ enum Shape {
 }
 
 impl Shape {
-    fn first() -> Self {
-        Self::dash()
-    }
-
-    fn next(previous: &Self) -> Self {
-        match previous {
-            Self::Dash(_) => Self::plus(),
-            Self::Plus(_) => Self::elbow(),
-            Self::Elbow(_) => Self::pipe(),
-            Self::Pipe(_) => Self::square(),
-            Self::Square(_) => Self::dash(),
+    // Rocks fall in the same order over and over.
+    fn nth(index: usize) -> Self {
+        match index % 5 {
+            0 => Self::dash(),
+            1 => Self::plus(),
+            2 => Self::elbow(),
+            3 => Self::pipe(),
+            _ => Self::square()
         }
     }
 
@@ -111,7 +90,7 @@ impl Shape {
     }
 
     fn top_right(&self, bottom_left: &Point) -> Point {
-        Point { x: bottom_left.x + self.width() - 1, y: bottom_left.y + self.height() - 1 }
+        Point::new(bottom_left.x + self.width() - 1, bottom_left.y + self.height() - 1)
     }
 }
 
@@ -123,58 +102,64 @@ enum Jet {
 
 struct Chamber {
     jets: Vec<Jet>,
-    rows: Vec<Vec<char>>
+    rows: Vec<Vec<char>>,
+    next_jet: usize,
+    rocks_dropped: usize
 }
 
 impl Chamber {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         Self::new_with_reversed_rows(input, vec!["+-------+".chars().collect()])
     }
 
-    fn new_with_reversed_rows(input: &str, rows: Vec<Vec<char>>) -> Self {
-        let jets : Vec<Jet> = input.chars().map(|symbol| {
+    fn new_with_reversed_rows(input: &str, rows: Vec<Vec<char>>) -> Result<Self, ParseError> {
+        let jets : Vec<Jet> = input.chars().enumerate().map(|(column, symbol)| {
             match symbol {
-                '<' => Jet::Left,
-                '>' => Jet::Right,
-                _ => panic!("Unexpected character: {symbol}")
+                '<' => Ok(Jet::Left),
+                '>' => Ok(Jet::Right),
+                _ => Err(ParseError::unexpected_character(symbol, column + 1))
             }
-        }).collect();
+        }).collect::<Result<_, _>>()?;
+
+        if jets.is_empty() {
+            return Err(ParseError::new("expected a jet pattern"));
+        }
 
         let mut rows = rows.clone();
         rows.reverse();
 
-        Self { jets, rows }
+        Ok(Self { jets, rows, next_jet: 0, rocks_dropped: 0 })
     }
 
     fn drop_rocks(&mut self, count: usize) {
-        let owned_jets = self.jets.clone();
-        let mut jet_cycle = owned_jets.iter().cycle();
-        let mut current_rock = Shape::first();
-
         for _ in 0..count {
-            self.drop_rock(&current_rock, &mut jet_cycle);
-            current_rock = Shape::next(&current_rock);
+            self.step();
         }
     }
 
-    fn drop_rock(&mut self, rock: &Shape, jet_cycle: &mut Cycle<Iter<Jet>>) {
+    fn next_jet(&mut self) -> Jet {
+        let jet = self.jets[self.next_jet].clone();
+        self.next_jet = (self.next_jet + 1) % self.jets.len();
+
+        jet
+    }
+
+    fn drop_rock(&mut self, rock: &Shape) {
         self.ensure_space_for_new_rock(rock);
 
-        let mut bottom_left = Point { x: 3, y: self.rock_height() + 4 };
+        let mut bottom_left = Point::new(3, self.rock_height() + 4);
 
         self.blit_rock(rock, &bottom_left);
-        // self.print();
         loop {
-            match jet_cycle.next() {
-                None => panic!("Jets ran out of steam!"),
-                Some(Jet::Left) => {
+            match self.next_jet() {
+                Jet::Left => {
                     if self.can_move_left(rock, &bottom_left) {
                         self.blank_rock(rock, &bottom_left);
                         bottom_left = bottom_left.left();
                         self.blit_rock(rock, &bottom_left);
                     }
                 },
-                Some(Jet::Right) => {
+                Jet::Right => {
                     if self.can_move_right(rock, &bottom_left) {
                         self.blank_rock(rock, &bottom_left);
                         bottom_left = bottom_left.right();
@@ -183,15 +168,13 @@ impl Chamber {
                 }
             }
 
-            // self.print();
             if self.can_move_down(rock, &bottom_left) {
                 self.blank_rock(rock, &bottom_left);
-                bottom_left = bottom_left.down();
+                // rows are stored bottom-up, so falling moves towards row zero
+                bottom_left = bottom_left.up();
                 self.blit_rock(rock, &bottom_left);
-                // self.print();
             } else {
                 self.halt_rock(rock, &bottom_left);
-                // self.print();
                 return;
             }
         }
@@ -226,8 +209,8 @@ impl Chamber {
     fn can_move_down(&self, rock: &Shape, bottom_left: &Point) -> bool {
         let top_right = rock.top_right(bottom_left);
         for row_pair in self.rows[(bottom_left.y - 1)..=top_right.y].windows(2) {
-            for column in 0..=8 {
-                if row_pair[1][column] == '@' && !(row_pair[0][column] == '.' || row_pair[0][column] == '@') {
+            for (below, above) in row_pair[0].iter().zip(row_pair[1].iter()).take(9) {
+                if *above == '@' && !(*below == '.' || *below == '@') {
                     return false
                 }
             }
@@ -243,13 +226,7 @@ impl Chamber {
                 if rock.unwrap()[row_index][column_index] == '.' {
                     continue;
                 }
-                if row >= self.rows.len() {
-                    self.print();
-                    dbg!(bottom_left, &top_right, row, row_index, column, column_index);
-                }
                 if self.rows[row][column] != '.' {
-                    self.print();
-                    dbg!(bottom_left, &top_right, row, row_index, column, column_index);
                     panic!("About to blow a hole in the universe! {}", self.rows[row][column])
                 }
                 self.rows[row][column] = '@';
@@ -257,11 +234,14 @@ impl Chamber {
         }
     }
 
+    // Only the falling rock is lifted, not settled rock inside its outline.
     fn blank_rock(&mut self, rock: &Shape, bottom_left: &Point) {
         let top_right = rock.top_right(bottom_left);
         for row in bottom_left.y..=top_right.y {
             for column in bottom_left.x..=top_right.x {
-                self.rows[row][column] = '.';
+                if self.rows[row][column] == '@' {
+                    self.rows[row][column] = '.';
+                }
             }
         }
     }
@@ -270,19 +250,20 @@ impl Chamber {
         let top_right = rock.top_right(bottom_left);
         for row in bottom_left.y..=top_right.y {
             for column in bottom_left.x..=top_right.x {
-                match self.rows[row][column] {
-                    '@' => { self.rows[row][column] = '#' },
-                    _ => {}
+                if self.rows[row][column] == '@' {
+                    self.rows[row][column] = '#';
                 }
             }
         }
     }
 
-    fn print(&self) {
-        for (index, row) in self.rows.iter().enumerate().rev() {
-            println!("{:4} {}", index, String::from_iter(row.iter()))
-        }
-        println!();
+    // The top `height` rows of the chamber, labelled with their row numbers.
+    fn view(&self, height: usize) -> Frame {
+        let rows : Vec<String> = self.rows.iter().enumerate().rev().take(height)
+            .map(|(index, row)| format!("{:4} {}", index, String::from_iter(row.iter())))
+            .collect();
+
+        Frame::parse(&rows.join("\n"))
     }
 
     fn ensure_space_for_new_rock(&mut self, rock: &Shape) {
@@ -313,9 +294,48 @@ impl Chamber {
     }
 }
 
+// The tower gets tall, so frames only show the top of the chamber.
+const VIEW_HEIGHT : usize = 30;
+
+impl Step for Chamber {
+    // Drops the next rock. There are always more rocks, so this never finishes.
+    fn step(&mut self) -> bool {
+        self.drop_rock(&Shape::nth(self.rocks_dropped));
+        self.rocks_dropped += 1;
+
+        true
+    }
+}
+
+impl Render for Chamber {
+    fn render(&self) -> Frame {
+        self.view(VIEW_HEIGHT).with_caption(format!("{} rock(s), tower height {}", self.rocks_dropped, self.rock_height()))
+    }
+
+    fn palette(&self) -> Palette {
+        Palette::new()
+            .with('#', Color::new(150, 150, 170))
+            .with('@', Color::new(240, 140, 40))
+            .with('.', Color::new(50, 50, 50))
+    }
+}
+
+// Records the first 2022 rocks falling into the chamber.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    recorder.run_for(&mut Chamber::new(input.trim())?, 2022);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut chamber = Chamber::new(input.trim())?;
+    chamber.drop_rocks(2022);
+
+    Ok(chamber.rock_height())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
@@ -327,7 +347,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(!chamber.can_move_left(&Shape::dash(), &Point { x: 1, y: 2 }));
         assert!(chamber.can_move_right(&Shape::dash(), &Point { x: 1, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::dash(), &Point { x: 1, y: 2 }));
@@ -343,7 +363,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::dash(), &Point { x: 2, y: 2 }));
         assert!(chamber.can_move_right(&Shape::dash(), &Point { x: 2, y: 2 }));
         assert!(chamber.can_move_down(&Shape::dash(), &Point { x: 2, y: 2 }));
@@ -358,7 +378,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::dash(), &Point { x: 4, y: 2 }));
         assert!(!chamber.can_move_right(&Shape::dash(), &Point { x: 4, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::dash(), &Point { x: 4, y: 2 }));
@@ -375,7 +395,7 @@ mod tests {
                 "|...#...|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(!chamber.can_move_left(&Shape::plus(), &Point { x: 1, y: 2 }));
         assert!(chamber.can_move_right(&Shape::plus(), &Point { x: 1, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::plus(), &Point { x: 1, y: 2 }));
@@ -392,7 +412,7 @@ mod tests {
                 "|...#...|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::plus(), &Point { x: 3, y: 2 }));
         assert!(chamber.can_move_right(&Shape::plus(), &Point { x: 3, y: 2 }));
         assert!(chamber.can_move_down(&Shape::plus(), &Point { x: 3, y: 2 }));
@@ -409,7 +429,7 @@ mod tests {
                 "|...#...|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(!chamber.can_move_left(&Shape::plus(), &Point { x: 4, y: 2 }));
         assert!(!chamber.can_move_right(&Shape::plus(), &Point { x: 4, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::plus(), &Point { x: 4, y: 2 }));
@@ -426,7 +446,7 @@ mod tests {
                 "|...#...|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::plus(), &Point { x: 4, y: 2 }));
         assert!(chamber.can_move_right(&Shape::plus(), &Point { x: 4, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::plus(), &Point { x: 4, y: 2 }));
@@ -443,7 +463,7 @@ mod tests {
                 "|...#...|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::plus(), &Point { x: 5, y: 2 }));
         assert!(chamber.can_move_right(&Shape::plus(), &Point { x: 5, y: 2 }));
         assert!(chamber.can_move_down(&Shape::plus(), &Point { x: 5, y: 2 }));
@@ -460,7 +480,7 @@ mod tests {
                 "|...#...|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::plus(), &Point { x: 6, y: 2 }));
         assert!(!chamber.can_move_right(&Shape::plus(), &Point { x: 6, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::plus(), &Point { x: 6, y: 2 }));
@@ -477,7 +497,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(!chamber.can_move_left(&Shape::elbow(), &Point { x: 1, y: 2 }));
         assert!(chamber.can_move_right(&Shape::elbow(), &Point { x: 1, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::elbow(), &Point { x: 1, y: 2 }));
@@ -494,7 +514,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::elbow(), &Point { x: 2, y: 2 }));
         assert!(chamber.can_move_right(&Shape::elbow(), &Point { x: 2, y: 2 }));
         assert!(chamber.can_move_down(&Shape::elbow(), &Point { x: 2, y: 2 }));
@@ -511,7 +531,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(!chamber.can_move_left(&Shape::elbow(), &Point { x: 2, y: 2 }));
         assert!(!chamber.can_move_right(&Shape::elbow(), &Point { x: 2, y: 2 }));
         assert!(chamber.can_move_down(&Shape::elbow(), &Point { x: 2, y: 2 }));
@@ -528,7 +548,7 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(!chamber.can_move_left(&Shape::elbow(), &Point { x: 2, y: 2 }));
         assert!(!chamber.can_move_right(&Shape::elbow(), &Point { x: 2, y: 2 }));
         assert!(chamber.can_move_down(&Shape::elbow(), &Point { x: 2, y: 2 }));
@@ -545,24 +565,56 @@ mod tests {
                 "|#.....#|".chars().collect(),
                 "+-------+".chars().collect()
             ]
-        );
+        ).unwrap();
         assert!(chamber.can_move_left(&Shape::elbow(), &Point { x: 5, y: 2 }));
         assert!(!chamber.can_move_right(&Shape::elbow(), &Point { x: 5, y: 2 }));
         assert!(!chamber.can_move_down(&Shape::elbow(), &Point { x: 5, y: 2 }));
     }
 
+    #[test]
+    fn invalid_jets() {
+        assert_eq!(Chamber::new("<<>^>").err().unwrap().to_string(), "column 4: unexpected character '^'");
+        assert_eq!(Chamber::new("").err().unwrap().to_string(), "expected a jet pattern");
+    }
+
+    #[test]
+    fn render_example() {
+        let mut chamber = Chamber::new(fixtures::example("example").unwrap().input.trim()).unwrap();
+        chamber.drop_rocks(2);
+        let frame = chamber.render();
+        let text = frame.to_string();
+
+        assert_eq!(frame.caption(), Some("2 rock(s), tower height 4"));
+        assert!(text.ends_with(concat!(
+            "   4 |...#...|\n",
+            "   3 |..###..|\n",
+            "   2 |...#...|\n",
+            "   1 |..####.|\n",
+            "   0 +-------+\n"
+        )), "{text}");
+    }
+
+    #[test]
+    fn animate_example() {
+        let mut recorder = Recorder::new().every(100);
+        animate(fixtures::example("example").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 22);
+        assert_eq!(recorder.frames().last().unwrap().caption(), Some("2022 rock(s), tower height 3068"));
+        assert!(recorder.frames().iter().all(|frame| frame.height() <= VIEW_HEIGHT));
+    }
+
     #[test]
     fn part_one_example() {
-        let mut chamber = Chamber::new(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>");
+        let mut chamber = Chamber::new(fixtures::example("example").unwrap().input.trim()).unwrap();
         chamber.drop_rocks(2022);
         assert_eq!(chamber.rock_height(), 3068);
     }
 
     #[test]
     fn part_one() {
-        let mut chamber = Chamber::new(fs::read_to_string("input.txt").unwrap().trim());
+        let mut chamber = Chamber::new(fixtures::input(2022, 17).unwrap().trim()).unwrap();
         chamber.drop_rocks(2022);
-        assert_eq!(chamber.rock_height(), 3176); // too low
-        assert_eq!(true, false);
+        assert_eq!(chamber.rock_height(), 3219);
     }
 }
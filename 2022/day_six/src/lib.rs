@@ -1,12 +1,18 @@
 use std::collections::HashSet;
+use parsing::ParseError;
 
 struct DataStream {
     bytes: Vec<char>
 }
 
 impl DataStream {
-    fn new(input: &str) -> Self {
-        Self { bytes: input.chars().collect() }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let input = input.trim_end();
+        if let Some((column, byte)) = input.chars().enumerate().find(|(_column, byte)| !byte.is_ascii_lowercase()) {
+            return Err(ParseError::unexpected_character(byte, column + 1));
+        }
+
+        Ok(Self { bytes: input.chars().collect() })
     }
 
     fn first_packet_byte_number(&self) -> usize {
@@ -30,41 +36,53 @@ impl DataStream {
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(DataStream::new(input)?.first_packet_byte_number())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(DataStream::new(input)?.first_message_byte_number())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_examples() {
-        assert_eq!(DataStream::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb").first_packet_byte_number(), 7);
-        assert_eq!(DataStream::new("bvwbjplbgvbhsrlpgdmjqwftvncz").first_packet_byte_number(), 5);
-        assert_eq!(DataStream::new("nppdvjthqldpwncqszvftbrmjlhg").first_packet_byte_number(), 6);
-        assert_eq!(DataStream::new("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").first_packet_byte_number(), 10);
-        assert_eq!(DataStream::new("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").first_packet_byte_number(), 11);
+        assert_eq!(DataStream::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap().first_packet_byte_number(), 7);
+        assert_eq!(DataStream::new("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap().first_packet_byte_number(), 5);
+        assert_eq!(DataStream::new("nppdvjthqldpwncqszvftbrmjlhg").unwrap().first_packet_byte_number(), 6);
+        assert_eq!(DataStream::new("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").unwrap().first_packet_byte_number(), 10);
+        assert_eq!(DataStream::new("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").unwrap().first_packet_byte_number(), 11);
     }
 
     #[test]
     fn part_one() {
         assert_eq!(
-            DataStream::new(fs::read_to_string("input.txt").unwrap().as_str()).first_packet_byte_number(),
+            DataStream::new(fixtures::input(2022, 6).unwrap().as_str()).unwrap().first_packet_byte_number(),
             1034
         )
     }
 
+    #[test]
+    fn invalid_streams() {
+        assert_eq!(DataStream::new("mjqj pqmg\n").err().unwrap().to_string(), "column 5: unexpected character ' '");
+    }
+
     #[test]
     fn part_two_examples() {
-        assert_eq!(DataStream::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb").first_message_byte_number(), 19);
-        assert_eq!(DataStream::new("bvwbjplbgvbhsrlpgdmjqwftvncz").first_message_byte_number(), 23);
-        assert_eq!(DataStream::new("nppdvjthqldpwncqszvftbrmjlhg").first_message_byte_number(), 23);
-        assert_eq!(DataStream::new("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").first_message_byte_number(), 29);
-        assert_eq!(DataStream::new("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").first_message_byte_number(), 26);
+        assert_eq!(DataStream::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap().first_message_byte_number(), 19);
+        assert_eq!(DataStream::new("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap().first_message_byte_number(), 23);
+        assert_eq!(DataStream::new("nppdvjthqldpwncqszvftbrmjlhg").unwrap().first_message_byte_number(), 23);
+        assert_eq!(DataStream::new("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").unwrap().first_message_byte_number(), 29);
+        assert_eq!(DataStream::new("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").unwrap().first_message_byte_number(), 26);
     }
 
     #[test]
     fn part_two() {
         assert_eq!(
-            DataStream::new(fs::read_to_string("input.txt").unwrap().as_str()).first_message_byte_number(),
+            DataStream::new(fixtures::input(2022, 6).unwrap().as_str()).unwrap().first_message_byte_number(),
             2472
         )
     }
@@ -0,0 +1,11 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use y2022_day_sixteen::valve_distances;
+
+fn search(c: &mut Criterion) {
+    let input = fixtures::input(2022, 16).unwrap();
+
+    c.bench_function("day 16 valve distances", |b| b.iter(|| valve_distances(&input)));
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
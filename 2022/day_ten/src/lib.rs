@@ -1,19 +1,39 @@
-struct CPU {
+use std::str::FromStr;
+use parsing::{parse_lines, ParseError};
+
+enum Instruction {
+    Noop,
+    AddX(i32)
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        match line.split(" ").collect::<Vec<&str>>()[..] {
+            ["noop"] => Ok(Self::Noop),
+            ["addx", value] => Ok(Self::AddX(parsing::number(line, value)?)),
+            _ => Err(ParseError::new(format!("unknown instruction '{line}'")))
+        }
+    }
+}
+
+struct Cpu {
     register_x_values: Vec<i32>
 }
 
-impl CPU {
+impl Cpu {
     fn new() -> Self { Self { register_x_values: vec![1] } }
 
-    fn run_program(&mut self, input: &str) {
-        for line in input.lines() {
-            let parts : Vec<&str> = line.split(" ").collect();
-            match parts[0] {
-                "noop" => { self.noop(); },
-                "addx" => { self.add_x(parts[1].parse().unwrap()); },
-                _ => panic!("Unknown instruction: {line}")
+    fn run_program(&mut self, input: &str) -> Result<(), ParseError> {
+        for instruction in parse_lines(input)? {
+            match instruction {
+                Instruction::Noop => { self.noop(); },
+                Instruction::AddX(value) => { self.add_x(value); }
             }
         }
+
+        Ok(())
     }
 
     fn x_value_at(&self, cycle: usize) -> i32 {
@@ -68,42 +88,62 @@ impl CPU {
     }
 }
 
+pub fn part_one(input: &str) -> Result<i32, ParseError> {
+    let mut cpu = Cpu::new();
+    cpu.run_program(input)?;
+
+    Ok(cpu.signal_samples_over_time())
+}
+
+pub fn part_two(input: &str) -> Result<String, ParseError> {
+    let mut cpu = Cpu::new();
+    cpu.run_program(input)?;
+
+    Ok(cpu.print_to_crt())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let mut cpu = CPU::new();
-        cpu.run_program(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut cpu = Cpu::new();
+        cpu.run_program(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(cpu.signal_samples_over_time(), 13140);
     }
 
     #[test]
     fn part_one() {
-        let mut cpu = CPU::new();
-        cpu.run_program(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut cpu = Cpu::new();
+        cpu.run_program(fixtures::input(2022, 10).unwrap().as_str()).unwrap();
         assert_eq!(cpu.signal_samples_over_time(), 11720);
     }
 
+    #[test]
+    fn invalid_programs() {
+        let mut cpu = Cpu::new();
+        assert_eq!(cpu.run_program("noop\naddx 3\nmulx 2").err().unwrap().to_string(), "line 3: unknown instruction 'mulx 2'");
+        assert_eq!(cpu.run_program("addx three").err().unwrap().to_string(), "line 1, column 6: invalid number 'three'");
+    }
+
     #[test]
     fn part_two_example() {
-        let mut cpu = CPU::new();
-        cpu.run_program(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut cpu = Cpu::new();
+        cpu.run_program(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(
             cpu.print_to_crt(),
-            fs::read_to_string("example_output.txt").unwrap().trim_end()
+            fixtures::example("example").unwrap().part_two.unwrap()
         );
     }
 
     #[test]
     fn part_two() {
-        let mut cpu = CPU::new();
-        cpu.run_program(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut cpu = Cpu::new();
+        cpu.run_program(fixtures::input(2022, 10).unwrap().as_str()).unwrap();
         assert_eq!(
             cpu.print_to_crt(),
-            fs::read_to_string("output.txt").unwrap().trim_end()
+            fixtures::answer(2022, 10, 2).unwrap().unwrap()
         );
     }
 }
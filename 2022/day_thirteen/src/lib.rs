@@ -1,5 +1,8 @@
 use std::borrow::Borrow;
 use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+use parsing::ParseError;
 
 #[derive(Clone, Eq, PartialEq)]
 enum PacketPart {
@@ -7,68 +10,67 @@ enum PacketPart {
     List(Vec<PacketPart>)
 }
 
-impl PacketPart {
-    fn new(input: &str) -> Self {
-        let mut chars : Vec<char> = input.chars().rev().collect();
+type Characters<'a> = Peekable<CharIndices<'a>>;
 
-        Self::parse_list(chars.as_mut())
-    }
+impl FromStr for PacketPart {
+    type Err = ParseError;
 
-    fn number_from_digit(digit: char) -> u8 {
-        digit as u8 - '0' as u8
-    }
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut chars = input.char_indices().peekable();
+        let packet = Self::parse_list(input, &mut chars)?;
 
-    fn parse_single_digit_number(digit: char) -> Self {
-        Self::Number(Self::number_from_digit(digit))
+        match chars.next() {
+            Some((index, current)) => Err(ParseError::unexpected_character(current, index + 1)),
+            None => Ok(packet)
+        }
     }
+}
 
-    fn parse_double_digit_number(first: char, second: char) -> Self {
-        Self::Number(Self::number_from_digit(first) * 10 + Self::number_from_digit(second))
+impl PacketPart {
+    fn unexpected(current: Option<(usize, char)>) -> ParseError {
+        match current {
+            Some((index, current)) => ParseError::unexpected_character(current, index + 1),
+            None => ParseError::new("unexpected end of packet")
+        }
     }
 
-    fn parse_list(chars: &mut Vec<char>) -> Self {
-        // dbg!(&chars);
+    fn parse_number(input: &str, chars: &mut Characters) -> Result<Self, ParseError> {
+        let start = chars.peek().map_or(input.len(), |&(index, _)| index);
+        let mut end = start;
+        while let Some(&(index, '0'..='9')) = chars.peek() {
+            end = index + 1;
+            chars.next();
+        }
+
+        Ok(Self::Number(parsing::number(input, &input[start..end])?))
+    }
 
+    fn parse_list(input: &str, chars: &mut Characters) -> Result<Self, ParseError> {
         let mut list : Vec<Self> = vec![];
-        let mut current = chars.pop().unwrap();
 
-        if current != '[' {
-            panic!("Expected '[' got: '{current}'")
+        match chars.next() {
+            Some((_, '[')) => {},
+            current => return Err(Self::unexpected(current))
         }
 
-        while !chars.is_empty() {
-            current = *chars.last().unwrap();
-            match current {
-                '0'..='9' => {
-                    let first_digit = chars.pop().unwrap();
-                    current = *chars.last().unwrap();
-                    match current {
-                        '0'..='9' => {
-                            list.push(Self::parse_double_digit_number(first_digit, chars.pop().unwrap()))
-                        },
-                        ',' | ']' => {
-                            list.push(Self::parse_single_digit_number(first_digit))
-                        },
-                        _ => panic!("Unexpected char: {current}")
-                    }
+        if let Some((_, ']')) = chars.peek() {
+            chars.next();
+            return Ok(Self::List(list)) // current list is blank
+        }
 
-                }, // simple number
-                '[' => { list.push(Self::parse_list(chars)) }, // start of nested list
-                ']' => {
-                    chars.pop();
-                    return Self::List(list)
-                }, // current list is blank
-                _ => panic!("Unexpected char: {current}")
+        loop {
+            match chars.peek().copied() {
+                Some((_, '0'..='9')) => { list.push(Self::parse_number(input, chars)?) }, // simple number
+                Some((_, '[')) => { list.push(Self::parse_list(input, chars)?) }, // start of nested list
+                current => return Err(Self::unexpected(current))
             }
-            current = chars.pop().unwrap();
-            match current {
-                ',' => {}, // another item in list
-                ']' => { return Self::List(list) }, // end of current list
-                _ => panic!("unexpected char: {current}")
+
+            match chars.next() {
+                Some((_, ',')) => {}, // another item in list
+                Some((_, ']')) => { return Ok(Self::List(list)) }, // end of current list
+                current => return Err(Self::unexpected(current))
             }
         }
-
-        panic!("Unexpected end of input!")
     }
 }
 
@@ -78,14 +80,12 @@ struct PacketPair {
 }
 
 impl PacketPair {
-    fn new(input: &str) -> Self {
-        let mut packets : Vec<PacketPart> = input.lines().map(|line| PacketPart::new(line)).collect();
-        if let PacketPart::List(right) = packets.pop().unwrap() {
-            if let PacketPart::List(left) = packets.pop().unwrap() {
-                return Self { left, right }
-            }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let packets : Vec<PacketPart> = parsing::parse_lines(input)?;
+        match <[PacketPart; 2]>::try_from(packets) {
+            Ok([PacketPart::List(left), PacketPart::List(right)]) => Ok(Self { left, right }),
+            _ => Err(ParseError::new(format!("expected a pair of packets, found:\n{input}")))
         }
-        panic!("Failed to parse input:\n{input}")
     }
 
     fn to_ord(&self) -> Ordering {
@@ -104,7 +104,7 @@ impl PacketPair {
         }
     }
 
-    fn lists_are_in_order(left: &Vec<PacketPart>, right: &Vec<PacketPart>) -> Option<bool> {
+    fn lists_are_in_order(left: &[PacketPart], right: &[PacketPart]) -> Option<bool> {
         let mut index = 0;
 
         while index < left.len() && index < right.len() {
@@ -121,9 +121,8 @@ impl PacketPair {
                         },
                         PacketPart::List(right_items) => {
                             let left_items : Vec<PacketPart> = vec![PacketPart::Number(*left_item)];
-                            match Self::lists_are_in_order(&left_items, right_items) {
-                                Some(success) => { return Some(success) },
-                                None => {}
+                            if let Some(success) = Self::lists_are_in_order(&left_items, right_items) {
+                                return Some(success)
                             }
                         }
                     }
@@ -132,15 +131,13 @@ impl PacketPair {
                     match right[index].borrow() {
                         PacketPart::Number(right_item) => {
                             let right_items : Vec<PacketPart> = vec![PacketPart::Number(*right_item)];
-                            match Self::lists_are_in_order(left_items, &right_items) {
-                                Some(success) => { return Some(success) },
-                                None => {}
+                            if let Some(success) = Self::lists_are_in_order(left_items, &right_items) {
+                                return Some(success)
                             }
                         },
                         PacketPart::List(right_items) => {
-                            match Self::lists_are_in_order(left_items, right_items) {
-                                Some(success) => { return Some(success) },
-                                None => {}
+                            if let Some(success) = Self::lists_are_in_order(left_items, right_items) {
+                                return Some(success)
                             }
                         }
                     }
@@ -165,8 +162,16 @@ struct DistressSignalDiagnostic {
 }
 
 impl DistressSignalDiagnostic {
-    fn new(input: &str) -> Self {
-        Self { packet_pairs: input.split("\n\n").map(|pair_input| PacketPair::new(pair_input)).collect() }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut packet_pairs = vec![];
+        let mut lines_read = 0;
+
+        for pair in input.split("\n\n") {
+            packet_pairs.push(PacketPair::new(pair).map_err(|error| error.after_lines(lines_read))?);
+            lines_read += pair.lines().count() + 1;
+        }
+
+        Ok(Self { packet_pairs })
     }
 
     fn diagnostic_code(&self) -> usize {
@@ -179,11 +184,14 @@ struct DistressSignal {
 }
 
 impl DistressSignal {
-    fn new(input: &str) -> Self {
-        let mut packets : Vec<PacketPart> = input.lines().filter(|line| !line.is_empty()).map(|line| PacketPart::new(line)).collect();
-
-        packets.push(PacketPart::new("[[2]]"));
-        packets.push(PacketPart::new("[[6]]"));
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut packets : Vec<PacketPart> = input.lines().enumerate()
+            .filter(|(_index, line)| !line.is_empty())
+            .map(|(index, line)| line.parse().map_err(|error: ParseError| error.with_line(index + 1)))
+            .collect::<Result<_, _>>()?;
+
+        packets.push(Self::divider_packet("[[2]]"));
+        packets.push(Self::divider_packet("[[6]]"));
         packets.sort_by(|left_part, right_part| {
             match left_part {
                 PacketPart::List(left_items) => {
@@ -201,7 +209,11 @@ impl DistressSignal {
             }
         });
 
-        Self { packets }
+        Ok(Self { packets })
+    }
+
+    fn divider_packet(input: &str) -> PacketPart {
+        input.parse().unwrap()
     }
 
     fn decoder_key(&self) -> usize {
@@ -209,36 +221,66 @@ impl DistressSignal {
     }
 
     fn position_of_packet(&self, input: &str) -> usize {
-        self.packets.iter().position(|packet| packet.eq(&PacketPart::new(input))).unwrap() + 1
+        self.packets.iter().position(|packet| packet.eq(&Self::divider_packet(input))).unwrap() + 1
     }
 }
 
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(DistressSignalDiagnostic::new(input)?.diagnostic_code())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(DistressSignal::new(input)?.decoder_key())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let signal = DistressSignalDiagnostic::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let signal = DistressSignalDiagnostic::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(signal.diagnostic_code(), 13);
     }
 
     #[test]
     fn part_one() {
-        let signal = DistressSignalDiagnostic::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let signal = DistressSignalDiagnostic::new(fixtures::input(2022, 13).unwrap().as_str()).unwrap();
         assert_eq!(signal.diagnostic_code(), 6187);
     }
 
+    #[test]
+    fn packets() {
+        assert!("[1,[2,[3,[4,[5,6,7]]]],8,9]".parse::<PacketPart>().is_ok());
+        assert!("[[10],[]]".parse::<PacketPart>() == Ok(PacketPart::List(vec![
+            PacketPart::List(vec![PacketPart::Number(10)]),
+            PacketPart::List(vec![])
+        ])));
+    }
+
+    #[test]
+    fn invalid_packets() {
+        assert_eq!("[1,2;3]".parse::<PacketPart>().err().unwrap().to_string(), "column 5: unexpected character ';'");
+        assert_eq!("[1,[2]".parse::<PacketPart>().err().unwrap().to_string(), "unexpected end of packet");
+        assert_eq!("[1]]".parse::<PacketPart>().err().unwrap().to_string(), "column 4: unexpected character ']'");
+        assert_eq!("[256]".parse::<PacketPart>().err().unwrap().to_string(), "column 2: invalid number '256'");
+
+        let error = DistressSignalDiagnostic::new("[1]\n[2]\n\n[3]\n[x]").err().unwrap();
+        assert_eq!(error.to_string(), "line 5, column 2: unexpected character 'x'");
+
+        let error = DistressSignalDiagnostic::new("[1]\n[2]\n\n[3]").err().unwrap();
+        assert_eq!(error.to_string(), "expected a pair of packets, found:\n[3]");
+    }
+
     #[test]
     fn part_two_example() {
-        let signal = DistressSignal::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let signal = DistressSignal::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(signal.decoder_key(), 140);
     }
 
     #[test]
     fn part_two() {
-        let signal = DistressSignal::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let signal = DistressSignal::new(fixtures::input(2022, 13).unwrap().as_str()).unwrap();
         assert_eq!(signal.decoder_key(), 23520);
     }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 use std::iter::Iterator;
+use std::str::FromStr;
+use parsing::{parse_lines, ParseError};
 
 const ALPHABET : &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
@@ -20,7 +22,7 @@ fn union<'a>(left: &'a HashSet<char>, right: &'a HashSet<char>) -> HashSet<char>
 fn intersection<'a>(left: &'a HashSet<char>, right: &'a HashSet<char>) -> HashSet<char> {
     let mut collector : HashSet<char> = HashSet::new();
 
-    for letter in left.intersection(&right) {
+    for letter in left.intersection(right) {
         collector.insert(*letter);
     }
 
@@ -38,7 +40,11 @@ struct ElfGroup {
 impl ElfGroup {
     fn new() -> Self { Self { rucksacks: vec![] } }
 
-    fn form_groups(rucksacks: Vec<Rucksack>) -> Vec<Self> {
+    fn form_groups(rucksacks: Vec<Rucksack>) -> Result<Vec<Self>, ParseError> {
+        if !rucksacks.len().is_multiple_of(3) {
+            return Err(ParseError::new(format!("{} rucksacks can't be split into groups of three", rucksacks.len())));
+        }
+
         let mut groups : Vec<Self> = vec![];
         let mut group = Self::new();
 
@@ -52,7 +58,7 @@ impl ElfGroup {
             }
         }
 
-        groups
+        Ok(groups)
     }
 
     fn push_rucksack(&mut self, rucksack: Rucksack) {
@@ -85,19 +91,31 @@ struct Rucksack {
     back: HashSet<char>
 }
 
-impl Rucksack {
-    fn new(input: &str) -> Self {
-        let front : Vec<char> = input[0..(input.len() / 2)].chars().collect();
-        let back : Vec<char> = input[(input.len() / 2)..input.len()].chars().collect();
+impl FromStr for Rucksack {
+    type Err = ParseError;
 
-        let new_rucksack = Self {
-            front: HashSet::from_iter(front),
-            back: HashSet::from_iter(back)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some((column, item)) = input.chars().enumerate().find(|(_column, item)| !ALPHABET.contains(*item)) {
+            return Err(ParseError::unexpected_character(item, column + 1));
+        }
+        if !input.len().is_multiple_of(2) {
+            return Err(ParseError::new(format!("compartments of '{input}' aren't the same size")));
+        }
+
+        let rucksack = Self {
+            front: input[0..(input.len() / 2)].chars().collect(),
+            back: input[(input.len() / 2)..input.len()].chars().collect()
         };
 
-        new_rucksack
+        if rucksack.front.is_disjoint(&rucksack.back) {
+            return Err(ParseError::new(format!("no item in '{input}' is in both compartments")));
+        }
+
+        Ok(rucksack)
     }
+}
 
+impl Rucksack {
     fn mismatched_letter(&self) -> char {
         *self.front.intersection(&self.back).next().unwrap()
     }
@@ -111,18 +129,27 @@ impl Rucksack {
     }
 }
 
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let rucksacks : Vec<Rucksack> = parse_lines(input)?;
+
+    Ok(rucksacks.iter().map(Rucksack::mismatched_letter_value).sum())
+}
+
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    Ok(ElfGroup::form_groups(parse_lines(input)?)?.iter().map(ElfGroup::badge_value).sum())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
-    fn read_rucksacks(file_name: &str) -> Vec<Rucksack> {
-        fs::read_to_string(file_name).unwrap().lines().map(Rucksack::new).collect()
+    fn read_rucksacks(input: &str) -> Vec<Rucksack> {
+        parse_lines(input).unwrap()
     }
 
     #[test]
     fn part_one_examples() {
-        let rucksacks : Vec<Rucksack> = read_rucksacks("example_input.txt");
+        let rucksacks : Vec<Rucksack> = read_rucksacks(&fixtures::example("example").unwrap().input);
 
         assert_eq!(
             String::from_iter(rucksacks.iter().map(Rucksack::mismatched_letter)),
@@ -134,13 +161,21 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let rucksacks : Vec<Rucksack> = read_rucksacks("input.txt");
+        let rucksacks : Vec<Rucksack> = read_rucksacks(&fixtures::input(2022, 3).unwrap());
         assert_eq!(rucksacks.iter().map(Rucksack::mismatched_letter_value).sum::<u32>(), 7763);
     }
 
+    #[test]
+    fn invalid_rucksacks() {
+        assert_eq!(parse_lines::<Rucksack>("vJrwpWtwJgWrhcsFMMfFFhFp\nab1a").err().unwrap().to_string(), "line 2, column 3: unexpected character '1'");
+        assert_eq!(parse_lines::<Rucksack>("abcab").err().unwrap().to_string(), "line 1: compartments of 'abcab' aren't the same size");
+        assert_eq!(parse_lines::<Rucksack>("abcd").err().unwrap().to_string(), "line 1: no item in 'abcd' is in both compartments");
+        assert!(ElfGroup::form_groups(parse_lines("aa\nbb").unwrap()).is_err());
+    }
+
     #[test]
     fn part_two_examples() {
-        let groups = ElfGroup::form_groups(read_rucksacks("example_input.txt"));
+        let groups = ElfGroup::form_groups(read_rucksacks(&fixtures::example("example").unwrap().input)).unwrap();
 
         assert_eq!(
             String::from_iter(groups.iter().map(ElfGroup::badge)),
@@ -152,7 +187,7 @@ mod tests {
 
     #[test]
     fn part_two() {
-        let groups = ElfGroup::form_groups(read_rucksacks("input.txt"));
+        let groups = ElfGroup::form_groups(read_rucksacks(&fixtures::input(2022, 3).unwrap())).unwrap();
 
         assert_eq!(groups.iter().map(ElfGroup::badge_value).sum::<u32>(), 2569);
     }
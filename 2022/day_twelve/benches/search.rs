@@ -0,0 +1,12 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use y2022_day_twelve::{part_one, part_two};
+
+fn search(c: &mut Criterion) {
+    let input = fixtures::input(2022, 12).unwrap();
+
+    c.bench_function("day 12 part one", |b| b.iter(|| part_one(&input)));
+    c.bench_function("day 12 part two", |b| b.iter(|| part_two(&input)));
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
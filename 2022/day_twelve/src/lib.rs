@@ -1,70 +1,53 @@
-type Altitude = u8;
-
-#[derive(Clone, Eq, PartialEq, Debug)]
-struct Point { x: usize, y: usize }
-
-impl Point {
-    fn left(&self) -> Self {
-        Point { x: self.x - 1, y: self.y }
-    }
+use grid::{Direction, Grid};
+use parsing::ParseError;
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
-    fn right(&self) -> Self {
-        Point { x: self.x + 1, y: self.y }
-    }
-
-    fn up(&self) -> Self {
-        Point { x: self.x, y: self.y - 1 }
-    }
+type Altitude = u8;
 
-    fn down(&self) -> Self {
-        Point { x: self.x, y: self.y + 1 }
-    }
-}
+type Point = grid::Point<usize>;
 
 struct HeightMap {
-    data: Vec<Vec<Altitude>>,
+    data: Grid<Altitude>,
     nodes: Vec<usize>,
-    total_nodes: usize,
     start_position: Point,
-    end_position: Point
+    end_position: Point,
+    trail: Vec<Point>
 }
 
 impl HeightMap {
-    fn new(input: &str) -> Self {
-        let mut start_position = Point { x: 0, y: 0 };
-        let mut end_position = Point { x: 0, y: 0 };
-        let data : Vec<Vec<Altitude>> = input.lines().enumerate().map( |(row, line)| {
-            line.chars().enumerate().map(|(column, letter)| {
-                match letter {
-                    'S' => {
-                        start_position = Point { x: column, y: row };
-                        0
-                    },
-                    'E' => {
-                        end_position = Point { x: column, y: row };
-                        25
-                    },
-                    'a'..='z' => {
-                        (letter as u8) - ('a' as u8)
-                    },
-                    _ => panic!("unexpected character {letter}")
-                }
-            }).collect()
-        }).collect();
-
-        let total_nodes = data.len() * data[0].len();
-        let nodes : Vec<usize> = vec![usize::MAX; total_nodes];
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut start_position = None;
+        let mut end_position = None;
+        let data = Grid::try_parse(input, |point, letter| {
+            match letter {
+                'S' => {
+                    start_position = Some(point);
+                    Some(0)
+                },
+                'E' => {
+                    end_position = Some(point);
+                    Some(25)
+                },
+                'a'..='z' => {
+                    Some((letter as u8) - b'a')
+                },
+                _ => None
+            }
+        })?;
 
+        let start_position = start_position.ok_or_else(|| ParseError::new("no start position 'S' on the map"))?;
+        let end_position = end_position.ok_or_else(|| ParseError::new("no best signal position 'E' on the map"))?;
+        let nodes : Vec<usize> = vec![usize::MAX; data.len()];
 
-        Self { data, start_position, end_position, nodes, total_nodes }
+        Ok(Self { data, start_position, end_position, nodes, trail: vec![] })
     }
 
     fn start_node(&self) -> usize {
-        self.start_position.y * self.width() + self.start_position.x
+        self.node_for_point(&self.start_position)
     }
 
     fn end_node(&self) -> usize {
-        self.end_position.y * self.width() + self.end_position.x
+        self.node_for_point(&self.end_position)
     }
 
     fn part_one_path_length(&self) -> usize {
@@ -83,12 +66,9 @@ impl HeightMap {
     fn possible_start_nodes(&self) -> Vec<usize> {
         let mut nodes_to_start_from : Vec<usize> = vec![];
 
-        for (row, cells) in self.data.iter().enumerate() {
-            for (column, &height) in cells.iter().enumerate() {
-                if height == 0 {
-                    let point = Point { x: column, y: row };
-                    nodes_to_start_from.push(self.node_for_point(&point));
-                }
+        for (point, &height) in self.data.iter() {
+            if height == 0 {
+                nodes_to_start_from.push(self.node_for_point(&point));
             }
         }
 
@@ -104,11 +84,11 @@ impl HeightMap {
     }
 
     fn point_for_node(&self, node: usize) -> Point {
-        Point { x: node % self.width(), y: node / self.width() }
+        self.data.point_at(node)
     }
 
     fn node_for_point(&self, point: &Point) -> usize {
-        point.y * self.width() + point.x
+        self.data.index_of(point)
     }
 
     fn calculate_paths(&mut self) {
@@ -116,51 +96,18 @@ impl HeightMap {
     }
 
     fn calculate_path(&mut self, start_node: usize) {
-        self.nodes[start_node] = 0;
+        let reachable = search::bfs_all(self.point_for_node(start_node), |point| self.neighbors(*point));
 
-        // self.print();
-
-        let mut to_visit : Vec<(usize, Point)> = (0..self.total_nodes).map(|node| (node, self.point_for_node(node))).collect();
-        let mut previous_nodes = vec![usize::MAX; self.total_nodes];
-
-        while !to_visit.is_empty() {
-            to_visit.sort_by_key(|(node, _point)| self.path_length_to(*node));
-            to_visit.reverse();
-
-            if to_visit.iter().all(|(node, _point)| self.path_length_to(*node) == usize::MAX) {
-                break;
-            }
-
-            let (node, point) = to_visit.pop().unwrap();
-            if self.path_length_to(node) == usize::MAX {
-                self.print();
-                dbg!(node, point);
-                panic!("Unexpected visit to infinite node")
-            }
-
-            // dbg!(node, point.clone());
-
-            for neighbor in self.neighbors(point) {
-                let (neighbor_node, _neighbor_point) = neighbor.clone();
-
-                // dbg!(neighbor.clone());
-
-                if to_visit.contains(&neighbor) {
-                    let new_distance = self.path_length_to(node) + 1;
-                    if new_distance < self.path_length_to(neighbor_node) {
-                        self.set_path_length_to(neighbor_node, new_distance);
-                        previous_nodes[neighbor_node] = node;
-                    }
-                }
-            }
+        for (point, distance) in reachable.iter() {
+            self.set_path_length_to(self.node_for_point(point), distance);
         }
     }
 
     #[allow(dead_code)]
     fn print(&self) {
-        for (row, cells) in self.data.iter().enumerate() {
+        for (row, cells) in self.data.rows().enumerate() {
             for (column, _) in cells.iter().enumerate() {
-                let point = Point { x: column, y: row };
+                let point = Point::new(column, row);
                 let distance = self.path_length_to(self.node_for_point(&point));
                 if distance == usize::MAX {
                     print!("   ");
@@ -170,14 +117,14 @@ impl HeightMap {
             }
             println!();
             for (column, &height) in cells.iter().enumerate() {
-                let point = Point { x: column, y: row };
+                let point = Point::new(column, row);
                 let _node = self.node_for_point(&point);
                 if self.start_position == point {
                     print!(" S ");
                 } else if self.end_position == point {
                     print!(" E ");
                 } else {
-                    print!(" {} ", (height + ('a' as Altitude)) as char)
+                    print!(" {} ", (height + b'a') as char)
                 }
             }
             println!();
@@ -185,62 +132,120 @@ impl HeightMap {
         println!();
     }
 
-    fn width(&self) -> usize {
-        self.data[0].len()
-    }
-
-    fn height(&self) -> usize {
-        self.data.len()
-    }
-
     fn height_for_point(&self, point: &Point) -> Altitude {
-        self.data[point.y][point.x]
+        self.data[*point]
     }
 
     fn can_climb(&self, from: &Point, to: &Point) -> bool {
         (self.height_for_point(from) as i32) - (self.height_for_point(to) as i32) < 2
     }
 
-    fn neighbors(&self, point: Point) -> Vec<(usize, Point)> {
-        let mut neighbors = vec![];
+    fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.data.neighbors(&point).filter(move |neighbor| self.can_climb(&point, neighbor))
+    }
 
-        if point.x > 0 {
-            let possible_neighbor = point.left();
-            if self.can_climb(&point, &possible_neighbor) {
-                neighbors.push((self.node_for_point(&possible_neighbor), possible_neighbor));
-            }
+    fn letter_at(&self, point: &Point) -> char {
+        if *point == self.start_position {
+            'S'
+        } else if *point == self.end_position {
+            'E'
+        } else {
+            (self.height_for_point(point) + b'a') as char
         }
-        if point.x < self.width() - 1 {
-            let possible_neighbor = point.right();
-            if self.can_climb(&point, &possible_neighbor) {
-                neighbors.push((self.node_for_point(&possible_neighbor), possible_neighbor));
-            }
+    }
+}
+
+impl Step for HeightMap {
+    // Walks one step from the start towards the best signal, along one of the
+    // shortest paths found by `calculate_paths`.
+    fn step(&mut self) -> bool {
+        let current = *self.trail.last().unwrap_or(&self.start_position);
+        let distance = self.path_length_to(self.node_for_point(&current));
+        if current == self.end_position || distance == usize::MAX {
+            return false;
         }
-        if point.y > 0 {
-            let possible_neighbor = point.up();
-            if self.can_climb(&point, &possible_neighbor) {
-                neighbors.push((self.node_for_point(&possible_neighbor), possible_neighbor));
-            }
+
+        let next = self.data.neighbors(&current).find(|next| {
+            self.can_climb(next, &current) && self.path_length_to(self.node_for_point(next)) == distance - 1
+        });
+        match next {
+            Some(next) => {
+                self.trail.push(next);
+                true
+            },
+            None => false
         }
-        if point.y < self.height() - 1 {
-            let possible_neighbor = point.down();
-            if self.can_climb(&point, &possible_neighbor) {
-                neighbors.push((self.node_for_point(&possible_neighbor), possible_neighbor));
+    }
+}
+
+impl Render for HeightMap {
+    // Squares already walked through point the way they were left, as in the puzzle.
+    fn render(&self) -> Frame {
+        let mut frame_cells : Vec<Vec<char>> = self.data.rows().enumerate().map(|(row, cells)| {
+            (0..cells.len()).map(|column| self.letter_at(&Point::new(column, row))).collect()
+        }).collect();
+
+        let mut from = self.start_position;
+        for &to in self.trail.iter() {
+            if let Some(direction) = Direction::ALL.into_iter().find(|&direction| from.checked_step(direction) == Some(to)) {
+                frame_cells[from.y][from.x] = direction.arrow();
             }
+            from = to;
         }
 
-        neighbors
+        Frame::from_fn(self.data.width(), self.data.height(), |column, row| frame_cells[row][column])
+            .with_caption(format!("{} step(s)", self.trail.len()))
+    }
+
+    fn palette(&self) -> Palette {
+        let arrow = Color::new(250, 220, 60);
+        let marker = Color::new(240, 70, 60);
+
+        let heights = (0..26u8).fold(Palette::new(), |palette, height| {
+            let shade = 40 + height * 8;
+            palette.with((b'a' + height) as char, Color::new(shade / 2, shade, shade / 2))
+        });
+
+        heights
+            .with('S', marker)
+            .with('E', marker)
+            .with('^', arrow)
+            .with('v', arrow)
+            .with('<', arrow)
+            .with('>', arrow)
     }
 }
 
+// Records a walk along the shortest path from the start to the best signal.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let mut height_map = HeightMap::new(input)?;
+    height_map.calculate_paths();
+    recorder.run(&mut height_map);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut height_map = HeightMap::new(input)?;
+    height_map.calculate_paths();
+
+    Ok(height_map.part_one_path_length())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let mut height_map = HeightMap::new(input)?;
+    height_map.calculate_paths();
+
+    Ok(height_map.part_two_path_length())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let mut height_map = HeightMap::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut height_map = HeightMap::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         height_map.calculate_paths();
         assert_eq!(
             height_map.part_one_path_length(),
@@ -250,7 +255,7 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let mut height_map = HeightMap::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut height_map = HeightMap::new(fixtures::input(2022, 12).unwrap().as_str()).unwrap();
         height_map.calculate_paths();
         assert_eq!(
             height_map.part_one_path_length(),
@@ -258,9 +263,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn animate_example() {
+        let mut recorder = Recorder::new();
+        animate(fixtures::example("example").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 32);
+        assert_eq!(recorder.frames()[0].to_string(), "0 step(s)\nSabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi\n");
+
+        let last = recorder.frames().last().unwrap();
+        let arrows = last.rows().flatten().filter(|&&symbol| Direction::from_arrow(symbol).is_some()).count();
+        assert_eq!(last.caption(), Some("31 step(s)"));
+        assert_eq!(arrows, 31);
+        assert_eq!(last.get(5, 2), Some('E'));
+    }
+
+    #[test]
+    fn invalid_maps() {
+        assert_eq!(HeightMap::new("Sab\nc#E").err().unwrap().to_string(), "line 2, column 2: unexpected character '#'");
+        assert_eq!(HeightMap::new("Sab\ncdz").err().unwrap().to_string(), "no best signal position 'E' on the map");
+    }
+
     #[test]
     fn part_two_example() {
-        let mut height_map = HeightMap::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut height_map = HeightMap::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         height_map.calculate_paths();
         assert_eq!(
             height_map.part_two_path_length(),
@@ -270,7 +296,7 @@ mod tests {
 
     #[test]
     fn part_two() {
-        let mut height_map = HeightMap::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut height_map = HeightMap::new(fixtures::input(2022, 12).unwrap().as_str()).unwrap();
         height_map.calculate_paths();
         assert_eq!(
             height_map.part_two_path_length(),
@@ -96,7 +96,6 @@ impl CoordinateFile {
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
@@ -109,7 +108,7 @@ mod tests {
     #[test]
     fn part_one_example() {
         assert_eq!(
-            CoordinateFile::new(fs::read_to_string("example_input.txt").unwrap().as_str()).coordinate_sum(),
+            CoordinateFile::new(fixtures::example("example").unwrap().input.as_str()).coordinate_sum(),
             4
         );
     }
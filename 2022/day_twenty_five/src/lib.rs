@@ -35,12 +35,11 @@ fn decimal_to_snafu(number: i32) -> String {
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let values : Vec<(String, i32)> = fs::read_to_string("example_input.txt").unwrap().lines().map(|snafu| (snafu.to_string(), parse_snafu(snafu))).collect();
+        let values : Vec<(String, i32)> = fixtures::example("example").unwrap().input.lines().map(|snafu| (snafu.to_string(), parse_snafu(snafu))).collect();
         for (snafu, value) in values.iter() {
             println!("{:>6}, {:4}", snafu, *value);
         }
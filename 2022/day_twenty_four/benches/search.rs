@@ -0,0 +1,13 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use y2022_day_twenty_four::part_one;
+
+fn search(c: &mut Criterion) {
+    let example_input = fixtures::example("example").unwrap().input;
+    let input = fixtures::input(2022, 24).unwrap();
+
+    c.bench_function("day 24 part one (example)", |b| b.iter(|| part_one(&example_input)));
+    c.bench_function("day 24 part one", |b| b.iter(|| part_one(&input)));
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
@@ -1,65 +1,10 @@
-enum Direction {
-    North,
-    South,
-    East,
-    West
-}
-
-impl Direction {
-    fn new(symbol: char) -> Option<Self> {
-        match symbol {
-            '^' => Some(Direction::North),
-            'v' => Some(Direction::South),
-            '<' => Some(Direction::West),
-            '>' => Some(Direction::East),
-            _ => None
-        }
-    }
-
-    fn to_string(&self) -> String {
-        match self {
-            Direction::North => String::from('^'),
-            Direction::South => String::from('v'),
-            Direction::West => String::from('<'),
-            Direction::East => String::from('>'),
-        }
-    }
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Ord, PartialOrd)]
-struct Point {
-    x: i32,
-    y: i32
-}
-
-impl Point {
-    fn up(&self) -> Self {
-        Self { x: self.x, y: self.y - 1 }
-    }
-
-    fn down(&self) -> Self {
-        Self { x: self.x, y: self.y + 1 }
-    }
-
-    fn left(&self) -> Self {
-        Self { x: self.x - 1, y: self.y }
-    }
-
-    fn right(&self) -> Self {
-        Self { x: self.x + 1, y: self.y }
-    }
-
-    fn neighbors(&self) -> Vec<Point> {
-        vec![self.up(), self.down(), self.left(), self.right()]
-    }
+use std::collections::HashSet;
+use std::iter;
+use grid::{Direction, Grid};
+use parsing::ParseError;
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
-    fn is_neighbor_of(&self, other: Point) -> bool {
-        other == self.up() ||
-            other == self.down() ||
-            other == self.left() ||
-            other == self.right()
-    }
-}
+type Point = grid::Point<i32>;
 
 struct Blizzard {
     start_point: Point,
@@ -89,8 +34,8 @@ impl Blizzard {
         }
     }
 
-    fn to_string(&self) -> String {
-        self.direction.to_string()
+    fn symbol(&self) -> char {
+        self.direction.arrow()
     }
 }
 
@@ -99,11 +44,18 @@ struct Valley {
     entrance: Point,
     exit: Point,
     interior_dimensions: Point,
-    open_rooms_through_time: Vec<Vec<Point>>
+    blizzard_starts: HashSet<(Point, Direction)>,
+    route: Vec<Point>,
+    minute: usize
 }
 
 impl Valley {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::try_parse(input, |_point, symbol| matches!(symbol, '#' | '.' | '^' | 'v' | '<' | '>').then_some(symbol))?;
+        if map.width() < 3 || map.height() < 3 {
+            return Err(ParseError::new(format!("a {}x{} valley has no room for an expedition", map.width(), map.height())));
+        }
+
         let mut lines = input.lines();
         let row_count = lines.clone().count() as i32 - 2;
         let example_line = lines.next().unwrap(); // ignore first line
@@ -126,250 +78,198 @@ impl Valley {
                     continue;
                 }
 
-                if let Some(direction) = Direction::new(character) {
+                if let Some(direction) = Direction::from_arrow(character) {
                     blizzards.push(Blizzard { direction, start_point:  Point { x: column, y: row } });
                 }
             }
         }
 
         let interior_dimensions = Point { x: column_count, y: row_count };
-        let loop_count = ((interior_dimensions.x + 1) * (interior_dimensions.y + 1)) as usize;
-
-        dbg!(loop_count);
-        let mut open_rooms_through_time: Vec<Vec<Point>> = vec![];
-        for tick in 0..loop_count {
-            let mut open_rooms: Vec<Point> = vec![entrance.clone()];
-
-            let mut rooms_to_check : Vec<Point>;
-            if tick == 0 {
-                rooms_to_check = vec![entrance.down()];
-            } else {
-                rooms_to_check = open_rooms_through_time[tick - 1].iter().flat_map(|point| {
-                    point.neighbors().into_iter().filter(|neighbor| {
-                        if neighbor.eq(&entrance) || neighbor.eq(&exit) {
-                            true
-                        } else {
-                            0 <= neighbor.x && neighbor.x < interior_dimensions.x
-                                && 0 <= neighbor.y && neighbor.y < interior_dimensions.y
-                        }
-                    })
-                }).collect();
-            }
-            rooms_to_check.sort();
-            rooms_to_check.dedup();
-            // for row in 0..interior_dimensions.y {
-            //     for column in 0..interior_dimensions.x {
-            //         let point = Point { x: column, y: row };
-            for point in rooms_to_check.iter() {
-                if !blizzards.iter().any(|blizzard| blizzard.is_forecast(tick, point, &interior_dimensions)) {
-                    if tick == 0 {
-                        if point.is_neighbor_of(entrance) {
-                            open_rooms.push(point.clone());
-                        }
-                    } else if open_rooms_through_time[tick - 1].iter().any(|previous_point| previous_point.eq(point) || previous_point.is_neighbor_of(point.clone())) {
-                        open_rooms.push(point.clone());
-                    }
-                }
-            }
+        let blizzard_starts = blizzards.iter().map(|blizzard| (blizzard.start_point, blizzard.direction)).collect();
 
-            open_rooms.push(exit.clone());
-
-            if tick % 100 == 0 {
-                dbg!(tick, open_rooms.len());
-            }
+        Ok(Self { blizzards, entrance, exit, interior_dimensions, blizzard_starts, route: vec![], minute: 0 })
+    }
 
-            open_rooms_through_time.push(open_rooms)
+    // The blizzards all return to their starting points after this many ticks.
+    fn period(&self) -> usize {
+        let (width, height) = (self.interior_dimensions.x as usize, self.interior_dimensions.y as usize);
+        let (mut a, mut b) = (width, height);
+        while b != 0 {
+            (a, b) = (b, a % b);
         }
 
-        Self { blizzards, entrance, exit, interior_dimensions, open_rooms_through_time }
+        width / a * height
     }
 
-    fn nodes(&self) -> Vec<(usize, usize, Point)> {
-        let mut nodes : Vec<(usize, Point)> = vec![(0, self.entrance.clone())];
+    fn is_open(&self, tick: usize, point: &Point) -> bool {
+        if *point == self.entrance || *point == self.exit {
+            return true;
+        }
 
-        for (tick, open_nodes) in self.open_rooms_through_time.iter().enumerate() {
-            if tick == 0 {
-                continue;
-            }
-            if open_nodes[0] == self.entrance.down() {
-                nodes.push((tick + 1, self.entrance.clone()))
-            }
-            nodes.extend(open_nodes.iter().map(|point| (tick, point.clone())));
-            if *open_nodes.last().unwrap() == self.exit.up() {
-                nodes.push((tick, self.exit.clone()))
-            }
+        let (width, height) = (self.interior_dimensions.x, self.interior_dimensions.y);
+        if point.x < 0 || point.x >= width || point.y < 0 || point.y >= height {
+            return false;
         }
 
-        nodes.iter().enumerate().map(|(index, (tick, point))| (index, *tick, *point)).collect()
+        // work backwards from the point to where a blizzard would have to start to be here now
+        let tick = (tick % self.period()) as i32;
+        let possible_starts = [
+            (Point { x: point.x, y: (point.y + tick).rem_euclid(height) }, Direction::North),
+            (Point { x: point.x, y: (point.y - tick).rem_euclid(height) }, Direction::South),
+            (Point { x: (point.x + tick).rem_euclid(width), y: point.y }, Direction::West),
+            (Point { x: (point.x - tick).rem_euclid(width), y: point.y }, Direction::East),
+        ];
+
+        !possible_starts.iter().any(|start| self.blizzard_starts.contains(start))
     }
 
     fn find_shortest_path(&self) -> usize {
-        let nodes = self.nodes();
-        let node_count = nodes.len();
-        dbg!(node_count, self.open_rooms_through_time.len());
-        let mut shortest_paths : Vec<usize> = vec![usize::MAX; node_count];
-        shortest_paths[0] = 0;
-
-        let mut to_visit = nodes.clone();
-        let mut previous_nodes = vec![usize::MAX; node_count];
-
-        let mut latest_tick = 0;
-        while !to_visit.is_empty() {
-            to_visit.sort_by_key(|(node, _tick, point)| {
-                let distance = shortest_paths[*node];
-                if distance == usize::MAX {
-                    distance
-                } else if point.eq(&self.entrance) {
-                    (distance * 2) + 1
-                } else {
-                    distance * 2
-                }
-            });
-            to_visit.reverse();
-
-            if to_visit.iter().all(|(node, _tick, _point)| shortest_paths[*node] == usize::MAX) {
-                break;
-            }
+        self.shortest_route().len() - 1
+    }
 
-            let (node, tick, point) = to_visit.pop().unwrap();
-            if tick > latest_tick {
-                // if tick % 10 == 0 {
-                    dbg!(tick);
-                // }
-                latest_tick = tick;
-            }
-            let distance = shortest_paths[node];
-            if shortest_paths[node] == usize::MAX {
-                self.print(tick, &point);
-                dbg!(node, point);
-                panic!("Unexpected visit to infinite node")
-            }
-            let neighbor_distance = distance + 1;
-            let neighbor_tick = tick + 1;
+    // Where the expedition is at each minute on the quickest way through.
+    fn shortest_route(&self) -> Vec<Point> {
+        let period = self.period();
+
+        let path = search::astar(
+            (self.entrance, 0),
+            |&(point, tick)| {
+                let next_tick = (tick + 1) % period;
+                iter::once(point).chain(point.neighbors())
+                    .filter(move |next_point| self.is_open(next_tick, next_point))
+                    .map(move |next_point| ((next_point, next_tick), 1))
+            },
+            |(point, _tick)| point.manhattan_distance(&self.exit) as usize,
+            |(point, _tick)| *point == self.exit
+        );
 
-            // dbg!(node, tick, distance, point.clone());
+        path.expect("No way through the valley!").nodes.into_iter().map(|(point, _tick)| point).collect()
+    }
 
-            for neighbor in nodes.iter().filter(|(_node, tick, _point)| *tick == neighbor_tick) {
-                let (_neighbor_node, _neighbor_tick, _neighbor_point) = neighbor;
-                let neighbor_node = *_neighbor_node;
-                let neighbor_point = *_neighbor_point;
+    fn plan_route(&mut self) {
+        self.route = self.shortest_route();
+        self.minute = 0;
+    }
 
-                if !(neighbor_point == point || neighbor_point.is_neighbor_of(point)) {
-                    // println!("Rejecting neighbor:");
-                    // dbg!(neighbor);
-                    continue;
-                }
+    fn expedition(&self) -> Point {
+        self.route.get(self.minute).copied().unwrap_or(self.entrance)
+    }
 
-                // println!("Processing neighbor:");
-                // dbg!(neighbor);
+    #[allow(dead_code)]
+    fn print(&self) {
+        println!("{}", self.render());
+    }
+}
 
-                if to_visit.contains(neighbor) {
-                    if neighbor_distance < shortest_paths[neighbor_node] {
-                        shortest_paths[neighbor_node] = neighbor_distance;
-                        previous_nodes[neighbor_node] = node;
-                    }
-                }
-            }
+impl Step for Valley {
+    // Follows the planned route one minute at a time.
+    fn step(&mut self) -> bool {
+        let advanced = self.minute + 1 < self.route.len();
+        if advanced {
+            self.minute += 1;
         }
 
-        // dbg!(&shortest_paths);
-
-        let paths = shortest_paths.iter().enumerate().filter_map(|(node, _distance)| {
-            let distance = *_distance;
-            if distance == usize::MAX {
-                None
-            } else {
-                let (_node, _tick, point) = nodes[node];
-                if point == self.exit {
-                    Some((node, distance))
-                } else {
-                    None
-                }
-            }
-        });
-        // dbg!(&paths.clone().collect::<Vec<(usize, usize)>>());
-        let (_final_node, distance) = paths.min_by_key(|(_node, distance)| *distance).unwrap();
-        // let mut path_nodes : Vec<usize> = vec![];
-        // path_nodes.push(final_node);
-        // let mut previous = previous_nodes[final_node];
-        // path_nodes.push(previous);
-        // while previous > 0 {
-        //     previous = previous_nodes[previous];
-        //     path_nodes.push(previous);
-        // }
-        // for &node in path_nodes.iter().rev() {
-        //     let (_node, tick, point) = nodes[node];
-        //     self.print(tick, &point);
-        // }
-        distance
+        advanced
     }
+}
 
-    fn print(&self, tick: usize, current_point: &Point) {
-        print!("#");
-        for column in 0..self.interior_dimensions.x {
-            let point = Point { x: column, y: -1 };
-            if point.eq(current_point) {
-                print!("E");
-            } else if column == 0 {
-                print!(".");
-            } else {
-                print!("#");
+impl Render for Valley {
+    fn render(&self) -> Frame {
+        let (width, height) = (self.interior_dimensions.x as usize, self.interior_dimensions.y as usize);
+        let expedition = self.expedition();
+
+        Frame::from_fn(width + 2, height + 2, |column, row| {
+            let point = Point { x: column as i32 - 1, y: row as i32 - 1 };
+            if point == expedition {
+                return 'E';
             }
-        }
-        println!("#");
-
-        for row in 0..self.interior_dimensions.y {
-            print!("#");
-            for column in 0..self.interior_dimensions.x {
-                let point = Point { x: column, y: row };
-                let blizzards : Vec<&Blizzard> = self.blizzards.iter().filter(|blizzard| blizzard.is_forecast(tick, &point, &self.interior_dimensions)).collect();
-                match blizzards.len() {
-                    0 => {
-                        if point.eq(current_point) {
-                            print!("E");
-                        } else {
-                            print!(".")
-                        }
-                    },
-                    1 => print!("{}", blizzards[0].to_string()),
-                    n => print!("{n}")
-                }
+            if point == self.entrance || point == self.exit {
+                return '.';
+            }
+            if column == 0 || column == width + 1 || row == 0 || row == height + 1 {
+                return '#';
             }
-            println!("#");
-        }
 
-        print!("#");
-        for column in 0..self.interior_dimensions.x {
-            let point = Point { x: column, y: self.exit.y };
-            if point.eq(current_point) {
-                print!("E");
-            } else if column == self.exit.x {
-                print!(".");
-            } else {
-                print!("#");
+            let blizzards : Vec<&Blizzard> = self.blizzards.iter().filter(|blizzard| blizzard.is_forecast(self.minute, &point, &self.interior_dimensions)).collect();
+            match blizzards.len() {
+                0 => '.',
+                1 => blizzards[0].symbol(),
+                count => char::from_digit(count as u32, 10).unwrap()
             }
-        }
-        println!("#");
-        println!();
+        }).with_caption(format!("minute {}", self.minute))
     }
+
+    fn palette(&self) -> Palette {
+        let blizzard = Color::new(170, 220, 255);
+
+        Palette::new()
+            .with('#', Color::new(120, 120, 120))
+            .with('E', Color::new(250, 80, 60))
+            .with('^', blizzard)
+            .with('v', blizzard)
+            .with('<', blizzard)
+            .with('>', blizzard)
+            .with('2', Color::new(220, 240, 255))
+            .with('3', Color::new(235, 245, 255))
+            .with('4', Color::new(255, 255, 255))
+    }
+}
+
+// Records the expedition taking the quickest way through the valley.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let mut valley = Valley::new(input)?;
+    valley.plan_route();
+    recorder.run(&mut valley);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(Valley::new(input)?.find_shortest_path())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let valley = Valley::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let valley = Valley::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
 
         assert_eq!(valley.find_shortest_path(), 18);
     }
 
+    #[test]
+    fn animate_example() {
+        let mut recorder = Recorder::new();
+        animate(fixtures::example("example").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 19);
+        assert_eq!(
+            recorder.frames()[0].to_string(),
+            "minute 0\n#E######\n#>>.<^<#\n#.<..<<#\n#>v.><>#\n#<^v^^>#\n######.#\n"
+        );
+        assert_eq!(
+            recorder.frames()[1].to_string(),
+            "minute 1\n#.######\n#E>3.<.#\n#<..<<.#\n#>2.22.#\n#>v..^<#\n######.#\n"
+        );
+        assert_eq!(
+            recorder.frames()[18].to_string(),
+            "minute 18\n#.######\n#>2.<.<#\n#.2v^2<#\n#>..>2>#\n#<....>#\n######E#\n"
+        );
+    }
+
+    #[test]
+    fn invalid_valleys() {
+        assert_eq!(Valley::new("#.###\n#.>x#\n###.#").err().unwrap().to_string(), "line 2, column 4: unexpected character 'x'");
+        assert_eq!(Valley::new("#.###\n#.>#\n###.#").err().unwrap().to_string(), "line 2: expected a row of width 5, found 4");
+        assert_eq!(Valley::new("#.#\n#.#").err().unwrap().to_string(), "a 3x2 valley has no room for an expedition");
+    }
+
     #[test]
     fn part_one() {
-        let valley = Valley::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let valley = Valley::new(fixtures::input(2022, 24).unwrap().as_str()).unwrap();
 
-        assert_eq!(valley.find_shortest_path(), 18);
+        assert_eq!(valley.find_shortest_path(), 334);
     }
 }
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+use parsing::{parse_lines, ParseError};
 
 type MonkeyShout = i64;
 
@@ -12,20 +14,31 @@ enum MonkeyBusiness {
 }
 
 impl MonkeyBusiness {
-    fn new(input: &str) -> Self {
-        let parts : Vec<&str> = input.split(" ").collect();
-        if parts.len() == 1 {
-            return MonkeyBusiness::Constant { value: parts[0].parse().unwrap() };
+    fn new(line: &str, input: &str) -> Result<Self, ParseError> {
+        let (left, operator, right) = match input.split(" ").collect::<Vec<&str>>()[..] {
+            [value] => return Ok(MonkeyBusiness::Constant { value: parsing::number(line, value)? }),
+            [left, operator, right] => (left, operator, right),
+            _ => return Err(ParseError::at_token(line, input, format!("unexpected operation '{input}'")))
+        };
+
+        let (left, right) = (left.to_string(), right.to_string());
+        match operator {
+            "+" => { Ok(MonkeyBusiness::Adder { left, right }) },
+            "-" => { Ok(MonkeyBusiness::Subtracter { left, right }) },
+            "*" => { Ok(MonkeyBusiness::Multiplier { left, right }) },
+            "/" => { Ok(MonkeyBusiness::Divider { left, right }) },
+            _ => Err(ParseError::at_token(line, operator, format!("unknown operator {operator}")))
         }
+    }
 
-        let left = parts[0].to_string();
-        let right = parts[2].to_string();
-        match parts[1] {
-            "+" => { MonkeyBusiness::Adder { left, right } },
-            "-" => { MonkeyBusiness::Subtracter { left, right } },
-            "*" => { MonkeyBusiness::Multiplier { left, right } },
-            "/" => { MonkeyBusiness::Divider { left, right } },
-            _ => panic!("Unexpected operation: {input}")
+    fn operands(&self) -> Vec<&String> {
+        match self {
+            MonkeyBusiness::Constant { value: _ } => vec![],
+            MonkeyBusiness::Adder { left, right }
+                | MonkeyBusiness::Subtracter { left, right }
+                | MonkeyBusiness::Multiplier { left, right }
+                | MonkeyBusiness::Divider { left, right }
+                    => { vec![left, right] }
         }
     }
 
@@ -68,14 +81,18 @@ struct Monkey {
     value: Option<MonkeyShout>
 }
 
-impl Monkey {
-    fn new(input: &str) -> Self {
-        let (name, operation) = input.split_once(": ").unwrap();
-        let business = MonkeyBusiness::new(operation);
+impl FromStr for Monkey {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (name, operation) = parsing::split_once(input, ": ")?;
+        let business = MonkeyBusiness::new(input, operation)?;
 
-        Self { name: name.to_string(), business, value: None }
+        Ok(Self { name: name.to_string(), business, value: None })
     }
+}
 
+impl Monkey {
     fn with_value(&self, value: MonkeyShout) -> Self {
         Self { name: self.name.clone(), business: self.business.clone(), value: Some(value) }
     }
@@ -86,14 +103,25 @@ struct Troop {
 }
 
 impl Troop {
-    fn new(input: &str) -> Self {
-        Self {
-            monkey_definitions: input.lines().map(|line| {
-                let monkey = Monkey::new(line);
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let monkeys : Vec<Monkey> = parse_lines(input)?;
+        let monkey_definitions : HashMap<String, Monkey> = monkeys.iter().map(|monkey| (monkey.name.clone(), monkey.clone())).collect();
 
-                (monkey.name.clone(), monkey)
-            }).collect()
+        for (index, monkey) in monkeys.iter().enumerate() {
+            if let Some(name) = monkey.business.operands().into_iter().find(|name| !monkey_definitions.contains_key(*name)) {
+                return Err(ParseError::new(format!("{} listens to unknown monkey {name}", monkey.name)).with_line(index + 1));
+            }
+        }
+        match monkey_definitions.get("root") {
+            Some(root) if !root.business.operands().is_empty() => {},
+            Some(_) => return Err(ParseError::new("root must combine two other monkeys")),
+            None => return Err(ParseError::new("no monkey named root"))
         }
+        if !monkey_definitions.contains_key("humn") {
+            return Err(ParseError::new("no monkey named humn"));
+        }
+
+        Ok(Self { monkey_definitions })
     }
 
     fn part_one_value(&mut self) -> MonkeyShout {
@@ -262,32 +290,53 @@ impl Troop {
     }
 }
 
+pub fn part_one(input: &str) -> Result<MonkeyShout, ParseError> {
+    Ok(Troop::new(input)?.part_one_value())
+}
+
+pub fn part_two(input: &str) -> Result<MonkeyShout, ParseError> {
+    Ok(Troop::new(input)?.part_two_value())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
-        let mut troop = Troop::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut troop = Troop::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(troop.part_one_value(), 152);
     }
 
     #[test]
     fn part_one() {
-        let mut troop = Troop::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut troop = Troop::new(fixtures::input(2022, 21).unwrap().as_str()).unwrap();
         assert_eq!(troop.part_one_value(), 159_591_692_827_554);
     }
 
+    #[test]
+    fn invalid_monkeys() {
+        let error = Troop::new("root: humn + abcd\nhumn: 5\nabcd: humn % 2").err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 12: unknown operator %");
+
+        let error = Troop::new("root: humn + abcd\nhumn: 5\nabcd: efgh * 2").err().unwrap();
+        assert_eq!(error.to_string(), "line 3: abcd listens to unknown monkey efgh");
+
+        let error = Troop::new("root: humn + abcd\nhumn: five\nabcd: 2").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 7: invalid number 'five'");
+
+        assert_eq!(Troop::new("root: 4\nhumn: 5").err().unwrap().to_string(), "root must combine two other monkeys");
+    }
+
     #[test]
     fn part_two_example() {
-        let mut troop = Troop::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut troop = Troop::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
         assert_eq!(troop.part_two_value(), 301);
     }
 
     #[test]
     fn part_two() {
-        let mut troop = Troop::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut troop = Troop::new(fixtures::input(2022, 21).unwrap().as_str()).unwrap();
         assert_eq!(troop.part_two_value(), 3_509_819_803_065);
     }
 }
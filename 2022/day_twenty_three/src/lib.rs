@@ -1,59 +1,9 @@
-use std::cmp::{max, min};
 use std::collections::HashMap;
-use std::iter::Cycle;
-use std::slice::Iter;
+use grid::{BoundingBox, SparseGrid};
+use parsing::ParseError;
+use render::{Color, Frame, Palette, Recorder, Render, Step};
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-struct Point {
-    x: i32,
-    y: i32
-}
-
-impl Point {
-    fn up(&self) -> Self {
-        Self { x: self.x, y: self.y - 1 }
-    }
-
-    fn down(&self) -> Self {
-        Self { x: self.x, y: self.y + 1 }
-    }
-
-    fn left(&self) -> Self {
-        Self { x: self.x - 1, y: self.y }
-    }
-
-    fn right(&self) -> Self {
-        Self { x: self.x + 1, y: self.y }
-    }
-
-    fn neighbors(&self) -> Vec<Point> {
-        let mut neighbors = vec![];
-        let mut current = self.up(); // north
-        let mut next = current.left(); // northwest
-        neighbors.push(current);
-        current = next;
-        next = current.down(); // west
-        neighbors.push(current);
-        current = next;
-        next = current.down(); // southwest
-        neighbors.push(current);
-        current = next;
-        next = current.right(); // south
-        neighbors.push(current);
-        current = next;
-        next = current.right(); // southeast
-        neighbors.push(current);
-        current = next;
-        next = current.up(); // east
-        neighbors.push(current);
-        current = next;
-        next = current.up(); // northeast
-        neighbors.push(current);
-        neighbors.push(next);
-
-        neighbors
-    }
-}
+type Point = grid::Point<i32>;
 
 #[derive(Clone)]
 struct Elf {
@@ -61,65 +11,69 @@ struct Elf {
 }
 
 struct PlantingParty {
-    elves: HashMap<Point, Elf>
+    elves: SparseGrid<Elf>,
+    rounds: usize
 }
 
 impl PlantingParty {
-    fn new(input: &str) -> Self {
-        let mut elves = HashMap::new();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut elves = SparseGrid::new();
 
         for (row, symbols) in input.lines().enumerate() {
             for (column, symbol) in symbols.chars().enumerate() {
-                if symbol == '#' {
-                    let point = Point { x: column as i32, y: row as i32 };
-                    elves.insert(point, Elf { current_position: point });
+                match symbol {
+                    '#' => {
+                        let point = Point::new(column as i32, row as i32);
+                        elves.insert(point, Elf { current_position: point });
+                    },
+                    '.' => {},
+                    _ => return Err(ParseError::unexpected_character(symbol, column + 1).with_line(row + 1))
                 }
             }
         }
 
-        Self { elves }
+        if elves.is_empty() {
+            return Err(ParseError::new("expected at least one elf"));
+        }
+
+        Ok(Self { elves, rounds: 0 })
     }
 
+    // Returns the round nobody moved in, or `rounds` if they were all run.
+    // The rounds can be watched through `animate` instead.
     fn run_rounds(&mut self, rounds: usize) -> usize {
-        let original_directions = vec!['N', 'S', 'W', 'E'];
-        let mut directions : Cycle<Iter<char>> = original_directions.iter().cycle();
-
-        println!("Start");
-        self.print();
-
         for n in 0..rounds {
             let round = n + 1;
-            let moves = self.run_round(&mut directions);
 
-            if moves == 0 {
-                println!("Reached steady state after {} round(s):", round);
-                self.print();
+            if !self.step() {
                 return round
             }
-
-            if round % 100 == 0 {
-                println!("Round: {}: {} move(s)", round, moves);
-                self.print();
-            }
-            directions.next();
         }
 
         rounds
     }
 
+    // The direction considered first moves on by one every round.
+    fn directions(&self) -> impl Iterator<Item = char> {
+        let original_directions = ['N', 'S', 'W', 'E'];
+        let rounds = self.rounds;
+
+        (0..4).map(move |offset| original_directions[(rounds + offset) % 4])
+    }
+
     fn has_elf_at(&self, point: &Point) -> bool {
-        self.elves.contains_key(point)
+        self.elves.contains(point)
     }
 
-    fn run_round(&mut self, directions: &mut Cycle<Iter<char>>) -> usize {
+    fn run_round(&mut self) -> usize {
         let mut proposed_moves : HashMap<Point, Vec<Elf>> = HashMap::new();
 
         for (point, elf) in self.elves.iter() {
-            if point.neighbors().iter().all(|neighbor| { !self.has_elf_at(neighbor)}) {
+            if point.all_neighbors().all(|neighbor| { !self.has_elf_at(&neighbor)}) {
                 continue;
             };
 
-            for direction in directions.clone().take(4) {
+            for direction in self.directions() {
                 match direction {
                     'N' => {
                         if let Some(point) = self.consider_moving_north(elf) {
@@ -160,7 +114,7 @@ impl PlantingParty {
             moves += 1;
 
             self.elves.remove(&elves[0].current_position);
-            self.elves.insert(point.clone(), Elf { current_position: point.clone()});
+            self.elves.insert(*point, Elf { current_position: *point});
         }
 
         moves
@@ -213,71 +167,92 @@ impl PlantingParty {
         }
     }
 
-    fn print(&self) {
-        let (top_left, bottom_right) = self.dimensions();
+    fn dimensions(&self) -> BoundingBox<i32> {
+        self.elves.bounds().unwrap()
+    }
 
-        for row in top_left.y..=bottom_right.y {
-            for column in top_left.x..=bottom_right.x {
-                if self.has_elf_at(&Point { x: column, y: row }) {
-                    print!("#")
-                } else {
-                    print!(".")
-                }
-            }
-            println!()
-        }
-        println!()
+    fn open_land(&self) -> usize {
+        self.dimensions().area() as usize - self.elves.len()
     }
+}
 
-    fn dimensions(&self) -> (Point, Point) {
-        let mut min_x : i32 = i32::MAX;
-        let mut min_y : i32 = i32::MAX;
-        let mut max_x : i32 = i32::MIN;
-        let mut max_y : i32 = i32::MIN;
-
-        for (point, _) in self.elves.iter() {
-            min_y = min(min_y, point.y);
-            max_y = max(max_y, point.y);
-            min_x = min(min_x, point.x);
-            max_x = max(max_x, point.x);
-        }
+impl Step for PlantingParty {
+    // Runs one round, finishing once no elf needs to move.
+    fn step(&mut self) -> bool {
+        let moves = self.run_round();
+        self.rounds += 1;
 
-        ( Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y } )
+        moves > 0
     }
+}
 
-    fn open_land(&self) -> usize {
-        let (top_left, bottom_right) = self.dimensions();
-        let mut land = 0;
+impl Render for PlantingParty {
+    fn render(&self) -> Frame {
+        let bounds = self.dimensions();
 
-        for row in top_left.y..=bottom_right.y {
-            for column in top_left.x..=bottom_right.x {
-                if !self.has_elf_at(&Point { x: column, y: row }) {
-                    land += 1
-                }
+        Frame::from_fn(bounds.width() as usize, bounds.height() as usize, |column, row| {
+            if self.has_elf_at(&(bounds.min + Point::new(column as i32, row as i32))) {
+                '#'
+            } else {
+                '.'
             }
-        }
+        }).with_origin(bounds.min.x, bounds.min.y)
+    }
 
-        land
+    fn palette(&self) -> Palette {
+        Palette::new()
+            .with('#', Color::new(60, 200, 80))
+            .with('.', Color::new(110, 80, 50))
     }
 }
 
+// Records every round until the elves stop moving.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    recorder.run(&mut PlantingParty::new(input)?);
+
+    Ok(())
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut planting_party = PlantingParty::new(input)?;
+    planting_party.run_rounds(10);
+
+    Ok(planting_party.open_land())
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(PlantingParty::new(input)?.run_rounds(usize::MAX))
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example_small() {
-        let mut planting_party = PlantingParty::new(fs::read_to_string("example_small_input.txt").unwrap().as_str());
+        let mut planting_party = PlantingParty::new(fixtures::example("example_small").unwrap().input.as_str()).unwrap();
 
         planting_party.run_rounds(10);
 
         assert_eq!(planting_party.open_land(), 25);
     }
 
+    #[test]
+    fn animate_example_small() {
+        let mut recorder = Recorder::new();
+        animate(fixtures::example("example_small").unwrap().input.as_str(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.len(), 4);
+        assert_eq!(recorder.frames()[0].to_string(), "##\n#.\n..\n##\n");
+        assert_eq!(
+            recorder.frames()[3].to_string(),
+            "..#..\n....#\n#....\n....#\n.....\n..#..\n"
+        );
+    }
+
     #[test]
     fn part_one_example() {
-        let mut planting_party = PlantingParty::new(fs::read_to_string("example_input.txt").unwrap().as_str());
+        let mut planting_party = PlantingParty::new(fixtures::example("example").unwrap().input.as_str()).unwrap();
 
         planting_party.run_rounds(10);
 
@@ -286,17 +261,23 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let mut planting_party = PlantingParty::new(fs::read_to_string("input.txt").unwrap().as_str());
+        let mut planting_party = PlantingParty::new(fixtures::input(2022, 23).unwrap().as_str()).unwrap();
 
         planting_party.run_rounds(10);
 
         assert_eq!(planting_party.open_land(), 4249);
     }
 
+    #[test]
+    fn invalid_groves() {
+        assert_eq!(PlantingParty::new("..#\n#o.").err().unwrap().to_string(), "line 2, column 2: unexpected character 'o'");
+        assert_eq!(PlantingParty::new("...\n...").err().unwrap().to_string(), "expected at least one elf");
+    }
+
     #[test]
     fn part_two_example() {
         assert_eq!(
-            PlantingParty::new(fs::read_to_string("example_input.txt").unwrap().as_str()).run_rounds(100),
+            PlantingParty::new(fixtures::example("example").unwrap().input.as_str()).unwrap().run_rounds(100),
             20
         );
     }
@@ -304,7 +285,7 @@ mod tests {
     #[test]
     fn part_two() {
         assert_eq!(
-            PlantingParty::new(fs::read_to_string("input.txt").unwrap().as_str()).run_rounds(1_000),
+            PlantingParty::new(fixtures::input(2022, 23).unwrap().as_str()).unwrap().run_rounds(1_000),
             980
         );
     }
@@ -1,43 +1,22 @@
 use std::collections::HashMap;
 use std::ops::RangeInclusive;
-use std::thread::sleep;
-use std::time::Duration;
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-enum Direction {
-    North,
-    South,
-    East,
-    West
-}
+use grid::Direction;
+use parsing::ParseError;
 
-impl Direction {
-    fn turn_left(&self) -> Direction {
-        match self {
-            Direction::North => Direction::West,
-            Direction::West => Direction::South,
-            Direction::South => Direction::East,
-            Direction::East => Direction::North
-        }
-    }
+type Point = grid::Point<i32>;
 
-    fn turn_right(&self) -> Direction {
-        match self {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North
-        }
-    }
+fn password(point: &Point, direction: &Direction) -> usize {
+    assert!(point.y >= 0);
+    assert!(point.x >= 0);
 
-    fn score(&self) -> usize {
-        match self {
-            Direction::East => 0,
-            Direction::South => 1,
-            Direction::West => 2,
-            Direction::North => 3,
-        }
-    }
+    let facing = match direction {
+        Direction::East => 0,
+        Direction::South => 1,
+        Direction::West => 2,
+        Direction::North => 3,
+    };
+
+    (((point.y + 1) * 1000) + ((point.x + 1) * 4)) as usize + facing
 }
 
 enum MapTile {
@@ -50,28 +29,29 @@ impl MapTile {
         match symbol {
             '.' => Some(MapTile::Space),
             '#' => Some(MapTile::Wall),
-            ' ' => None,
-            _ => panic!("Unexpected character: {symbol}")
+            _ => None
         }
     }
 }
 
+type EdgeMap = HashMap<(MapFace, MapFace), (Direction, fn(&CubeMap, &Point) -> Point)>;
+
 struct CubeMap {
     grid: Vec<Vec<Option<MapFace>>>,
     grid_size: usize,
     map: HashMap<MapFace, Point>,
     portals: HashMap<(MapFace, Direction), MapFace>,
-    edges: HashMap<(MapFace, MapFace), (Direction, fn(&Self, &Point) -> Point)>
+    edges: EdgeMap
 }
 
 impl CubeMap {
-    fn build(grid: Vec<Vec<Option<MapFace>>>, grid_size: usize, portals: HashMap<(MapFace, Direction), MapFace>, edges: HashMap<(MapFace, MapFace), (Direction, fn(&Self, &Point) -> Point)>) -> Self {
+    fn build(grid: Vec<Vec<Option<MapFace>>>, grid_size: usize, portals: HashMap<(MapFace, Direction), MapFace>, edges: EdgeMap) -> Self {
         let mut map : HashMap<MapFace, Point> = HashMap::new();
 
         for (row, possible_faces) in grid.iter().enumerate() {
             for (column, possible_face) in possible_faces.iter().enumerate() {
                 if let Some(face) = possible_face {
-                    map.insert(face.clone(), Point { x: column as i32, y: row as i32 });
+                    map.insert(*face, Point { x: column as i32, y: row as i32 });
                 }
             }
         }
@@ -79,6 +59,7 @@ impl CubeMap {
         Self { grid, grid_size, map, portals, edges }
     }
 
+    #[allow(dead_code)]
     fn example() -> Self {
         let mut portals : HashMap<(MapFace, Direction), MapFace> = HashMap::new();
 
@@ -102,7 +83,7 @@ impl CubeMap {
 
         portals.insert((MapFace::Right, Direction::North), MapFace::Front);
 
-        let mut edges : HashMap<(MapFace, MapFace), (Direction, fn(&Self, &Point) -> Point)> = HashMap::new();
+        let mut edges : EdgeMap = HashMap::new();
 
         // connected faces (opposite edges)
         edges.insert((MapFace::Top, MapFace::Front), (Direction::South, Self::wrap_to_grid_top));
@@ -175,7 +156,7 @@ impl CubeMap {
         portals.insert((MapFace::Left, Direction::North), MapFace::Front);
         portals.insert((MapFace::Left, Direction::West), MapFace::Top);
 
-        let mut edges : HashMap<(MapFace, MapFace), (Direction, fn(&Self, &Point) -> Point)> = HashMap::new();
+        let mut edges : EdgeMap = HashMap::new();
 
         // connected faces (opposite edges)
         edges.insert((MapFace::Top, MapFace::Front), (Direction::South, Self::wrap_to_grid_top));
@@ -239,10 +220,14 @@ impl CubeMap {
     }
 
     fn at_grid(&self, row: i32, column: i32) -> Option<MapFace> {
+        if row < 0 || column < 0 {
+            return None;
+        }
+
         let row = row as usize;
         let column = column as usize;
 
-        if row < 0 || self.height() <= row || column < 0 || self.width() <= column {
+        if self.height() <= row || self.width() <= column {
             return None;
         }
 
@@ -255,21 +240,8 @@ impl CubeMap {
 
     fn face_for(&self, point: &Point) -> Option<(MapFace, Point)> {
         let grid_size = self.grid_size as i32;
-        let grid_row : i32;
-        let grid_column : i32;
-
-        if point.y < 0 {
-            grid_row = -1;
-        } else {
-            grid_row = point.y / grid_size;
-        }
-        if point.x < 0 {
-            grid_column = -1;
-        } else {
-            grid_column = point.x / grid_size;
-        }
-
-        dbg!(grid_size, point, grid_row, grid_column);
+        let grid_row = if point.y < 0 { -1 } else { point.y / grid_size };
+        let grid_column = if point.x < 0 { -1 } else { point.x / grid_size };
 
         match self.at_grid(grid_row, grid_column) {
             Some(face) => {
@@ -283,48 +255,51 @@ impl CubeMap {
     }
 
     fn portal(&self, from: MapFace, pointed: Direction, grid_point: Point) -> (MapFace, Direction, Point) {
-        let new_face = self.portals.get(&(from.clone(), pointed.clone())).unwrap().clone();
-        let (new_direction, new_grid_point) = self.traverse(from, new_face.clone(), grid_point);
+        let new_face = *self.portals.get(&(from, pointed)).unwrap();
+        let (new_direction, new_grid_point) = self.traverse(from, new_face, grid_point);
 
         (new_face, new_direction, new_grid_point)
     }
 
     fn wrap_to_grid_top(&self, point: &Point) -> Point {
-        point.wrap_to_grid_top()
+        Point::new(point.x, 0)
     }
 
     fn wrap_to_grid_bottom(&self, point: &Point) -> Point {
-        point.wrap_to_grid_bottom(self.grid_size)
+        Point::new(point.x, self.grid_size as i32 - 1)
     }
 
     fn wrap_to_grid_left(&self, point: &Point) -> Point {
-        point.wrap_to_grid_left()
+        Point::new(0, point.y)
     }
 
     fn wrap_to_grid_right(&self, point: &Point) -> Point {
-        point.wrap_to_grid_right(self.grid_size)
+        Point::new(self.grid_size as i32 - 1, point.y)
     }
 
     fn transpose_grid_top_right_edge(&self, point: &Point) -> Point {
-        point.transpose_grid_top_right_edge()
+        Point::new(point.y, point.x)
     }
 
+    #[allow(dead_code)]
     fn transpose_grid_top_left_edge(&self, point: &Point) -> Point {
-        point.transpose_grid_top_left_edge(self.grid_size)
+        let grid_size = self.grid_size as i32;
+        Point::new(grid_size - (point.y + 1), grid_size - (point.x + 1))
     }
 
+    #[allow(dead_code)]
     fn invert_x(&self, point: &Point) -> Point {
-        point.invert_x(self.grid_size)
+        Point::new(self.grid_size as i32 - (point.x + 1), point.y)
     }
 
     fn invert_y(&self, point: &Point) -> Point {
-        point.invert_y(self.grid_size)
+        Point::new(point.x, self.grid_size as i32 - (point.y + 1))
     }
 
     fn traverse(&self, from: MapFace, to: MapFace, from_grid_point: Point) -> (Direction, Point) {
         let (direction, transformer) = self.edges.get(&(from, to)).unwrap();
 
-        (direction.clone(), transformer(&self, &from_grid_point))
+        (*direction, transformer(self, &from_grid_point))
     }
 }
 
@@ -338,86 +313,6 @@ enum MapFace {
     Right,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct Point {
-    x:  i32,
-    y: i32
-}
-
-impl Point {
-    fn up(&self) -> Self {
-        Self { x: self.x, y: self.y - 1 }
-    }
-
-    fn down(&self) -> Self {
-        Self { x: self.x, y: self.y + 1 }
-    }
-
-    fn left(&self) -> Self {
-        Self { x: self.x - 1, y: self.y }
-    }
-
-    fn right(&self) -> Self {
-        Self { x: self.x + 1, y: self.y }
-    }
-
-    fn plus(&self, delta: &Self) -> Self {
-        Self { x: self.x + delta.x, y: self.y + delta.y }
-    }
-
-    fn scale(&self, multiplier: i32) -> Self {
-        Self { x: self.x * multiplier, y: self.y * multiplier }
-    }
-
-    fn wrap_to_grid_top(&self) -> Self {
-        Self { x: self.x, y: 0 }
-    }
-
-    fn wrap_to_grid_bottom(&self, grid_size: usize) -> Self {
-        Self { x: self.x, y: (grid_size - 1) as i32 }
-    }
-
-    fn wrap_to_grid_left(&self) -> Self {
-        Self { x: 0, y: self.y }
-    }
-
-    fn wrap_to_grid_right(&self, grid_size: usize) -> Self {
-        Self { x: (grid_size - 1) as i32, y: self.y }
-    }
-
-    fn transpose_grid_top_right_edge(&self) -> Self {
-        Self { x: self.y, y: self.x }
-    }
-
-    fn transpose_grid_top_left_edge(&self, grid_size: usize) -> Self {
-        Self { x: grid_size as i32 - (self.y + 1), y: grid_size as i32 - (self.x + 1) }
-    }
-
-    fn invert_y(&self, grid_size: usize) -> Self {
-        Self { x: self.x, y: grid_size as i32 - (self.y + 1) }
-    }
-
-    fn invert_x(&self, grid_size: usize) -> Self {
-        Self { x: grid_size as i32 - (self.x + 1), y: self.y }
-    }
-
-    fn move_one_step(&self, direction: &Direction) -> Self {
-        match direction {
-            Direction::North => self.up(),
-            Direction::East => self.right(),
-            Direction::South => self.down(),
-            Direction::West => self.left()
-        }
-    }
-
-    fn score(&self) -> usize {
-        assert!(self.y >= 0);
-        assert!(self.x >= 0);
-
-        (((self.y + 1) * 1000) + ((self.x + 1) * 4)) as usize
-    }
-}
-
 #[derive(Debug)]
 enum Instruction {
     Move(usize),
@@ -426,12 +321,12 @@ enum Instruction {
 }
 
 impl Instruction {
-    fn new_sequence(input: &str) -> Vec<Self> {
+    fn new_sequence(input: &str) -> Result<Vec<Self>, ParseError> {
+        let input = input.trim();
         let mut sequence = vec![];
-        let mut characters = input.trim().chars();
-        let mut character = characters.next().unwrap();
+        let mut characters = input.char_indices().peekable();
 
-        loop {
+        while let Some((index, character)) = characters.next() {
             match character {
                 'L' => {
                     sequence.push(Instruction::TurnLeft);
@@ -440,37 +335,22 @@ impl Instruction {
                     sequence.push(Instruction::TurnRight);
                 },
                 '0'..='9' => {
-                    let mut number = character.to_digit(10).unwrap();
-                    if let Some(temp_character) = characters.next() {
-                        character = temp_character;
-                        match character {
-                            '0'..='9' => {
-                                number = (number * 10) + character.to_digit(10).unwrap();
-                                sequence.push(Instruction::Move(number as usize));
-                                // TODO(?): Handle 3 digit numbers
-                            },
-                            _ => {
-                                sequence.push(Instruction::Move(number as usize));
-                                continue;
-                            }
-                        }
-                    } else {
-                        sequence.push(Instruction::Move(number as usize));
-                        return sequence;
+                    let mut end = index + 1;
+                    while let Some(&(next_index, '0'..='9')) = characters.peek() {
+                        end = next_index + 1;
+                        characters.next();
                     }
+                    sequence.push(Instruction::Move(parsing::number(input, &input[index..end])?));
                 },
-                _ => panic!("Unexpected character: {character}")
-            }
-
-            if let Some(temp_character) = characters.next() {
-                character = temp_character;
-                continue;
+                _ => return Err(ParseError::unexpected_character(character, index + 1))
             }
+        }
 
-            break;
+        if sequence.is_empty() {
+            return Err(ParseError::new("expected a path description"));
         }
 
-        sequence
+        Ok(sequence)
     }
 }
 
@@ -484,27 +364,36 @@ struct PasswordMap {
 }
 
 impl PasswordMap {
-    fn new(input: &str, cube_map: CubeMap) -> Self {
-        let (map_str, instructions_str) = input.split_once("\n\n").unwrap();
-        let instructions = Instruction::new_sequence(instructions_str);
+    fn new(input: &str, cube_map: CubeMap) -> Result<Self, ParseError> {
+        let (map_str, instructions_str) = parsing::split_once(input, "\n\n")
+            .map_err(|_| ParseError::new("expected a blank line between the map and the path"))?;
 
         let mut map: HashMap<Point, MapTile> = HashMap::new();
         let mut row_ranges : Vec<RowRange> = vec![];
 
         for (row, line) in map_str.lines().enumerate() {
             let mut start_col = 0;
-            let end_col = (line.len() - 1) as i32;
+            let end_col = line.chars().count() as i32 - 1;
             for (column, character) in line.chars().enumerate() {
-                if let Some(tile) = MapTile::new(character) {
-                    map.insert(Point { x: column as i32, y: row as i32 }, tile);
-                } else {
+                if character == ' ' {
                     start_col += 1;
+                    continue;
                 }
+
+                let tile = MapTile::new(character)
+                    .ok_or_else(|| ParseError::unexpected_character(character, column + 1).with_line(row + 1))?;
+                map.insert(Point { x: column as i32, y: row as i32 }, tile);
+            }
+            if start_col > end_col {
+                return Err(ParseError::new("expected at least one tile").with_line(row + 1));
             }
             row_ranges.push(start_col..=end_col)
         }
 
-        Self { map, instructions, row_ranges, cube_map }
+        let path_line = map_str.lines().count() + 2;
+        let instructions = Instruction::new_sequence(instructions_str).map_err(|error| error.with_line(path_line))?;
+
+        Ok(Self { map, instructions, row_ranges, cube_map })
     }
 
     fn row_range(&self, row: usize) -> &RowRange {
@@ -515,6 +404,7 @@ impl PasswordMap {
         *self.row_range(row).start()
     }
 
+    #[allow(dead_code)]
     fn end_of_row(&self, row: usize) -> i32 {
         *self.row_range(row).end()
     }
@@ -533,17 +423,14 @@ impl PasswordMap {
         let mut path: HashMap<Point, Direction> = HashMap::new();
 
         for instruction in self.instructions.iter() {
-            dbg!(instruction);
-            // self.print(&path, &current_point, &current_direction);
-
             match instruction {
                 Instruction::Move(distance) => {
                     if folded {
-                        let (new_current_point, new_current_direction) = self.walk_forward_folded(current_point, current_direction.clone(), *distance, &mut path);
+                        let (new_current_point, new_current_direction) = self.walk_forward_folded(current_point, current_direction, *distance, &mut path);
                         current_point = new_current_point;
                         current_direction = new_current_direction;
                     } else {
-                        let (new_current_point, new_current_direction) = self.walk_forward(current_point, current_direction.clone(), *distance, &mut path);
+                        let (new_current_point, new_current_direction) = self.walk_forward(current_point, current_direction, *distance, &mut path);
                         current_point = new_current_point;
                         current_direction = new_current_direction;
                     }
@@ -555,35 +442,26 @@ impl PasswordMap {
                     current_direction = current_direction.turn_right();
                 }
             }
-            // sleep(Duration::from_secs(1));
         }
 
-        current_point.score() + current_direction.score()
+        password(&current_point, &current_direction)
     }
 
     fn walk_forward(&self, current_point: Point, current_direction: Direction, distance: usize, path: &mut HashMap<Point, Direction>) -> (Point, Direction) {
-        let mut next_point = current_point.clone();
-
-        // println!("Walking forward");
-        // self.print(&path, &next_point, &current_direction);
+        let mut next_point = current_point;
 
         for _ in 0..distance {
-            let mut possible_next_point = next_point.move_one_step(&current_direction);
-            // dbg!(&possible_next_point);
+            let mut possible_next_point = next_point.step(current_direction);
 
             match self.map.get(&possible_next_point) {
                 Some(MapTile::Space) => {
-                    path.insert(next_point.clone(), current_direction.clone());
+                    path.insert(next_point, current_direction);
                     next_point = possible_next_point;
-                    // self.print(&path, &next_point, &current_direction);
                 },
                 Some(MapTile::Wall) => {
-                    // self.print(&path, &next_point, &current_direction);
-                    // println!("Hit a wall");
-                    return (next_point, current_direction.clone());
+                    return (next_point, current_direction);
                 },
                 None => {
-                    // println!("Wrapping around");
                     possible_next_point =
                         match current_direction {
                             Direction::North => Point { x: current_point.x, y: self.height() as i32 },
@@ -591,23 +469,19 @@ impl PasswordMap {
                             Direction::South => Point { x: current_point.x, y: 0 },
                             Direction::West => Point { x: self.width() as i32, y: current_point.y },
                         };
-                    // dbg!(&possible_next_point);
 
                     loop {
                         match self.map.get(&possible_next_point) {
                             Some(MapTile::Space) => {
-                                path.insert(next_point.clone(), current_direction.clone());
+                                path.insert(next_point, current_direction);
                                 next_point = possible_next_point;
-                                // self.print(&path, &next_point, &current_direction);
                                 break;
                             },
                             Some(MapTile::Wall) => {
-                                // self.print(&path, &next_point, &current_direction);
-                                // println!("Hit a wall");
-                                return (next_point, current_direction.clone());
+                                return (next_point, current_direction);
                             },
                             None => {
-                                possible_next_point = possible_next_point.move_one_step(&current_direction);
+                                possible_next_point = possible_next_point.step(current_direction);
                             }
                         }
                     }
@@ -619,25 +493,21 @@ impl PasswordMap {
     }
 
     fn walk_forward_folded(&self, current_point: Point, current_direction: Direction, distance: usize, path: &mut HashMap<Point, Direction>) -> (Point, Direction) {
-        let mut next_point = current_point.clone();
+        let mut next_point = current_point;
         let (mut next_face, mut next_grid_point)  = self.cube_map.face_for(&next_point).unwrap();
-        let mut next_direction = current_direction.clone();
-        // dbg!(next_point, next_direction, next_face);
+        let mut next_direction = current_direction;
 
         for _ in 0..distance {
-            let mut possible_next_point = next_point.move_one_step(&next_direction);
-            let mut possible_next_direction = next_direction.clone();
-            let mut possible_next_face = next_face.clone();
-            let mut possible_next_grid_point = next_grid_point.clone();
-
-            // dbg!(possible_next_point, possible_next_direction, possible_next_face);
+            let mut possible_next_point = next_point.step(next_direction);
+            let mut possible_next_direction = next_direction;
+            let possible_next_face;
+            let mut possible_next_grid_point;
 
             match self.cube_map.face_for(&possible_next_point) {
                 Some((_possible_next_face, _possible_next_grid_point)) => {
                     possible_next_face = _possible_next_face;
                     possible_next_grid_point = _possible_next_grid_point;
 
-                    // dbg!(next_face, possible_next_face);
                     if next_face != possible_next_face {
                         let (_possible_next_direction, _possible_next_grid_point) =
                             self.cube_map.traverse(next_face, possible_next_face, possible_next_grid_point);
@@ -647,35 +517,28 @@ impl PasswordMap {
                     }
                 },
                 None => {
-                    println!("Time to portal!");
-                    // dbg!(next_face, next_direction, next_grid_point);
                     let (_possible_next_face, _possible_next_direction, _possible_next_grid_point) =
                         self.cube_map.portal(next_face, next_direction, next_grid_point);
                     possible_next_face = _possible_next_face;
                     possible_next_direction = _possible_next_direction;
                     possible_next_grid_point = _possible_next_grid_point;
-                    // dbg!(possible_next_grid_point, possible_next_direction, possible_next_face);
                     possible_next_point = self.point_from_grid_point(&possible_next_grid_point, &possible_next_face);
-                    // dbg!(possible_next_point);
                 }
             }
 
             // having determined my possible next step, actually try to move in that direction
             match self.map.get(&possible_next_point) {
                 Some(MapTile::Space) => {
-                    path.insert(next_point.clone(), next_direction.clone());
+                    path.insert(next_point, next_direction);
                     next_point = possible_next_point;
                     next_direction = possible_next_direction;
                     next_face = possible_next_face;
                     next_grid_point = possible_next_grid_point;
-                    // self.print(path, &next_point, &next_direction);
                 },
                 Some(MapTile::Wall) => {
-                    // self.print(path, &next_point, &next_direction);
                     return (next_point, next_direction)
                 },
                 None => {
-                    dbg!(possible_next_point, possible_next_direction, possible_next_face);
                     panic!("Somehow I fell off the cube...");
                 }
             }
@@ -684,66 +547,35 @@ impl PasswordMap {
         (next_point, next_direction)
     }
 
+    #[allow(dead_code)]
     fn grid_size(&self) -> usize {
         self.height() / 3
     }
 
     fn point_from_grid_point(&self, grid_point: &Point, face: &MapFace) -> Point {
-        grid_point.plus( &self.cube_map.grid_origin(face))
+        *grid_point + self.cube_map.grid_origin(face)
     }
+}
 
-    // cargo test --package day_twenty_two --lib tests::part_two -- --exact --nocapture
-    #[allow(dead_code)]
-    fn print(&self, path: &HashMap<Point, Direction>, current_point: &Point, current_direction: &Direction) {
-        for (row_index, row_range) in self.row_ranges.iter().enumerate() {
-            for _ in 0..*row_range.start() {
-                print!(" ");
-            }
-
-            for column_index in row_range.clone() {
-                let point = Point { x: column_index, y: row_index as i32 };
-                match path.get(&point) {
-                    Some(Direction::North) => print!("^"),
-                    Some(Direction::East) => print!(">"),
-                    Some(Direction::South) => print!("v"),
-                    Some(Direction::West) => print!("<"),
-                    None => {
-                        if point.eq(current_point) {
-                            match current_direction {
-                                Direction::North => print!("^"),
-                                Direction::East => print!(">"),
-                                Direction::South => print!("v"),
-                                Direction::West => print!("<"),
-                            }
-                        } else {
-                            match self.map.get(&point) {
-                                Some(MapTile::Space) => print!("."),
-                                Some(MapTile::Wall) => print!("#"),
-                                None => { print!("?") }
-                            }
-                        }
-                    }
-                }
-            }
-            println!();
-        }
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    Ok(PasswordMap::new(input, CubeMap::full())?.decode_password(false))
+}
 
-        println!();
-    }
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    Ok(PasswordMap::new(input, CubeMap::full())?.decode_password(true))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_example() {
         assert_eq!(
             PasswordMap::new(
-                fs::read_to_string("example_input.txt").unwrap().as_str(),
+                fixtures::example("example").unwrap().input.as_str(),
                 CubeMap::example()
-            ).decode_password(false),
+            ).unwrap().decode_password(false),
             6032
         );
     }
@@ -752,20 +584,31 @@ mod tests {
     fn part_one() {
         assert_eq!(
             PasswordMap::new(
-                fs::read_to_string("input.txt").unwrap().as_str(),
+                fixtures::input(2022, 22).unwrap().as_str(),
                 CubeMap::full()
-            ).decode_password(false),
+            ).unwrap().decode_password(false),
             30552
         );
     }
 
+    #[test]
+    fn invalid_notes() {
+        let error = PasswordMap::new("  ..#\n  .x.\n\n10R5", CubeMap::example()).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 4: unexpected character 'x'");
+
+        let error = PasswordMap::new("  ..#\n  ...\n\n10R5U", CubeMap::example()).err().unwrap();
+        assert_eq!(error.to_string(), "line 4, column 5: unexpected character 'U'");
+
+        assert!(PasswordMap::new("  ..#\n  ...\n10R5", CubeMap::example()).is_err());
+    }
+
     #[test]
     fn part_two_example() {
         assert_eq!(
             PasswordMap::new(
-                fs::read_to_string("example_input.txt").unwrap().as_str(),
+                fixtures::example("example").unwrap().input.as_str(),
                 CubeMap::example()
-            ).decode_password(true),
+            ).unwrap().decode_password(true),
             5031
         );
     }
@@ -775,9 +618,9 @@ mod tests {
     fn part_two() {
         assert_eq!(
             PasswordMap::new(
-                fs::read_to_string("input.txt").unwrap().as_str(),
+                fixtures::input(2022, 22).unwrap().as_str(),
                 CubeMap::full()
-            ).decode_password(true),
+            ).unwrap().decode_password(true),
             130350 // Too low
         );
     }
@@ -1,133 +1,307 @@
-enum Outcome {
+use std::{error, fmt};
+use std::str::FromStr;
+use parsing::ParseError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
     Win,
     Draw,
     Loss
 }
 
-impl Outcome {
-    fn new(input: &str) -> Self {
+impl FromStr for Outcome {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
         match input {
-            "X" => Self::Loss,
-            "Y" => Self::Draw,
-            "Z" => Self::Win,
-            _ => panic!("Invalid outcome symbol: {input}")
+            "X" => Ok(Self::Loss),
+            "Y" => Ok(Self::Draw),
+            "Z" => Ok(Self::Win),
+            _ => Err(ParseError::at_column(1, format!("invalid outcome symbol '{input}'")))
         }
     }
+}
 
-    fn score(&self) -> u32 {
-        match self {
-            Self::Win => 6,
-            Self::Draw => 3,
-            Self::Loss => 0
-        }
+// A move is its place in the game's cycle.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Move(usize);
+
+impl Move {
+    pub fn index(self) -> usize {
+        self.0
     }
 }
 
-#[derive(Copy,Clone)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scoring {
+    pub moves: Vec<u32>,
+    pub win: u32,
+    pub draw: u32,
+    pub loss: u32
 }
 
-impl Move {
-    fn new(symbol: &str) -> Self {
-        match symbol {
-            "A" | "X" => { Self::Rock },
-            "B" | "Y" => { Self::Paper },
-            "C" | "Z" => { Self::Scissors },
-            _ => panic!("Unknown move symbol: {symbol}")
+impl Scoring {
+    // Each move scores its place in the game counting from 1, with 6 for a
+    // win and 3 for a draw.
+    pub fn standard(moves: usize) -> Self {
+        Self { moves: (1..=moves as u32).collect(), win: 6, draw: 3, loss: 0 }
+    }
+
+    fn outcome(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Win => self.win,
+            Outcome::Draw => self.draw,
+            Outcome::Loss => self.loss
         }
     }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum GameError {
+    MoveCount(usize),
+    DuplicateMove(String),
+    Symbols { expected: usize, found: String },
+    Scores { expected: usize, found: usize }
+}
 
-    fn base_score(&self) -> u32 {
+impl fmt::Display for GameError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Rock => 1,
-            Self::Paper => 2,
-            Self::Scissors => 3
+            GameError::MoveCount(count) => write!(formatter, "a cyclic game needs an odd number of moves from 3 to 25, not {count}"),
+            GameError::DuplicateMove(name) => write!(formatter, "'{name}' appears more than once"),
+            GameError::Symbols { expected, found } => write!(formatter, "expected {expected} distinct symbols, found '{found}'"),
+            GameError::Scores { expected, found } => write!(formatter, "expected a score for each of {expected} moves, found {found}")
         }
     }
 }
 
-struct Round {
-    opponent: Move,
-    you: Move
+impl error::Error for GameError {}
+
+// What the second column of a strategy guide means.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Column {
+    Move,
+    Outcome
+}
+
+// Every move beats the half of the others just before it in the cycle and
+// loses to the half just after, which is why there has to be an odd number.
+// The opponent's moves are written with the first letters of the alphabet and
+// yours with the last ones, unless other symbols are given.
+#[derive(Clone, Debug)]
+pub struct Game {
+    names: Vec<String>,
+    opponent_symbols: Vec<char>,
+    your_symbols: Vec<char>,
+    scoring: Scoring
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::rock_paper_scissors()
+    }
 }
 
-impl Round {
-    fn new_round_one(input: &str) -> Self {
-        let moves: Vec<Move> = input.split(" ").map(Move::new).collect();
-        if moves.len() != 2 {
-            panic!("Invalid input for round: {input}")
+impl Game {
+    pub fn new(names: &[&str]) -> Result<Self, GameError> {
+        let count = names.len();
+        if !(3..=25).contains(&count) || count.is_multiple_of(2) {
+            return Err(GameError::MoveCount(count));
         }
+        if let Some((_, name)) = names.iter().enumerate().find(|(index, name)| names[..*index].contains(name)) {
+            return Err(GameError::DuplicateMove(name.to_string()));
+        }
+
+        Ok(Self {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            opponent_symbols: ('A'..='Z').take(count).collect(),
+            your_symbols: ('A'..='Z').skip(26 - count).collect(),
+            scoring: Scoring::standard(count)
+        })
+    }
 
-        Self { opponent: moves[0], you: moves[1] }
+    pub fn rock_paper_scissors() -> Self {
+        Self::new(&["rock", "paper", "scissors"]).unwrap()
     }
 
-    fn new_round_two(input: &str) -> Self {
-        let parts: Vec<&str> = input.split(" ").collect();
-        if parts.len() != 2 {
-            panic!("Invalid input for round: {input}")
+    // Ordered so that every move beats the two before it.
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::new(&["rock", "Spock", "paper", "lizard", "scissors"]).unwrap()
+    }
+
+    pub fn with_symbols(mut self, opponent: &str, you: &str) -> Result<Self, GameError> {
+        self.opponent_symbols = self.symbols(opponent)?;
+        self.your_symbols = self.symbols(you)?;
+        Ok(self)
+    }
+
+    pub fn with_scoring(mut self, scoring: Scoring) -> Result<Self, GameError> {
+        if scoring.moves.len() != self.names.len() {
+            return Err(GameError::Scores { expected: self.names.len(), found: scoring.moves.len() });
         }
 
-        let opponent = Move::new(parts[0]);
-        let outcome = Outcome::new(parts[1]);
-        let you = match (opponent, outcome) {
-            (Move::Rock, Outcome::Draw) | (Move::Paper, Outcome::Loss) | (Move::Scissors, Outcome::Win) => Move::Rock,
-            (Move::Paper, Outcome::Draw) | (Move::Scissors, Outcome::Loss) | (Move::Rock, Outcome::Win) => Move::Paper,
-            (Move::Scissors, Outcome::Draw) | (Move::Rock, Outcome::Loss) | (Move::Paper, Outcome::Win) => Move::Scissors
-        };
+        self.scoring = scoring;
+        Ok(self)
+    }
 
-        Self { opponent, you }
+    fn symbols(&self, symbols: &str) -> Result<Vec<char>, GameError> {
+        let characters : Vec<char> = symbols.chars().collect();
+        let distinct = characters.iter().enumerate().all(|(index, symbol)| !characters[..index].contains(symbol));
+        if characters.len() != self.names.len() || !distinct || characters.iter().any(|symbol| symbol.is_whitespace()) {
+            return Err(GameError::Symbols { expected: self.names.len(), found: symbols.to_string() });
+        }
+
+        Ok(characters)
     }
 
-    fn score(&self) -> u32 {
-        self.base_score() + self.outcome_score()
+    pub fn moves(&self) -> impl DoubleEndedIterator<Item = Move> {
+        (0..self.names.len()).map(Move)
     }
 
-    fn base_score(&self) -> u32 {
-        self.you.base_score()
+    pub fn name(&self, of: Move) -> &str {
+        &self.names[of.0]
     }
 
-    fn outcome_score(&self) -> u32 {
-        self.outcome().score()
+    pub fn find(&self, name: &str) -> Option<Move> {
+        self.names.iter().position(|candidate| candidate == name).map(Move)
     }
 
-    fn outcome(&self) -> Outcome {
-        match (self.you, self.opponent) {
-            (Move::Rock, Move::Scissors) | (Move::Scissors, Move::Paper) | (Move::Paper, Move::Rock) => Outcome::Win,
-            (Move::Rock, Move::Rock) | (Move::Paper, Move::Paper) | (Move::Scissors, Move::Scissors) => Outcome::Draw,
-            (Move::Rock, Move::Paper) | (Move::Paper, Move::Scissors) | (Move::Scissors, Move::Rock) => Outcome::Loss
+    pub fn outcome(&self, you: Move, opponent: Move) -> Outcome {
+        let count = self.names.len();
+        match (you.0 + count - opponent.0) % count {
+            0 => Outcome::Draw,
+            distance if distance <= count / 2 => Outcome::Win,
+            _ => Outcome::Loss
         }
     }
+
+    pub fn score(&self, round: Round) -> u32 {
+        self.scoring.moves[round.you.0] + self.scoring.outcome(self.outcome(round.you, round.opponent))
+    }
+
+    // The best scoring move that ends the round the given way, with the
+    // earliest move in the cycle winning a tie.
+    pub fn response(&self, opponent: Move, outcome: Outcome) -> Move {
+        self.moves().rev()
+            .filter(|&you| self.outcome(you, opponent) == outcome)
+            .max_by_key(|&you| self.scoring.moves[you.0])
+            .unwrap()
+    }
+
+    // The best scoring move whatever the outcome.
+    pub fn best_response(&self, opponent: Move) -> Move {
+        self.moves().rev().max_by_key(|&you| self.score(Round { opponent, you })).unwrap()
+    }
+
+    // The guide that scores the most against the opponent's moves. Each move
+    // only has one best response, so they're worked out once up front.
+    pub fn optimal_guide(&self, opponent: &[Move]) -> Tournament<'_> {
+        let responses : Vec<Move> = self.moves().map(|opponent| self.best_response(opponent)).collect();
+        let rounds = opponent.iter().map(|&opponent| Round { opponent, you: responses[opponent.0] }).collect();
+
+        Tournament { game: self, rounds }
+    }
+
+    pub fn tournament(&self, input: &str, column: Column) -> Result<Tournament<'_>, ParseError> {
+        let rounds = input.lines().enumerate()
+            .map(|(index, line)| self.round(line, column).map_err(|error| error.with_line(index + 1)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Tournament { game: self, rounds })
+    }
+
+    // Only the first column of each line is read.
+    pub fn opponent_moves(&self, input: &str) -> Result<Vec<Move>, ParseError> {
+        input.lines().enumerate()
+            .map(|(index, line)| {
+                let symbol = line.split(' ').next().unwrap();
+                self.parse_move(&self.opponent_symbols, line, symbol).map_err(|error| error.with_line(index + 1))
+            })
+            .collect()
+    }
+
+    fn round(&self, input: &str, column: Column) -> Result<Round, ParseError> {
+        let (opponent, second) = split(input)?;
+        let opponent = self.parse_move(&self.opponent_symbols, input, opponent)?;
+        let you = match column {
+            Column::Move => self.parse_move(&self.your_symbols, input, second)?,
+            Column::Outcome => self.response(opponent, parse_symbol(input, second)?)
+        };
+
+        Ok(Round { opponent, you })
+    }
+
+    fn parse_move(&self, symbols: &[char], input: &str, symbol: &str) -> Result<Move, ParseError> {
+        let mut characters = symbol.chars();
+        match (characters.next(), characters.next()) {
+            (Some(character), None) => symbols.iter().position(|candidate| *candidate == character).map(Move),
+            _ => None
+        }.ok_or_else(|| ParseError::at_token(input, symbol, format!("unknown move symbol '{symbol}'")))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Round {
+    pub opponent: Move,
+    pub you: Move
+}
+
+fn parse_symbol<T: FromStr<Err = ParseError>>(input: &str, symbol: &str) -> Result<T, ParseError> {
+    symbol.parse().map_err(|error: ParseError| error.within(input, symbol))
+}
+
+fn split(input: &str) -> Result<(&str, &str), ParseError> {
+    match input.split(" ").collect::<Vec<&str>>()[..] {
+        [left, right] => Ok((left, right)),
+        _ => Err(ParseError::new(format!("invalid input for round: '{input}'")))
+    }
 }
 
-struct Tournament {
+pub struct Tournament<'a> {
+    game: &'a Game,
     rounds: Vec<Round>
 }
 
-impl Tournament {
-    fn new(input: &str, round_parser: fn(&str) -> Round) -> Self {
-        Self { rounds: input.lines().map(round_parser).collect() }
+impl Tournament<'_> {
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn score(&self) -> u32 {
+        self.rounds.iter().map(|&round| self.game.score(round)).sum()
     }
+}
+
+// Written back out as a guide of moves.
+impl fmt::Display for Tournament<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for round in &self.rounds {
+            writeln!(formatter, "{} {}", self.game.opponent_symbols[round.opponent.0], self.game.your_symbols[round.you.0])?;
+        }
 
-    fn score(&self) -> u32 {
-        self.rounds.iter().map(Round::score).sum()
+        Ok(())
     }
 }
 
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    Ok(Game::rock_paper_scissors().tournament(input, Column::Move)?.score())
+}
+
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    Ok(Game::rock_paper_scissors().tournament(input, Column::Outcome)?.score())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
     use super::*;
 
     #[test]
     fn part_one_examples() {
         assert_eq!(
-            Tournament::new(
-                fs::read_to_string("example_input.txt").unwrap().as_str(),
-                Round::new_round_one
-            ).score(),
+            Game::default().tournament(
+                fixtures::example("example").unwrap().input.as_str(),
+                Column::Move
+            ).unwrap().score(),
             15
         );
     }
@@ -135,21 +309,40 @@ mod tests {
     #[test]
     fn part_one() {
         assert_eq!(
-            Tournament::new(
-                fs::read_to_string("input.txt").unwrap().as_str(),
-                Round::new_round_one
-            ).score(),
+            Game::default().tournament(
+                fixtures::input(2022, 2).unwrap().as_str(),
+                Column::Move
+            ).unwrap().score(),
             13268
         );
     }
 
+    #[test]
+    fn invalid_rounds() {
+        let game = Game::default();
+        let error = game.tournament("A Y\nB W", Column::Move).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 3: unknown move symbol 'W'");
+
+        let error = game.tournament("A Y\nD X", Column::Outcome).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: unknown move symbol 'D'");
+
+        let error = game.tournament("A Y B", Column::Outcome).err().unwrap();
+        assert_eq!(error.to_string(), "line 1: invalid input for round: 'A Y B'");
+
+        let error = game.tournament("A A", Column::Move).err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 3: unknown move symbol 'A'");
+
+        let error = game.tournament("C W", Column::Outcome).err().unwrap();
+        assert_eq!(error.to_string(), "line 1, column 3: invalid outcome symbol 'W'");
+    }
+
     #[test]
     fn part_two_examples() {
         assert_eq!(
-            Tournament::new(
-                fs::read_to_string("example_input.txt").unwrap().as_str(),
-                Round::new_round_two
-            ).score(),
+            Game::default().tournament(
+                fixtures::example("example").unwrap().input.as_str(),
+                Column::Outcome
+            ).unwrap().score(),
             12
         );
     }
@@ -157,11 +350,86 @@ mod tests {
     #[test]
     fn part_two() {
         assert_eq!(
-            Tournament::new(
-                fs::read_to_string("input.txt").unwrap().as_str(),
-                Round::new_round_two
-            ).score(),
+            Game::default().tournament(
+                fixtures::input(2022, 2).unwrap().as_str(),
+                Column::Outcome
+            ).unwrap().score(),
             15508
         );
     }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let beats = |winner: &str, loser: &str| game.outcome(game.find(winner).unwrap(), game.find(loser).unwrap());
+
+        for (winner, loser) in [
+            ("scissors", "paper"), ("paper", "rock"), ("rock", "lizard"), ("lizard", "Spock"), ("Spock", "scissors"),
+            ("scissors", "lizard"), ("lizard", "paper"), ("paper", "Spock"), ("Spock", "rock"), ("rock", "scissors")
+        ] {
+            assert_eq!(beats(winner, loser), Outcome::Win, "{winner} against {loser}");
+            assert_eq!(beats(loser, winner), Outcome::Loss, "{loser} against {winner}");
+        }
+        assert_eq!(beats("lizard", "lizard"), Outcome::Draw);
+
+        // Five moves are written A to E and V to Z.
+        let tournament = game.tournament("A V\nB Z\nE W", Column::Move).unwrap();
+        assert_eq!(tournament.score(), 4 + 5 + 8);
+
+        // Either of two moves could win, and the better scoring one is picked.
+        let tournament = game.tournament("A Z\nC X", Column::Outcome).unwrap();
+        let names : Vec<&str> = tournament.rounds().iter().map(|round| game.name(round.you)).collect();
+        assert_eq!(names, vec!["paper", "Spock"]);
+    }
+
+    #[test]
+    fn invalid_games() {
+        assert_eq!(Game::new(&["rock", "paper"]).err().unwrap().to_string(), "a cyclic game needs an odd number of moves from 3 to 25, not 2");
+        assert_eq!(Game::new(&["rock"]).err(), Some(GameError::MoveCount(1)));
+        assert_eq!(Game::new(&["rock", "paper", "rock"]).err().unwrap().to_string(), "'rock' appears more than once");
+
+        let error = Game::default().with_symbols("RPS", "rps ").err().unwrap();
+        assert_eq!(error.to_string(), "expected 3 distinct symbols, found 'rps '");
+        assert!(Game::default().with_symbols("RPR", "rps").is_err());
+
+        let error = Game::default().with_scoring(Scoring { moves: vec![1, 2], win: 6, draw: 3, loss: 0 }).err().unwrap();
+        assert_eq!(error.to_string(), "expected a score for each of 3 moves, found 2");
+    }
+
+    #[test]
+    fn custom_symbols_and_scoring() {
+        let game = Game::default()
+            .with_symbols("rps", "RPS").unwrap()
+            .with_scoring(Scoring { moves: vec![10, 0, 0], win: 1, draw: 2, loss: 3 }).unwrap();
+
+        let tournament = game.tournament("r P\ns R", Column::Move).unwrap();
+        assert_eq!(tournament.score(), 1 + 11);
+        assert_eq!(tournament.to_string(), "r P\ns R\n");
+
+        // Losing scores best now, and rock scores best of all.
+        assert_eq!(game.best_response(game.find("paper").unwrap()), game.find("rock").unwrap());
+        assert_eq!(game.best_response(game.find("rock").unwrap()), game.find("rock").unwrap());
+    }
+
+    #[test]
+    fn optimal_guides() {
+        let game = Game::default();
+        let input = fixtures::input(2022, 2).unwrap();
+        let opponent = game.opponent_moves(&input).unwrap();
+        let guide = game.optimal_guide(&opponent);
+
+        // Winning always pays best with the standard scores.
+        assert!(guide.rounds().iter().all(|round| game.outcome(round.you, round.opponent) == Outcome::Win));
+        for column in [Column::Move, Column::Outcome] {
+            assert!(guide.score() >= game.tournament(&input, column).unwrap().score());
+        }
+        assert_eq!(game.tournament(&guide.to_string(), Column::Move).unwrap().rounds(), guide.rounds());
+
+        let example = game.opponent_moves("A Y\nB X\nC Z").unwrap();
+        assert_eq!(game.optimal_guide(&example).to_string(), "A Y\nB Z\nC X\n");
+        assert_eq!(game.optimal_guide(&example).score(), 8 + 9 + 7);
+
+        let error = game.opponent_moves("A Y\nQ").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: unknown move symbol 'Q'");
+    }
 }
@@ -0,0 +1,32 @@
+use std::fs;
+use std::time::Duration;
+use criterion::{criterion_group, criterion_main, Criterion};
+use aoc_runner::solutions;
+use fixtures::Answers;
+
+// Every implemented part on its real input, one group per day. Filter with
+// e.g. `cargo bench -p aoc_runner -- "2022 day 12"`.
+fn days(c: &mut Criterion) {
+    for solution in solutions() {
+        let answers = Answers::load(&Answers::path(solution.year)).unwrap();
+        if answers.day(solution.day).is_some_and(|answers| answers.skip.is_some()) {
+            continue;
+        }
+        let Ok(input) = fs::read_to_string(solution.default_input_path()) else { continue };
+
+        // Some parts take seconds, so keep the sample count down.
+        let mut group = c.benchmark_group(format!("{} day {}", solution.year, solution.day));
+        group.sample_size(10).warm_up_time(Duration::from_secs(1)).measurement_time(Duration::from_secs(5));
+
+        for part in [1, 2] {
+            if let Some(solver) = solution.part(part) {
+                group.bench_function(format!("part {part}"), |b| b.iter(|| solver(&input)));
+            }
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -0,0 +1,256 @@
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use parsing::ParseError;
+use serde::{Deserialize, Serialize};
+use crate::Solver;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Timing {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub runs: usize,
+    pub min_ns: u64,
+    pub median_ns: u64
+}
+
+// Identifies a part as (year, day, part).
+pub type PartKey = (u16, u8, u8);
+
+impl Timing {
+    pub fn key(&self) -> PartKey {
+        (self.year, self.day, self.part)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Run {
+    pub commit: String,
+    // Whether there were uncommitted changes when the run was made.
+    pub dirty: bool,
+    pub timestamp: u64,
+    pub profile: String,
+    pub timings: Vec<Timing>
+}
+
+impl Run {
+    // Stamps the timings with the current commit, so runs can be compared across commits.
+    pub fn new(timings: Vec<Timing>) -> Self {
+        let git = |args: &[&str]| {
+            process::Command::new("git").args(args).output().ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+
+        Self {
+            commit: git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| String::from("unknown")),
+            dirty: git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty()),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default(),
+            profile: String::from(if cfg!(debug_assertions) { "debug" } else { "release" }),
+            timings
+        }
+    }
+
+    fn timing(&self, key: PartKey) -> Option<&Timing> {
+        self.timings.iter().find(|timing| timing.key() == key)
+    }
+
+    fn label(&self) -> String {
+        if self.dirty {
+            format!("{}+", self.commit)
+        } else {
+            self.commit.clone()
+        }
+    }
+}
+
+// Saved benchmark runs, oldest first.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct History {
+    pub runs: Vec<Run>
+}
+
+impl History {
+    pub fn path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("bench_history.json")
+    }
+
+    // A missing file has no runs yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|error| format!("{}: {error}", path.display())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(format!("{}: {error}", path.display()))
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|error| error.to_string())?;
+        fs::write(path, json + "\n").map_err(|error| format!("{}: {error}", path.display()))
+    }
+
+    // The most recent saved timing for the same part, built with the same profile.
+    pub fn previous(&self, timing: &Timing, profile: &str) -> Option<(&Run, &Timing)> {
+        self.runs.iter().rev()
+            .filter(|run| run.profile == profile)
+            .find_map(|run| run.timing(timing.key()).map(|previous| (run, previous)))
+    }
+
+    // Every saved timing for the part, oldest first.
+    pub fn series(&self, key: PartKey) -> impl Iterator<Item = (&Run, &Timing)> {
+        self.runs.iter().filter_map(move |run| run.timing(key).map(|timing| (run, timing)))
+    }
+}
+
+// Runs a part `runs` times after one untimed warm up run.
+pub fn time_part(year: u16, day: u8, part: u8, solver: Solver, input: &str, runs: usize) -> Result<Timing, ParseError> {
+    solver(input)?;
+
+    let mut durations : Vec<Duration> = (0..runs.max(1)).map(|_| {
+        let start = Instant::now();
+        let _ = solver(input);
+        start.elapsed()
+    }).collect();
+    durations.sort();
+
+    let nanoseconds = |duration: Duration| duration.as_nanos().min(u64::MAX as u128) as u64;
+    Ok(Timing {
+        year,
+        day,
+        part,
+        runs: durations.len(),
+        min_ns: nanoseconds(durations[0]),
+        median_ns: nanoseconds(durations[durations.len() / 2])
+    })
+}
+
+fn format_duration(nanoseconds: u64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanoseconds))
+}
+
+fn format_change(previous: u64, current: u64) -> String {
+    let change = (current as f64 - previous as f64) / previous.max(1) as f64 * 100.0;
+    format!("{change:+.1}%")
+}
+
+// Compares each timing with the last saved run of the same part.
+pub fn bench_table(timings: &[Timing], history: &History, profile: &str) -> String {
+    let header = ["year", "day", "part", "median", "min", "previous", "change"].map(str::to_string);
+    let rows : Vec<[String; 7]> = timings.iter().map(|timing| {
+        let previous = history.previous(timing, profile);
+        [
+            timing.year.to_string(),
+            timing.day.to_string(),
+            timing.part.to_string(),
+            format_duration(timing.median_ns),
+            format_duration(timing.min_ns),
+            previous.map(|(run, previous)| format!("{} ({})", format_duration(previous.median_ns), run.label())).unwrap_or_default(),
+            previous.map(|(_, previous)| format_change(previous.median_ns, timing.median_ns)).unwrap_or_default()
+        ]
+    }).collect();
+
+    crate::check::format_table(header, rows)
+}
+
+// One line per saved run of each part, showing how its median changed over time.
+pub fn history_table(history: &History, parts: &[PartKey]) -> String {
+    let header = ["year", "day", "part", "commit", "profile", "median", "change"].map(str::to_string);
+    let mut rows : Vec<[String; 7]> = vec![];
+
+    for &key in parts {
+        let mut previous_median = None;
+        for (run, saved) in history.series(key) {
+            rows.push([
+                saved.year.to_string(),
+                saved.day.to_string(),
+                saved.part.to_string(),
+                run.label(),
+                run.profile.clone(),
+                format_duration(saved.median_ns),
+                previous_median.map(|previous| format_change(previous, saved.median_ns)).unwrap_or_default()
+            ]);
+            previous_median = Some(saved.median_ns);
+        }
+    }
+
+    crate::check::format_table(header, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::*;
+
+    fn timing(day: u8, part: u8, median_ns: u64) -> Timing {
+        Timing { year: 2022, day, part, runs: 5, min_ns: median_ns.saturating_sub(100), median_ns }
+    }
+
+    fn run(commit: &str, profile: &str, timings: Vec<Timing>) -> Run {
+        Run { commit: commit.to_string(), dirty: false, timestamp: 0, profile: profile.to_string(), timings }
+    }
+
+    #[test]
+    fn times_a_part() {
+        let solver : Solver = |input: &str| Ok(input.len().to_string());
+        let timing = time_part(2022, 1, 2, solver, "abc", 3).unwrap();
+
+        assert_eq!((timing.year, timing.day, timing.part, timing.runs), (2022, 1, 2, 3));
+        assert!(timing.min_ns <= timing.median_ns);
+
+        let failing : Solver = |_input: &str| Err(ParseError::new("oops"));
+        assert_eq!(time_part(2022, 1, 1, failing, "abc", 3).err().unwrap().to_string(), "oops");
+    }
+
+    #[test]
+    fn finds_previous_timings() {
+        let history = History {
+            runs: vec![
+                run("aaaaaaa", "release", vec![timing(1, 1, 2_000), timing(1, 2, 5_000)]),
+                run("bbbbbbb", "release", vec![timing(1, 1, 1_000)]),
+                run("ccccccc", "debug", vec![timing(1, 1, 9_000)])
+            ]
+        };
+
+        let (run, previous) = history.previous(&timing(1, 1, 0), "release").unwrap();
+        assert_eq!((run.commit.as_str(), previous.median_ns), ("bbbbbbb", 1_000));
+        assert_eq!(history.previous(&timing(1, 2, 0), "release").unwrap().0.commit, "aaaaaaa");
+        assert_eq!(history.previous(&timing(2, 1, 0), "release"), None);
+        assert_eq!(history.series((2022, 1, 1)).count(), 3);
+    }
+
+    #[test]
+    fn formats_tables() {
+        let history = History { runs: vec![run("aaaaaaa", "release", vec![timing(1, 1, 2_000)])] };
+
+        assert_eq!(
+            bench_table(&[timing(1, 1, 3_000), timing(1, 2, 1_000)], &history, "release"),
+            "year  day  part  median  min      previous         change\n\
+             2022    1     1  3.0µs   2.9µs    2.0µs (aaaaaaa)  +50.0%\n\
+             2022    1     2  1.0µs   900.0ns\n"
+        );
+
+        let history = History { runs: vec![history.runs[0].clone(), Run { dirty: true, ..run("bbbbbbb", "release", vec![timing(1, 1, 1_000)]) }] };
+        assert_eq!(
+            history_table(&history, &[(2022, 1, 1)]),
+            "year  day  part  commit    profile  median  change\n\
+             2022    1     1  aaaaaaa   release  2.0µs\n\
+             2022    1     1  bbbbbbb+  release  1.0µs   -50.0%\n"
+        );
+    }
+
+    #[test]
+    fn load_and_save() {
+        let path = env::temp_dir().join(format!("aoc-bench-history-{}.json", std::process::id()));
+        assert_eq!(History::load(&path).unwrap(), History::default());
+
+        let history = History { runs: vec![run("aaaaaaa", "release", vec![timing(1, 1, 2_000)])] };
+        history.save(&path).unwrap();
+        assert_eq!(History::load(&path).unwrap(), history);
+
+        fs::write(&path, "{").unwrap();
+        assert!(History::load(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
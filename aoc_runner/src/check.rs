@@ -0,0 +1,215 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use fixtures::DayAnswers;
+use crate::Solution;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing,
+    Unimplemented,
+    Skipped
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Missing => "missing",
+            Status::Unimplemented => "unimplemented",
+            Status::Skipped => "skipped"
+        };
+        formatter.pad(name)
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+    // The computed answer, or what went wrong computing it.
+    pub answer: Option<Result<String, String>>,
+    pub expected: Option<String>,
+    pub elapsed: Option<Duration>
+}
+
+impl Check {
+    fn new(solution: &Solution, part: u8, status: Status) -> Self {
+        Self { year: solution.year, day: solution.day, part, status, answer: None, expected: None, elapsed: None }
+    }
+
+    pub fn computed_answer(&self) -> Option<&str> {
+        match &self.answer {
+            Some(Ok(answer)) => Some(answer),
+            _ => None
+        }
+    }
+}
+
+// Runs one part against the day's input and compares it with the recorded answer.
+pub fn check_part(solution: &Solution, part: u8, input: &Result<String, String>, answers: Option<&DayAnswers>) -> Check {
+    let Some(solver) = solution.part(part) else {
+        return Check::new(solution, part, Status::Unimplemented);
+    };
+
+    let expected = answers.and_then(|answers| answers.answer(part)).map(str::to_string);
+    if let Some(reason) = answers.and_then(|answers| answers.skip.as_ref()) {
+        return Check { answer: Some(Err(reason.clone())), expected, ..Check::new(solution, part, Status::Skipped) };
+    }
+
+    let (answer, elapsed) = match input {
+        Ok(input) => {
+            let start = Instant::now();
+            let answer = solver(input).map_err(|error| error.to_string());
+            (answer, Some(start.elapsed()))
+        },
+        Err(error) => (Err(error.clone()), None)
+    };
+
+    let status = match (&answer, &expected) {
+        (Err(_), _) => Status::Fail,
+        (Ok(_), None) => Status::Missing,
+        (Ok(answer), Some(expected)) if answer.trim_end() == expected => Status::Pass,
+        (Ok(_), Some(_)) => Status::Fail
+    };
+
+    Check { answer: Some(answer), expected, elapsed, ..Check::new(solution, part, status) }
+}
+
+// Multi-line answers only show their first line.
+fn summarize(text: &str) -> String {
+    let mut lines = text.trim_end().lines();
+    let first = lines.next().unwrap_or_default();
+    if lines.next().is_some() {
+        format!("{first} …")
+    } else {
+        first.to_string()
+    }
+}
+
+pub fn table(checks: &[Check]) -> String {
+    let header = ["year", "day", "part", "status", "answer", "expected", "time"].map(str::to_string);
+    let rows : Vec<[String; 7]> = checks.iter().map(|check| {
+        [
+            check.year.to_string(),
+            check.day.to_string(),
+            check.part.to_string(),
+            check.status.to_string(),
+            match &check.answer {
+                Some(Ok(answer)) => summarize(answer),
+                Some(Err(error)) => summarize(error),
+                None => String::new()
+            },
+            check.expected.as_deref().map(summarize).unwrap_or_default(),
+            check.elapsed.map(|elapsed| format!("{elapsed:.1?}")).unwrap_or_default()
+        ]
+    }).collect();
+
+    format_table(header, rows)
+}
+
+// Lines up columns, with the leading year, day and part columns on the right.
+pub(crate) fn format_table<const N: usize>(header: [String; N], rows: Vec<[String; N]>) -> String {
+    let mut widths = header.clone().map(|column| column.chars().count());
+    for row in rows.iter() {
+        for (width, column) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in [header].iter().chain(rows.iter()) {
+        let columns : Vec<String> = row.iter().zip(widths.iter()).enumerate().map(|(index, (column, &width))| {
+            if index < 3 {
+                format!("{column:>width$}")
+            } else {
+                format!("{column:<width$}")
+            }
+        }).collect();
+        table.push_str(columns.join("  ").trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+pub fn summary(checks: &[Check]) -> String {
+    let count = |status: Status| checks.iter().filter(|check| check.status == status).count();
+
+    format!(
+        "{} passed, {} failed, {} missing, {} unimplemented, {} skipped",
+        count(Status::Pass), count(Status::Fail), count(Status::Missing), count(Status::Unimplemented), count(Status::Skipped)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use parsing::ParseError;
+    use super::*;
+
+    fn solution() -> Solution {
+        Solution::new(
+            2022,
+            1,
+            "2022/day_one",
+            Some(|input: &str| input.trim().parse::<u32>().map(|number| (number * 2).to_string()).map_err(|_| ParseError::new("not a number"))),
+            None
+        )
+    }
+
+    fn answers(part_one: &str) -> DayAnswers {
+        DayAnswers { part_one: Some(part_one.to_string()), ..DayAnswers::default() }
+    }
+
+    #[test]
+    fn statuses() {
+        let input = Ok(String::from("21\n"));
+
+        assert_eq!(check_part(&solution(), 1, &input, Some(&answers("42"))).status, Status::Pass);
+        assert_eq!(check_part(&solution(), 1, &input, Some(&answers("41"))).status, Status::Fail);
+        assert_eq!(check_part(&solution(), 1, &input, None).status, Status::Missing);
+        assert_eq!(check_part(&solution(), 2, &input, Some(&answers("42"))).status, Status::Unimplemented);
+
+        let skipped = DayAnswers { skip: Some(String::from("too slow")), ..answers("42") };
+        let check = check_part(&solution(), 1, &input, Some(&skipped));
+        assert_eq!(check.status, Status::Skipped);
+        assert_eq!(check.elapsed, None);
+    }
+
+    #[test]
+    fn errors_fail() {
+        let check = check_part(&solution(), 1, &Ok(String::from("lots")), Some(&answers("42")));
+        assert_eq!(check.status, Status::Fail);
+        assert_eq!(check.answer, Some(Err(String::from("not a number"))));
+        assert_eq!(check.computed_answer(), None);
+
+        let check = check_part(&solution(), 1, &Err(String::from("no input")), None);
+        assert_eq!(check.status, Status::Fail);
+    }
+
+    #[test]
+    fn formats_a_table() {
+        let mut checks = vec![
+            check_part(&solution(), 1, &Ok(String::from("21")), Some(&answers("42"))),
+            check_part(&solution(), 1, &Ok(String::from("5")), None),
+            check_part(&solution(), 2, &Ok(String::from("5")), None)
+        ];
+        checks[1].answer = Some(Ok(String::from("##..\n.##.")));
+        for check in checks.iter_mut() {
+            check.elapsed = None;
+        }
+
+        assert_eq!(
+            table(&checks),
+            "year  day  part  status         answer  expected  time\n\
+             2022    1     1  pass           42      42\n\
+             2022    1     1  missing        ##.. …\n\
+             2022    1     2  unimplemented\n"
+        );
+        assert_eq!(summary(&checks), "1 passed, 0 failed, 1 missing, 1 unimplemented, 0 skipped");
+    }
+}
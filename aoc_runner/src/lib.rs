@@ -0,0 +1,692 @@
+mod bench;
+mod check;
+mod solve;
+
+use std::path::{Path, PathBuf};
+use fixtures::{Example, InputStore};
+use parsing::ParseError;
+use render::Recorder;
+
+pub use bench::{bench_table, history_table, time_part, History, PartKey, Run, Timing};
+pub use check::{check_part, summary, table, Check, Status};
+pub use solve::{read_input, solve_part, DayAnswer, PartAnswer};
+
+pub type Solver = fn(&str) -> Result<String, ParseError>;
+
+// Records a day's simulation running on the given input.
+pub type Animation = fn(&str, &mut Recorder) -> Result<(), ParseError>;
+
+macro_rules! solver {
+    ($day:ident :: $part:ident) => {
+        Some(|input: &str| $day::$part(input).map(|answer| answer.to_string()))
+    };
+}
+
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub directory: &'static str,
+    pub part_one: Option<Solver>,
+    pub part_two: Option<Solver>,
+    pub animations: [Option<Animation>; 2]
+}
+
+impl Solution {
+    fn new(year: u16, day: u8, directory: &'static str, part_one: Option<Solver>, part_two: Option<Solver>) -> Self {
+        Self { year, day, directory, part_one, part_two, animations: [None, None] }
+    }
+
+    fn animated(mut self, part: u8, animation: Animation) -> Self {
+        self.animations[part as usize - 1] = Some(animation);
+        self
+    }
+
+    // Without a part, the first part with an animation.
+    pub fn animation(&self, part: Option<u8>) -> Option<Animation> {
+        match part {
+            Some(part @ 1..=2) => self.animations[part as usize - 1],
+            Some(_) => None,
+            None => self.animations.iter().flatten().next().copied()
+        }
+    }
+
+    pub fn part(&self, part: u8) -> Option<Solver> {
+        match part {
+            1 => self.part_one,
+            2 => self.part_two,
+            _ => None
+        }
+    }
+
+    pub fn default_input_path(&self) -> PathBuf {
+        InputStore::default().path(self.year, self.day)
+    }
+
+    pub fn examples_directory(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join(self.directory).join("examples")
+    }
+
+    pub fn examples(&self) -> Result<Vec<Example>, fixtures::Error> {
+        fixtures::examples_in(&self.examples_directory())
+    }
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2015, 1, "2015/day_one", solver!(y2015_day_one::part_one), solver!(y2015_day_one::part_two)),
+        Solution::new(2015, 2, "2015/day_two", solver!(y2015_day_two::part_one), solver!(y2015_day_two::part_two)),
+        Solution::new(2015, 3, "2015/day_three", solver!(y2015_day_three::part_one), solver!(y2015_day_three::part_two)),
+        Solution::new(2015, 4, "2015/day_four", solver!(y2015_day_four::part_one), solver!(y2015_day_four::part_two)),
+        Solution::new(2015, 5, "2015/day_five", solver!(y2015_day_five::part_one), solver!(y2015_day_five::part_two)),
+        Solution::new(2015, 6, "2015/day_six", solver!(y2015_day_six::part_one), solver!(y2015_day_six::part_two))
+            .animated(1, y2015_day_six::animate_part_one)
+            .animated(2, y2015_day_six::animate_part_two),
+        Solution::new(2015, 7, "2015/day_seven", solver!(y2015_day_seven::part_one), solver!(y2015_day_seven::part_two)),
+        Solution::new(2015, 8, "2015/day_eight", solver!(y2015_day_eight::part_one), solver!(y2015_day_eight::part_two)),
+
+        Solution::new(2022, 1, "2022/day_one", solver!(y2022_day_one::part_one), solver!(y2022_day_one::part_two)),
+        Solution::new(2022, 2, "2022/day_two", solver!(y2022_day_two::part_one), solver!(y2022_day_two::part_two)),
+        Solution::new(2022, 3, "2022/day_three", solver!(y2022_day_three::part_one), solver!(y2022_day_three::part_two)),
+        Solution::new(2022, 4, "2022/day_four", solver!(y2022_day_four::part_one), solver!(y2022_day_four::part_two)),
+        Solution::new(2022, 5, "2022/day_five", solver!(y2022_day_five::part_one), solver!(y2022_day_five::part_two)),
+        Solution::new(2022, 6, "2022/day_six", solver!(y2022_day_six::part_one), solver!(y2022_day_six::part_two)),
+        Solution::new(2022, 7, "2022/day_seven", solver!(y2022_day_seven::part_one), solver!(y2022_day_seven::part_two)),
+        Solution::new(2022, 8, "2022/day_eight", solver!(y2022_day_eight::part_one), solver!(y2022_day_eight::part_two)).animated(1, y2022_day_eight::animate),
+        Solution::new(2022, 9, "2022/day_nine", solver!(y2022_day_nine::part_one), solver!(y2022_day_nine::part_two)).animated(2, y2022_day_nine::animate),
+        Solution::new(2022, 10, "2022/day_ten", solver!(y2022_day_ten::part_one), solver!(y2022_day_ten::part_two)),
+        Solution::new(2022, 11, "2022/day_eleven", solver!(y2022_day_eleven::part_one), solver!(y2022_day_eleven::part_two)),
+        Solution::new(2022, 12, "2022/day_twelve", solver!(y2022_day_twelve::part_one), solver!(y2022_day_twelve::part_two)).animated(1, y2022_day_twelve::animate),
+        Solution::new(2022, 13, "2022/day_thirteen", solver!(y2022_day_thirteen::part_one), solver!(y2022_day_thirteen::part_two)),
+        Solution::new(2022, 14, "2022/day_fourteen", solver!(y2022_day_fourteen::part_one), solver!(y2022_day_fourteen::part_two)).animated(1, y2022_day_fourteen::animate),
+        Solution::new(2022, 15, "2022/day_fifteen", solver!(y2022_day_fifteen::part_one), solver!(y2022_day_fifteen::part_two)),
+        Solution::new(2022, 16, "2022/day_sixteen", solver!(y2022_day_sixteen::part_one), None),
+        Solution::new(2022, 17, "2022/day_seventeen", solver!(y2022_day_seventeen::part_one), None).animated(1, y2022_day_seventeen::animate),
+        Solution::new(2022, 18, "2022/day_eighteen", solver!(y2022_day_eighteen::part_one), solver!(y2022_day_eighteen::part_two)),
+        Solution::new(2022, 19, "2022/day_nineteen", None, None),
+        Solution::new(2022, 20, "2022/day_twenty", None, None),
+        Solution::new(2022, 21, "2022/day_twenty_one", solver!(y2022_day_twenty_one::part_one), solver!(y2022_day_twenty_one::part_two)),
+        Solution::new(2022, 22, "2022/day_twenty_two", solver!(y2022_day_twenty_two::part_one), solver!(y2022_day_twenty_two::part_two)),
+        Solution::new(2022, 23, "2022/day_twenty_three", solver!(y2022_day_twenty_three::part_one), solver!(y2022_day_twenty_three::part_two)).animated(2, y2022_day_twenty_three::animate),
+        Solution::new(2022, 24, "2022/day_twenty_four", solver!(y2022_day_twenty_four::part_one), None).animated(1, y2022_day_twenty_four::animate),
+        Solution::new(2022, 25, "2022/day_twenty_five", None, None),
+    ]
+}
+
+pub fn find_solution(year: u16, day: u8) -> Option<Solution> {
+    solutions().into_iter().find(|solution| solution.year == year && solution.day == day)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Solve(Options),
+    Fetch(FetchOptions),
+    Check(CheckOptions),
+    Bench(BenchOptions),
+    Animate(AnimateOptions),
+    Replay(ReplayOptions),
+    Export(ExportOptions)
+}
+
+impl Command {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        match args.split_first() {
+            Some((command, rest)) if command == "fetch" => FetchOptions::parse(rest).map(Command::Fetch),
+            Some((command, rest)) if command == "check" => CheckOptions::parse(rest).map(Command::Check),
+            Some((command, rest)) if command == "bench" => BenchOptions::parse(rest).map(Command::Bench),
+            Some((command, rest)) if command == "animate" => AnimateOptions::parse(rest).map(Command::Animate),
+            Some((command, rest)) if command == "replay" => ReplayOptions::parse(rest).map(Command::Replay),
+            Some((command, rest)) if command == "export" => ExportOptions::parse(rest).map(Command::Export),
+            _ => Options::parse(args).map(Command::Solve)
+        }
+    }
+}
+
+fn parse_year_and_day(positional: &[&str], usage: &str) -> Result<(u16, u8), String> {
+    if positional.len() != 2 {
+        return Err(String::from(usage));
+    }
+
+    let year = positional[0].parse::<u16>().map_err(|_| format!("Invalid year: {}", positional[0]))?;
+    let day = positional[1].parse::<u8>().map_err(|_| format!("Invalid day: {}", positional[1]))?;
+
+    Ok((year, day))
+}
+
+// Leaving out the year selects every year, and leaving out the day selects every day.
+fn parse_selection(positional: &[&str], usage: &str) -> Result<(Option<u16>, Option<u8>), String> {
+    match positional.len() {
+        0 => Ok((None, None)),
+        1 => Ok((Some(positional[0].parse::<u16>().map_err(|_| format!("Invalid year: {}", positional[0]))?), None)),
+        _ => parse_year_and_day(positional, usage).map(|(year, day)| (Some(year), Some(day)))
+    }
+}
+
+fn parse_count(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("{flag} needs a value"))?;
+    value.parse().ok().filter(|&count| count > 0).ok_or_else(|| format!("Invalid count for {flag}: {value}"))
+}
+
+fn parse_part(value: Option<&String>) -> Result<u8, String> {
+    let value = value.ok_or("--part needs a value")?;
+    match value.as_str() {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("Invalid part: {value}"))
+    }
+}
+
+fn is_selected(year: Option<u16>, day: Option<u8>, solution: &Solution) -> bool {
+    year.is_none_or(|year| year == solution.year) && day.is_none_or(|day| day == solution.day)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub json: bool
+}
+
+// An input of "-" is read from stdin.
+impl Options {
+    pub fn usage() -> &'static str {
+        "usage: aoc <year> <day> [--part <1|2>] [--input <path|->] [--json]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut part = None;
+        let mut input = None;
+        let mut json = false;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" | "-p" => part = Some(parse_part(args.next())?),
+                "--input" | "-i" => {
+                    let value = args.next().ok_or("--input needs a value")?;
+                    input = Some(PathBuf::from(value));
+                },
+                "--json" => json = true,
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        let (year, day) = parse_year_and_day(&positional, Self::usage())?;
+
+        Ok(Self { year, day, part, input, json })
+    }
+}
+
+// The server and session fall back to AOC_SERVER and AOC_SESSION when left out.
+#[derive(Debug, PartialEq)]
+pub struct FetchOptions {
+    pub year: u16,
+    pub day: u8,
+    pub server: Option<String>,
+    pub session: Option<String>
+}
+
+impl FetchOptions {
+    pub fn usage() -> &'static str {
+        "usage: aoc fetch <year> <day> [--server <url>] [--session <token>]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut server = None;
+        let mut session = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--server" => {
+                    server = Some(args.next().ok_or("--server needs a value")?.clone());
+                },
+                "--session" => {
+                    session = Some(args.next().ok_or("--session needs a value")?.clone());
+                },
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        let (year, day) = parse_year_and_day(&positional, Self::usage())?;
+
+        Ok(Self { year, day, server, session })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CheckOptions {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub record: bool
+}
+
+impl CheckOptions {
+    pub fn usage() -> &'static str {
+        "usage: aoc check [<year> [<day>]] [--record]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut record = false;
+
+        for arg in args {
+            match arg.as_str() {
+                "--record" => record = true,
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        let (year, day) = parse_selection(&positional, Self::usage())?;
+
+        Ok(Self { year, day, record })
+    }
+
+    pub fn includes(&self, solution: &Solution) -> bool {
+        is_selected(self.year, self.day, solution)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub runs: usize,
+    pub save: bool,
+    pub history: bool
+}
+
+impl BenchOptions {
+    pub fn usage() -> &'static str {
+        "usage: aoc bench [<year> [<day>]] [--runs <count>] [--save] [--history]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut runs = 5;
+        let mut save = false;
+        let mut history = false;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--runs" => {
+                    let value = args.next().ok_or("--runs needs a value")?;
+                    runs = value.parse().ok().filter(|&runs| runs > 0).ok_or_else(|| format!("Invalid run count: {value}"))?;
+                },
+                "--save" => save = true,
+                "--history" => history = true,
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        let (year, day) = parse_selection(&positional, Self::usage())?;
+
+        Ok(Self { year, day, runs, save, history })
+    }
+
+    pub fn includes(&self, solution: &Solution) -> bool {
+        is_selected(self.year, self.day, solution)
+    }
+}
+
+// Without --dump the frames are played back in the terminal, either with a
+// delay between them or, with --step, one frame per press of enter.
+#[derive(Debug, PartialEq)]
+pub struct AnimateOptions {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub every: usize,
+    pub limit: Option<usize>,
+    pub delay: u64,
+    pub step: bool,
+    pub dump: Option<PathBuf>
+}
+
+impl AnimateOptions {
+    pub fn usage() -> &'static str {
+        "usage: aoc animate <year> <day> [--part <1|2>] [--input <path>] [--every <n>] [--limit <frames>] [--delay <ms>] [--step] [--dump <directory>]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut part = None;
+        let mut input = None;
+        let mut every = 1;
+        let mut limit = None;
+        let mut delay = 100;
+        let mut step = false;
+        let mut dump = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" | "-p" => part = Some(parse_part(args.next())?),
+                "--input" | "-i" => {
+                    input = Some(PathBuf::from(args.next().ok_or("--input needs a value")?));
+                },
+                "--every" => every = parse_count("--every", args.next())?,
+                "--limit" => limit = Some(parse_count("--limit", args.next())?),
+                "--delay" => {
+                    let value = args.next().ok_or("--delay needs a value")?;
+                    delay = value.parse().map_err(|_| format!("Invalid delay: {value}"))?;
+                },
+                "--step" => step = true,
+                "--dump" => {
+                    dump = Some(PathBuf::from(args.next().ok_or("--dump needs a value")?));
+                },
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        let (year, day) = parse_year_and_day(&positional, Self::usage())?;
+
+        Ok(Self { year, day, part, input, every, limit, delay, step, dump })
+    }
+}
+
+// A PNG shows how a simulation ends, and a GIF every recorded frame of it.
+// The colours in a --palette file replace the day's own for their symbols.
+#[derive(Debug, PartialEq)]
+pub struct ExportOptions {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub output: PathBuf,
+    pub format: ImageFormat,
+    pub cell_size: usize,
+    pub palette: Option<PathBuf>,
+    pub every: usize,
+    pub limit: Option<usize>,
+    pub delay: u64
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Gif
+}
+
+impl ImageFormat {
+    fn of(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("png") => Ok(ImageFormat::Png),
+            Some(extension) if extension.eq_ignore_ascii_case("gif") => Ok(ImageFormat::Gif),
+            _ => Err(format!("Unknown image format for {}, use .png or .gif", path.display()))
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn usage() -> &'static str {
+        "usage: aoc export <year> <day> --output <file.png|file.gif> [--part <1|2>] [--input <path>] [--cell <pixels>] [--palette <path>] [--every <n>] [--limit <frames>] [--delay <ms>]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut part = None;
+        let mut input = None;
+        let mut output = None;
+        let mut cell_size = 4;
+        let mut palette = None;
+        let mut every = 1;
+        let mut limit = None;
+        let mut delay = 100;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" | "-p" => part = Some(parse_part(args.next())?),
+                "--input" | "-i" => {
+                    input = Some(PathBuf::from(args.next().ok_or("--input needs a value")?));
+                },
+                "--output" | "-o" => {
+                    output = Some(PathBuf::from(args.next().ok_or("--output needs a value")?));
+                },
+                "--cell" => cell_size = parse_count("--cell", args.next())?,
+                "--palette" => {
+                    palette = Some(PathBuf::from(args.next().ok_or("--palette needs a value")?));
+                },
+                "--every" => every = parse_count("--every", args.next())?,
+                "--limit" => limit = Some(parse_count("--limit", args.next())?),
+                "--delay" => {
+                    let value = args.next().ok_or("--delay needs a value")?;
+                    delay = value.parse().map_err(|_| format!("Invalid delay: {value}"))?;
+                },
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        let (year, day) = parse_year_and_day(&positional, Self::usage())?;
+        let output = output.ok_or(Self::usage())?;
+        let format = ImageFormat::of(&output)?;
+
+        Ok(Self { year, day, part, input, output, format, cell_size, palette, every, limit, delay })
+    }
+}
+
+// Plays back frames saved with `aoc animate --dump`.
+#[derive(Debug, PartialEq)]
+pub struct ReplayOptions {
+    pub directory: PathBuf,
+    pub delay: u64,
+    pub step: bool
+}
+
+impl ReplayOptions {
+    pub fn usage() -> &'static str {
+        "usage: aoc replay <directory> [--delay <ms>] [--step]"
+    }
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional : Vec<&str> = vec![];
+        let mut delay = 100;
+        let mut step = false;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--delay" => {
+                    let value = args.next().ok_or("--delay needs a value")?;
+                    delay = value.parse().map_err(|_| format!("Invalid delay: {value}"))?;
+                },
+                "--step" => step = true,
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                value => positional.push(value)
+            }
+        }
+
+        match positional[..] {
+            [directory] => Ok(Self { directory: PathBuf::from(directory), delay, step }),
+            _ => Err(String::from(Self::usage()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use super::*;
+
+    fn args(input: &str) -> Vec<String> {
+        input.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parse_year_and_day() {
+        assert_eq!(
+            Options::parse(&args("2022 17")),
+            Ok(Options { year: 2022, day: 17, part: None, input: None, json: false })
+        );
+    }
+
+    #[test]
+    fn parse_part_and_input() {
+        assert_eq!(
+            Options::parse(&args("2022 17 --part 2 --input example.txt")),
+            Ok(Options { year: 2022, day: 17, part: Some(2), input: Some(PathBuf::from("example.txt")), json: false })
+        );
+        assert_eq!(
+            Options::parse(&args("2015 4 --input - --json")),
+            Ok(Options { year: 2015, day: 4, part: None, input: Some(PathBuf::from("-")), json: true })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_arguments() {
+        assert!(Options::parse(&args("2022")).is_err());
+        assert!(Options::parse(&args("2022 17 --part 3")).is_err());
+        assert!(Options::parse(&args("2022 17 --input")).is_err());
+        assert!(Options::parse(&args("2022 17 --verbose")).is_err());
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            Command::parse(&args("2022 17 --part 1")),
+            Ok(Command::Solve(Options { year: 2022, day: 17, part: Some(1), input: None, json: false }))
+        );
+        assert_eq!(
+            Command::parse(&args("fetch 2015 8 --server http://localhost:8080 --session abc")),
+            Ok(Command::Fetch(FetchOptions {
+                year: 2015,
+                day: 8,
+                server: Some(String::from("http://localhost:8080")),
+                session: Some(String::from("abc"))
+            }))
+        );
+        assert_eq!(Command::parse(&args("fetch 2015")), Err(String::from(FetchOptions::usage())));
+        assert_eq!(Command::parse(&args("fetch 2015 8 --part 1")), Err(String::from("Unknown option: --part")));
+        assert_eq!(Command::parse(&args("")), Err(String::from(Options::usage())));
+        assert_eq!(
+            Command::parse(&args("check 2022 --record")),
+            Ok(Command::Check(CheckOptions { year: Some(2022), day: None, record: true }))
+        );
+        assert_eq!(Command::parse(&args("check")), Ok(Command::Check(CheckOptions { year: None, day: None, record: false })));
+        assert_eq!(Command::parse(&args("check 2022 1 2")), Err(String::from(CheckOptions::usage())));
+        assert_eq!(
+            Command::parse(&args("bench 2015 4 --runs 3 --save")),
+            Ok(Command::Bench(BenchOptions { year: Some(2015), day: Some(4), runs: 3, save: true, history: false }))
+        );
+        assert_eq!(Command::parse(&args("bench --runs 0")), Err(String::from("Invalid run count: 0")));
+        assert_eq!(
+            Command::parse(&args("animate 2022 14 --every 10 --limit 50 --dump frames")),
+            Ok(Command::Animate(AnimateOptions {
+                year: 2022,
+                day: 14,
+                part: None,
+                input: None,
+                every: 10,
+                limit: Some(50),
+                delay: 100,
+                step: false,
+                dump: Some(PathBuf::from("frames"))
+            }))
+        );
+        assert_eq!(Command::parse(&args("animate 2022 14 --every 0")), Err(String::from("Invalid count for --every: 0")));
+        assert_eq!(
+            Command::parse(&args("replay frames --delay 20 --step")),
+            Ok(Command::Replay(ReplayOptions { directory: PathBuf::from("frames"), delay: 20, step: true }))
+        );
+        assert_eq!(Command::parse(&args("replay")), Err(String::from(ReplayOptions::usage())));
+        assert_eq!(
+            Command::parse(&args("export 2015 6 --part 2 --output lights.gif --cell 1 --palette warm.txt --every 20")),
+            Ok(Command::Export(ExportOptions {
+                year: 2015,
+                day: 6,
+                part: Some(2),
+                input: None,
+                output: PathBuf::from("lights.gif"),
+                format: ImageFormat::Gif,
+                cell_size: 1,
+                palette: Some(PathBuf::from("warm.txt")),
+                every: 20,
+                limit: None,
+                delay: 100
+            }))
+        );
+        assert_eq!(Command::parse(&args("export 2022 14")), Err(String::from(ExportOptions::usage())));
+        assert_eq!(
+            Command::parse(&args("export 2022 14 --output cave.jpg")),
+            Err(String::from("Unknown image format for cave.jpg, use .png or .gif"))
+        );
+        assert_eq!(Command::parse(&args("export 2022 14 -o cave.PNG --cell 0")), Err(String::from("Invalid count for --cell: 0")));
+    }
+
+    #[test]
+    fn solutions_are_unique() {
+        let keys : HashSet<(u16, u8)> = solutions().iter().map(|solution| (solution.year, solution.day)).collect();
+        assert_eq!(keys.len(), solutions().len());
+    }
+
+    #[test]
+    fn default_input_paths_exist() {
+        for solution in solutions() {
+            if solution.part_one.is_some() {
+                assert!(solution.default_input_path().exists(), "{}", solution.directory);
+            }
+        }
+    }
+
+    #[test]
+    fn examples_produce_their_answers() {
+        for solution in solutions() {
+            for example in solution.examples().unwrap() {
+                for part in [1, 2] {
+                    if let (Some(expected), Some(solver)) = (example.answer(part), solution.part(part)) {
+                        let answer = solver(&example.input).unwrap();
+                        assert_eq!(answer.trim_end(), expected, "{} day {} part {part} ({})", solution.year, solution.day, example.name);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn animations_record_their_examples() {
+        for solution in solutions() {
+            for (part, animation) in solution.animations.iter().enumerate() {
+                let Some(animation) = animation else { continue };
+                let example = solution.examples().unwrap().into_iter().next().unwrap();
+                let mut recorder = Recorder::new().limit(10);
+
+                animation(&example.input, &mut recorder).unwrap();
+                assert!(!recorder.is_empty(), "{} day {} part {}", solution.year, solution.day, part + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn animation_parts() {
+        let solution = find_solution(2022, 9).unwrap();
+        assert!(solution.animation(None).is_some());
+        assert!(solution.animation(Some(1)).is_none());
+        assert!(solution.animation(Some(2)).is_some());
+
+        let solution = find_solution(2015, 6).unwrap();
+        assert!(solution.animation(Some(1)).is_some());
+        assert!(solution.animation(Some(2)).is_some());
+        assert!(solution.animation(Some(3)).is_none());
+        assert!(find_solution(2022, 1).unwrap().animation(None).is_none());
+    }
+
+    #[test]
+    fn dispatches_to_day() {
+        let solution = find_solution(2022, 1).unwrap();
+        assert_eq!(solution.part(1).unwrap()("1000\n2000\n\n4000"), Ok(String::from("4000")));
+        assert_eq!(solution.part(1).unwrap()("1000\nlots").err().unwrap().to_string(), "line 2, column 1: invalid number 'lots'");
+        assert!(solution.part(3).is_none());
+    }
+}
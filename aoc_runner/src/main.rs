@@ -0,0 +1,335 @@
+use std::{env, fs, process};
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use aoc_runner::{
+    bench_table, check_part, find_solution, history_table, read_input, solutions, solve_part, summary, table, time_part,
+    AnimateOptions, BenchOptions, Check, CheckOptions, Command, DayAnswer, ExportOptions, FetchOptions, History, ImageFormat, Options,
+    ReplayOptions, Run, Status
+};
+use fixtures::{extract_examples, Answers, Client, Example, InputStore};
+use render::{Palette, Recorder};
+
+// Prints each part's answer as it is found, or with --json every part at once.
+fn run(options: Options) -> Result<(), String> {
+    let solution = find_solution(options.year, options.day)
+        .ok_or_else(|| format!("No solution for {} day {}", options.year, options.day))?;
+
+    let input_path = options.input.unwrap_or_else(|| solution.default_input_path());
+    let input = read_input(&input_path)?;
+
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => vec![1, 2]
+    };
+
+    if options.json {
+        let answers = DayAnswer {
+            year: solution.year,
+            day: solution.day,
+            input: input_path.display().to_string(),
+            parts: parts.into_iter().map(|part| solve_part(&solution, part, &input)).collect()
+        };
+        println!("{}", answers.to_json());
+
+        return match answers.parts.iter().filter(|part| part.error.is_some()).count() {
+            0 => Ok(()),
+            failed => Err(format!("{failed} part(s) failed"))
+        };
+    }
+
+    for part in parts {
+        let prefix = format!("{} day {} part {}", solution.year, solution.day, part);
+        let answer = solve_part(&solution, part, &input);
+        let elapsed = Duration::from_nanos(answer.elapsed_ns.unwrap_or_default());
+
+        match (answer.answer, answer.error) {
+            (Some(answer), _) if answer.contains('\n') => println!("{prefix} ({elapsed:?}):\n{answer}"),
+            (Some(answer), _) => println!("{prefix}: {answer} ({elapsed:?})"),
+            (None, Some(error)) => return Err(format!("{prefix}: {}: {error}", input_path.display())),
+            (None, None) => println!("{prefix}: not implemented")
+        }
+    }
+
+    Ok(())
+}
+
+// Caches the day's input, then seeds examples/example.txt from the puzzle page
+// if the day's crate has no examples yet.
+fn fetch(options: FetchOptions) -> Result<(), String> {
+    let server = options.server.or_else(|| env::var("AOC_SERVER").ok())
+        .ok_or("No server to fetch from, pass --server or set AOC_SERVER")?;
+    let session = options.session.or_else(|| env::var("AOC_SESSION").ok());
+    let client = Client::new(&server, session).map_err(|error| error.to_string())?;
+    let store = InputStore::default();
+
+    if store.contains(options.year, options.day) {
+        println!("Input already cached at {}", store.path(options.year, options.day).display());
+    } else {
+        let input = client.input(options.year, options.day).map_err(|error| error.to_string())?;
+        let path = store.save(options.year, options.day, &input).map_err(|error| error.to_string())?;
+        println!("Saved input to {}", path.display());
+    }
+
+    let Some(solution) = find_solution(options.year, options.day) else {
+        println!("No crate for {} day {} yet, skipping examples", options.year, options.day);
+        return Ok(());
+    };
+    if !solution.examples().map_err(|error| error.to_string())?.is_empty() {
+        return Ok(());
+    }
+
+    let page = client.puzzle(options.year, options.day).map_err(|error| error.to_string())?;
+    match extract_examples(&page).first() {
+        Some(input) => {
+            let directory = solution.examples_directory();
+            let path = directory.join("example.txt");
+            fs::create_dir_all(&directory)
+                .and_then(|_| fs::write(&path, Example::new("example", input).to_string()))
+                .map_err(|error| format!("Could not write {}: {error}", path.display()))?;
+            println!("Saved example to {}, add its answers under '--- part one' and '--- part two'", path.display());
+        },
+        None => println!("No example found on the puzzle page")
+    }
+
+    Ok(())
+}
+
+fn confirm(check: &Check, answer: &str) -> Result<bool, String> {
+    let answer = if answer.contains('\n') { format!("\n{answer}\n") } else { format!("{answer} ") };
+    print!("Record {} day {} part {} answer {answer}? [y/N] ", check.year, check.day, check.part);
+    io::stdout().flush().map_err(|error| error.to_string())?;
+
+    let mut reply = String::new();
+    io::stdin().lock().read_line(&mut reply).map_err(|error| error.to_string())?;
+
+    Ok(matches!(reply.trim(), "y" | "Y" | "yes"))
+}
+
+// Runs every implemented day against its recorded answers. With --record, new
+// answers are written to <year>/answers.toml once they have been confirmed.
+fn check(options: CheckOptions) -> Result<(), String> {
+    let solutions : Vec<_> = solutions().into_iter().filter(|solution| options.includes(solution)).collect();
+    let years : BTreeSet<u16> = solutions.iter().map(|solution| solution.year).collect();
+    let mut checks = vec![];
+
+    for year in years {
+        let path = Answers::path(year);
+        let mut answers = Answers::load(&path).map_err(|error| error.to_string())?;
+        let mut recorded = false;
+
+        for solution in solutions.iter().filter(|solution| solution.year == year) {
+            let input_path = solution.default_input_path();
+            let input = fs::read_to_string(&input_path)
+                .map_err(|error| format!("Could not read {}: {error}", input_path.display()));
+
+            for part in [1, 2] {
+                let check = check_part(solution, part, &input, answers.day(solution.day));
+                if let (true, Status::Missing, Some(answer)) = (options.record, check.status, check.computed_answer()) {
+                    if confirm(&check, answer)? {
+                        answers.record(solution.day, part, answer);
+                        recorded = true;
+                    }
+                }
+                checks.push(check);
+            }
+        }
+
+        if recorded {
+            answers.save(&path).map_err(|error| error.to_string())?;
+            println!("Saved answers to {}", path.display());
+        }
+    }
+
+    print!("{}", table(&checks));
+    println!("{}", summary(&checks));
+
+    match checks.iter().filter(|check| check.status == Status::Fail).count() {
+        0 => Ok(()),
+        failed => Err(format!("{failed} check(s) failed"))
+    }
+}
+
+// Times every implemented part on its real input and compares it with the last
+// saved run. Days marked as skipped in answers.toml are left out.
+fn bench(options: BenchOptions) -> Result<(), String> {
+    let path = History::path();
+    let mut history = History::load(&path)?;
+    let mut timings = vec![];
+    let mut parts = vec![];
+
+    for solution in solutions().into_iter().filter(|solution| options.includes(solution)) {
+        let answers = Answers::load(&Answers::path(solution.year)).map_err(|error| error.to_string())?;
+        if answers.day(solution.day).is_some_and(|answers| answers.skip.is_some()) {
+            continue;
+        }
+
+        for part in [1, 2] {
+            let Some(solver) = solution.part(part) else { continue };
+            parts.push((solution.year, solution.day, part));
+            if options.history {
+                continue;
+            }
+
+            let input_path = solution.default_input_path();
+            let input = fs::read_to_string(&input_path)
+                .map_err(|error| format!("Could not read {}: {error}", input_path.display()))?;
+            let timing = time_part(solution.year, solution.day, part, solver, &input, options.runs)
+                .map_err(|error| format!("{} day {} part {part}: {error}", solution.year, solution.day))?;
+            timings.push(timing);
+        }
+    }
+
+    if options.history {
+        print!("{}", history_table(&history, &parts));
+        return Ok(());
+    }
+
+    let run = Run::new(timings);
+    if run.profile == "debug" {
+        eprintln!("Timings are from a debug build, use --release for numbers worth comparing");
+    }
+    print!("{}", bench_table(&run.timings, &history, &run.profile));
+
+    if options.save {
+        history.runs.push(run);
+        history.save(&path)?;
+        println!("Saved run to {}", path.display());
+    }
+
+    Ok(())
+}
+
+// Runs a day's simulation on its input, or the given one, into the recorder.
+fn record(year: u16, day: u8, part: Option<u8>, input: Option<PathBuf>, mut recorder: Recorder) -> Result<Recorder, String> {
+    let solution = find_solution(year, day).ok_or_else(|| format!("No solution for {year} day {day}"))?;
+    let animation = solution.animation(part).ok_or_else(|| match part {
+        Some(part) => format!("{year} day {day} part {part} has nothing to animate"),
+        None => format!("{year} day {day} has nothing to animate")
+    })?;
+
+    let input_path = input.unwrap_or_else(|| solution.default_input_path());
+    let input = read_input(&input_path)?;
+
+    animation(&input, &mut recorder).map_err(|error| format!("{}: {error}", input_path.display()))?;
+
+    Ok(recorder)
+}
+
+// Records a day's simulation, then either plays it back or writes out its frames.
+fn animate(options: AnimateOptions) -> Result<(), String> {
+    let mut recorder = Recorder::new().every(options.every);
+    if let Some(limit) = options.limit {
+        recorder = recorder.limit(limit);
+    }
+    let recorder = record(options.year, options.day, options.part, options.input, recorder)?;
+
+    match options.dump {
+        Some(directory) => {
+            let paths = recorder.dump(&directory)
+                .map_err(|error| format!("Could not write {}: {error}", directory.display()))?;
+            println!("Saved {} frame(s) to {}", paths.len(), directory.display());
+            Ok(())
+        },
+        None => play(&recorder, options.delay, options.step)
+    }
+}
+
+// Only the final frame goes into a PNG, so nothing in between is kept.
+fn export(options: ExportOptions) -> Result<(), String> {
+    let every = match options.format {
+        ImageFormat::Png => usize::MAX,
+        ImageFormat::Gif => options.every
+    };
+    let mut recorder = Recorder::new().every(every);
+    if let Some(limit) = options.limit {
+        recorder = recorder.limit(limit);
+    }
+    let recorder = record(options.year, options.day, options.part, options.input, recorder)?;
+
+    let mut palette = recorder.palette().clone();
+    if let Some(path) = &options.palette {
+        let text = fs::read_to_string(path).map_err(|error| format!("Could not read {}: {error}", path.display()))?;
+        let overrides = Palette::parse(&text).ok_or_else(|| format!("Invalid palette in {}", path.display()))?;
+        palette = palette.merged(&overrides);
+    }
+
+    let frames = recorder.frames();
+    let image = match options.format {
+        ImageFormat::Png => render::png(frames.last().ok_or("Nothing was recorded")?, &palette, options.cell_size),
+        ImageFormat::Gif => render::gif(frames, &palette, options.cell_size, Duration::from_millis(options.delay))
+    }.map_err(|error| error.to_string())?;
+
+    fs::write(&options.output, image)
+        .map_err(|error| format!("Could not write {}: {error}", options.output.display()))?;
+    match options.format {
+        ImageFormat::Png => println!("Saved the final frame to {}", options.output.display()),
+        ImageFormat::Gif => println!("Saved {} frame(s) to {}", frames.len(), options.output.display())
+    }
+
+    Ok(())
+}
+
+fn replay(options: ReplayOptions) -> Result<(), String> {
+    let recorder = Recorder::load(&options.directory)
+        .map_err(|error| format!("Could not read {}: {error}", options.directory.display()))?;
+    if recorder.is_empty() {
+        return Err(format!("No frames in {}", options.directory.display()));
+    }
+
+    play(&recorder, options.delay, options.step)
+}
+
+// Stepping waits for enter between frames, 'b' goes back a frame and 'q' stops.
+fn play(recorder: &Recorder, delay: u64, step: bool) -> Result<(), String> {
+    let mut out = io::stdout().lock();
+    if !step {
+        return recorder.play(&mut out, Duration::from_millis(delay)).map_err(|error| error.to_string());
+    }
+
+    let mut player = recorder.player();
+    while let Some(frame) = player.current() {
+        recorder.show(&mut out, frame).map_err(|error| error.to_string())?;
+        write!(out, "frame {} of {} [enter: next, b: back, q: quit] ", player.position() + 1, player.len())
+            .and_then(|_| out.flush())
+            .map_err(|error| error.to_string())?;
+
+        let mut reply = String::new();
+        if io::stdin().lock().read_line(&mut reply).map_err(|error| error.to_string())? == 0 {
+            break;
+        }
+        match reply.trim() {
+            "q" => break,
+            "b" => { player.back(); },
+            _ => {
+                if !player.forward() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args : Vec<String> = env::args().skip(1).collect();
+
+    let result = Command::parse(&args).and_then(|command| {
+        match command {
+            Command::Solve(options) => run(options),
+            Command::Fetch(options) => fetch(options),
+            Command::Check(options) => check(options),
+            Command::Bench(options) => bench(options),
+            Command::Animate(options) => animate(options),
+            Command::Replay(options) => replay(options),
+            Command::Export(options) => export(options)
+        }
+    });
+
+    if let Err(message) = result {
+        eprintln!("{message}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,118 @@
+use std::{fs, io};
+use std::io::Read;
+use std::path::Path;
+use std::time::Instant;
+use serde::Serialize;
+use crate::Solution;
+
+// A path of "-" reads the input from stdin instead.
+pub fn read_input(path: &Path) -> Result<String, String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).map_err(|error| format!("Could not read stdin: {error}"))?;
+        return Ok(input);
+    }
+
+    fs::read_to_string(path).map_err(|error| format!("Could not read {}: {error}", path.display()))
+}
+
+// A part without a solver has neither an answer nor an error.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PartAnswer {
+    pub part: u8,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub elapsed_ns: Option<u64>
+}
+
+impl PartAnswer {
+    pub fn is_implemented(&self) -> bool {
+        self.answer.is_some() || self.error.is_some()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DayAnswer {
+    pub year: u16,
+    pub day: u8,
+    pub input: String,
+    pub parts: Vec<PartAnswer>
+}
+
+impl DayAnswer {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("answers always serialize")
+    }
+}
+
+pub fn solve_part(solution: &Solution, part: u8, input: &str) -> PartAnswer {
+    let Some(solver) = solution.part(part) else {
+        return PartAnswer { part, answer: None, error: None, elapsed_ns: None };
+    };
+
+    let start = Instant::now();
+    let result = solver(input);
+    let elapsed_ns = Some(start.elapsed().as_nanos().min(u64::MAX as u128) as u64);
+
+    match result {
+        Ok(answer) => PartAnswer { part, answer: Some(answer), error: None, elapsed_ns },
+        Err(error) => PartAnswer { part, answer: None, error: Some(error.to_string()), elapsed_ns }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parsing::ParseError;
+    use super::*;
+
+    fn solution() -> Solution {
+        Solution::new(
+            2022,
+            1,
+            "2022/day_one",
+            Some(|input: &str| input.trim().parse::<u32>().map(|number| (number * 2).to_string()).map_err(|_| ParseError::new("not a number"))),
+            None
+        )
+    }
+
+    #[test]
+    fn solves_parts() {
+        let answer = solve_part(&solution(), 1, "21\n");
+        assert_eq!(answer.answer.as_deref(), Some("42"));
+        assert_eq!(answer.error, None);
+        assert!(answer.elapsed_ns.is_some());
+
+        let answer = solve_part(&solution(), 1, "lots");
+        assert_eq!(answer.answer, None);
+        assert_eq!(answer.error.as_deref(), Some("not a number"));
+        assert!(answer.is_implemented());
+
+        assert!(!solve_part(&solution(), 2, "21").is_implemented());
+    }
+
+    #[test]
+    fn json() {
+        let day = DayAnswer {
+            year: 2022,
+            day: 1,
+            input: String::from("-"),
+            parts: vec![
+                PartAnswer { part: 1, answer: Some(String::from("42")), error: None, elapsed_ns: Some(1500) },
+                solve_part(&solution(), 2, "21")
+            ]
+        };
+
+        let json : serde_json::Value = serde_json::from_str(&day.to_json()).unwrap();
+        assert_eq!(json["year"], 2022);
+        assert_eq!(json["input"], "-");
+        assert_eq!(json["parts"][0]["answer"], "42");
+        assert_eq!(json["parts"][0]["elapsed_ns"], 1500);
+        assert_eq!(json["parts"][1]["answer"], serde_json::Value::Null);
+        assert_eq!(json["parts"][1]["error"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn reads_files() {
+        assert!(read_input(Path::new("/no/such/input.txt")).unwrap_err().starts_with("Could not read /no/such/input.txt"));
+    }
+}
@@ -0,0 +1,36 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use aoc_runner::solutions;
+
+// Scripts read `--json` output straight from stdout, so nothing a solver prints
+// may end up mixed into it. Each part runs on the first example with an answer
+// for it, since some solvers are tuned to the real input's size.
+#[test]
+fn every_solver_prints_valid_json() {
+    for solution in solutions() {
+        let examples = solution.examples().unwrap();
+        for part in [1, 2] {
+            let Some(example) = examples.iter().find(|example| example.answer(part).is_some()) else { continue };
+            if solution.part(part).is_none() {
+                continue;
+            }
+            let context = format!("{} day {} part {part} ({})", solution.year, solution.day, example.name);
+
+            let mut child = Command::new(env!("CARGO_BIN_EXE_aoc"))
+                .args([solution.year.to_string(), solution.day.to_string()])
+                .args(["--part", &part.to_string(), "--input", "-", "--json"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            child.stdin.take().unwrap().write_all(example.input.as_bytes()).unwrap();
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success(), "{context}: {}", output.status);
+
+            let stdout = String::from_utf8(output.stdout).unwrap();
+            let json : serde_json::Value = serde_json::from_str(&stdout).unwrap_or_else(|error| panic!("{context}: {error} in\n{stdout}"));
+            assert_eq!(json["parts"][0]["answer"].as_str().map(str::trim_end), example.answer(part), "{context}");
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use std::{fmt, fs, io};
+use std::path::{Path, PathBuf};
+use parsing::ParseError;
+use crate::Error;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DayAnswers {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+    // Why the day is left out of regression runs, e.g. it takes too long.
+    pub skip: Option<String>
+}
+
+impl DayAnswers {
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None
+        }
+    }
+}
+
+// The recorded answers for one year, kept in <year>/answers.toml using a small
+// subset of TOML: a table per day, holding part_one, part_two and skip.
+//
+//     [1]
+//     part_one = 68775
+//     part_two = 202585
+//
+//     [10]
+//     part_two = """
+//     ###..
+//     """
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    days: BTreeMap<u8, DayAnswers>
+}
+
+impl Answers {
+    pub fn path(year: u16) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(year.to_string()).join("answers.toml")
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut answers = Self::default();
+        let mut day = None;
+        let mut lines = text.lines().enumerate();
+
+        while let Some((index, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.strip_suffix(']')
+                    .ok_or_else(|| ParseError::new(format!("expected ']' to close '{line}'")).with_line(index + 1))?;
+                let number = parsing::number(line, header).map_err(|error| error.with_line(index + 1))?;
+                if answers.days.contains_key(&number) {
+                    return Err(ParseError::new(format!("day {number} is listed twice")).with_line(index + 1));
+                }
+                answers.days.insert(number, DayAnswers::default());
+                day = Some(number);
+                continue;
+            }
+
+            let (key, value) = parsing::split_once(line, "=").map_err(|error| error.with_line(index + 1))?;
+            let (key, value) = (key.trim(), value.trim());
+            let value = if value == "\"\"\"" {
+                let mut value_lines = vec![];
+                loop {
+                    match lines.next() {
+                        Some((_, "\"\"\"")) => break,
+                        Some((_, value_line)) => value_lines.push(value_line),
+                        None => return Err(ParseError::new(format!("unterminated multi-line string for {key}")).with_line(index + 1))
+                    }
+                }
+                value_lines.join("\n")
+            } else {
+                parse_value(line, value).map_err(|error| error.with_line(index + 1))?
+            };
+
+            let number = day.ok_or_else(|| ParseError::new(format!("{key} is outside of a [day] table")).with_line(index + 1))?;
+            let day_answers = answers.days.get_mut(&number).unwrap();
+            let field = match key {
+                "part_one" => &mut day_answers.part_one,
+                "part_two" => &mut day_answers.part_two,
+                "skip" => &mut day_answers.skip,
+                _ => return Err(ParseError::at_token(line, key, format!("unknown key '{key}'")).with_line(index + 1))
+            };
+            *field = Some(value);
+        }
+
+        Ok(answers)
+    }
+
+    // A missing file has no answers yet.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|error| Error::Parse(path.to_path_buf(), error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(Error::Io(path.to_path_buf(), error))
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        fs::write(path, self.to_string()).map_err(|error| Error::Io(path.to_path_buf(), error))
+    }
+
+    pub fn day(&self, day: u8) -> Option<&DayAnswers> {
+        self.days.get(&day)
+    }
+
+    pub fn answer(&self, day: u8, part: u8) -> Option<&str> {
+        self.day(day).and_then(|answers| answers.answer(part))
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: &str) {
+        let answers = self.days.entry(day).or_default();
+        match part {
+            1 => answers.part_one = Some(answer.to_string()),
+            2 => answers.part_two = Some(answer.to_string()),
+            _ => panic!("There is no part {part}")
+        }
+    }
+}
+
+pub fn answer(year: u16, day: u8, part: u8) -> Result<Option<String>, Error> {
+    Ok(Answers::load(&Answers::path(year))?.answer(day, part).map(str::to_string))
+}
+
+fn parse_value(line: &str, value: &str) -> Result<String, ParseError> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let quoted = quoted.strip_suffix('"').ok_or_else(|| ParseError::at_token(line, value, "unterminated string"))?;
+        let mut unescaped = String::new();
+        let mut characters = quoted.chars();
+        while let Some(character) = characters.next() {
+            if character != '\\' {
+                unescaped.push(character);
+                continue;
+            }
+            match characters.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('t') => unescaped.push('\t'),
+                Some(escaped @ ('"' | '\\')) => unescaped.push(escaped),
+                _ => return Err(ParseError::at_token(line, value, format!("invalid escape in {value}")))
+            }
+        }
+        Ok(unescaped)
+    } else if value.trim_start_matches('-').chars().all(|character| character.is_ascii_digit() || character == '_') && value.chars().any(|character| character.is_ascii_digit()) {
+        Ok(value.replace('_', ""))
+    } else {
+        Err(ParseError::at_token(line, value, format!("expected a number or a string, found '{value}'")))
+    }
+}
+
+fn format_value(value: &str) -> String {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if !digits.is_empty() && digits.chars().all(|character| character.is_ascii_digit()) && (digits == "0" || !digits.starts_with('0')) {
+        value.to_string()
+    } else if value.contains('\n') {
+        format!("\"\"\"\n{value}\n\"\"\"")
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\t', "\\t"))
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (day, answers)) in self.days.iter().enumerate() {
+            if index > 0 {
+                writeln!(formatter)?;
+            }
+            writeln!(formatter, "[{day}]")?;
+            for (key, value) in [("part_one", &answers.part_one), ("part_two", &answers.part_two), ("skip", &answers.skip)] {
+                if let Some(value) = value {
+                    writeln!(formatter, "{key} = {}", format_value(value))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse("# 2022\n\n[1]\npart_one = 68_775\npart_two = \"202585\"\n\n[10]\npart_two = \"\"\"\n##..\n.##.\n\"\"\"\n[16]\nskip = \"too \\\"slow\\\"\"\n").unwrap();
+
+        assert_eq!(answers.answer(1, 1), Some("68775"));
+        assert_eq!(answers.answer(1, 2), Some("202585"));
+        assert_eq!(answers.answer(10, 1), None);
+        assert_eq!(answers.answer(10, 2), Some("##..\n.##."));
+        assert_eq!(answers.day(16).unwrap().skip.as_deref(), Some("too \"slow\""));
+        assert_eq!(answers.day(2), None);
+    }
+
+    #[test]
+    fn invalid_answers() {
+        assert_eq!(Answers::parse("part_one = 1").err().unwrap().to_string(), "line 1: part_one is outside of a [day] table");
+        assert_eq!(Answers::parse("[one]").err().unwrap().to_string(), "line 1, column 2: invalid number 'one'");
+        assert_eq!(Answers::parse("[1]\n[1]").err().unwrap().to_string(), "line 2: day 1 is listed twice");
+        assert_eq!(Answers::parse("[1]\npart_three = 3").err().unwrap().to_string(), "line 2, column 1: unknown key 'part_three'");
+        assert_eq!(Answers::parse("[1]\npart_one = CMZ").err().unwrap().to_string(), "line 2, column 12: expected a number or a string, found 'CMZ'");
+        assert_eq!(Answers::parse("[1]\npart_one = \"CMZ").err().unwrap().to_string(), "line 2, column 12: unterminated string");
+        assert_eq!(Answers::parse("[10]\npart_two = \"\"\"\n##..").err().unwrap().to_string(), "line 2: unterminated multi-line string for part_two");
+    }
+
+    #[test]
+    fn record_and_round_trip() {
+        let mut answers = Answers::default();
+        answers.record(5, 1, "CMZ");
+        answers.record(1, 2, "45000");
+        answers.record(10, 2, "##..\n.##.");
+        answers.record(1, 1, "007");
+
+        assert_eq!(answers.to_string(), "[1]\npart_one = \"007\"\npart_two = 45000\n\n[5]\npart_one = \"CMZ\"\n\n[10]\npart_two = \"\"\"\n##..\n.##.\n\"\"\"\n");
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers);
+    }
+
+    #[test]
+    fn load_and_save() {
+        let path = env::temp_dir().join(format!("fixtures-answers-{}.toml", std::process::id()));
+        assert_eq!(Answers::load(&path).unwrap(), Answers::default());
+
+        let mut answers = Answers::default();
+        answers.record(24, 1, "334");
+        answers.save(&path).unwrap();
+        assert_eq!(Answers::load(&path).unwrap().answer(24, 1), Some("334"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn recorded_answers() {
+        assert_eq!(answer(2015, 7, 1).unwrap().as_deref(), Some("16076"));
+        assert_eq!(answer(2015, 25, 1).unwrap(), None);
+    }
+}
@@ -0,0 +1,196 @@
+use std::{fmt, fs, io};
+use std::path::Path;
+use parsing::ParseError;
+use crate::Error;
+
+// An example input and the answers it should produce, stored as
+//
+//     --- part one
+//     24000
+//     --- part two
+//     45000
+//     --- input
+//     1000
+//     ...
+//
+// Either answer can be left out. The input comes last and runs to the end of
+// the file untouched, so it can contain anything (even lines starting "---").
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    pub part_one: Option<String>,
+    pub part_two: Option<String>
+}
+
+impl Example {
+    pub fn new(name: &str, input: &str) -> Self {
+        Self { name: name.to_string(), input: input.to_string(), part_one: None, part_two: None }
+    }
+
+    pub fn parse(name: &str, text: &str) -> Result<Self, ParseError> {
+        let mut part_one : Option<Vec<&str>> = None;
+        let mut part_two : Option<Vec<&str>> = None;
+        let mut current : Option<&mut Vec<&str>> = None;
+        let mut offset = 0;
+
+        for (index, line) in text.split_inclusive('\n').enumerate() {
+            offset += line.len();
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if let Some(header) = line.strip_prefix("--- ") {
+                let section = match header.trim() {
+                    "part one" => &mut part_one,
+                    "part two" => &mut part_two,
+                    "input" => {
+                        return Ok(Self {
+                            name: name.to_string(),
+                            input: text[offset..].to_string(),
+                            part_one: answer(part_one),
+                            part_two: answer(part_two)
+                        });
+                    },
+                    _ => return Err(ParseError::at_token(line, header, format!("unknown section '{}'", header.trim())).with_line(index + 1))
+                };
+
+                if section.is_some() {
+                    return Err(ParseError::new(format!("duplicate section '{}'", header.trim())).with_line(index + 1));
+                }
+                current = Some(section.insert(vec![]));
+            } else {
+                match current.as_mut() {
+                    Some(lines) => lines.push(line),
+                    _ if line.trim().is_empty() => {},
+                    _ => return Err(ParseError::new("expected a section header").with_line(index + 1))
+                }
+            }
+        }
+
+        Err(ParseError::new("expected an '--- input' section"))
+    }
+
+    // Names the example after its file, so examples/larger.txt is "larger".
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path).map_err(|error| Error::Io(path.to_path_buf(), error))?;
+        let name = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+
+        Self::parse(&name, &text).map_err(|error| Error::Parse(path.to_path_buf(), error))
+    }
+
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None
+        }
+    }
+}
+
+fn answer(lines: Option<Vec<&str>>) -> Option<String> {
+    lines.map(|lines| lines.join("\n").trim_end().to_string()).filter(|answer| !answer.is_empty())
+}
+
+impl fmt::Display for Example {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(answer) = &self.part_one {
+            writeln!(formatter, "--- part one\n{answer}")?;
+        }
+        if let Some(answer) = &self.part_two {
+            writeln!(formatter, "--- part two\n{answer}")?;
+        }
+        write!(formatter, "--- input\n{}", self.input)
+    }
+}
+
+// Loads examples/<name>.txt, relative to the crate being tested.
+pub fn example(name: &str) -> Result<Example, Error> {
+    Example::load(&Path::new("examples").join(format!("{name}.txt")))
+}
+
+// Every example in `directory`, sorted by name. A missing directory has none.
+pub fn examples_in(directory: &Path) -> Result<Vec<Example>, Error> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(Error::Io(directory.to_path_buf(), error))
+    };
+
+    let mut paths = vec![];
+    for entry in entries {
+        let path = entry.map_err(|error| Error::Io(directory.to_path_buf(), error))?.path();
+        if path.extension().is_some_and(|extension| extension == "txt") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths.iter().map(|path| Example::load(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let example = Example::parse("example", "--- part one\n24000\n\n--- part two\n##..\n.##.\n--- input\n1000\n--- 2000\n\n").unwrap();
+
+        assert_eq!(example.name, "example");
+        assert_eq!(example.answer(1), Some("24000"));
+        assert_eq!(example.answer(2), Some("##..\n.##."));
+        assert_eq!(example.input, "1000\n--- 2000\n\n");
+    }
+
+    #[test]
+    fn parse_without_answers() {
+        let example = Example::parse("bare", "\n--- input\r\n  [D]\n").unwrap();
+
+        assert_eq!(example, Example::new("bare", "  [D]\n"));
+        assert_eq!(example.answer(1), None);
+    }
+
+    #[test]
+    fn invalid_examples() {
+        let error = Example::parse("example", "24000\n--- input\n1").err().unwrap();
+        assert_eq!(error.to_string(), "line 1: expected a section header");
+
+        let error = Example::parse("example", "--- part one\n1\n--- part three\n3\n--- input\n1").err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 5: unknown section 'part three'");
+
+        let error = Example::parse("example", "--- part one\n1\n--- part one\n2\n--- input\n1").err().unwrap();
+        assert_eq!(error.to_string(), "line 3: duplicate section 'part one'");
+
+        let error = Example::parse("example", "--- part one\n1\n").err().unwrap();
+        assert_eq!(error.to_string(), "expected an '--- input' section");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut example = Example::new("example", "A Y\nB X\nC Z\n");
+        example.part_one = Some(String::from("15"));
+
+        assert_eq!(example.to_string(), "--- part one\n15\n--- input\nA Y\nB X\nC Z\n");
+        assert_eq!(Example::parse("example", &example.to_string()).unwrap(), example);
+    }
+
+    #[test]
+    fn load_directory() {
+        let directory = env::temp_dir().join(format!("fixtures-examples-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("larger.txt"), "--- part two\n2\n--- input\nb").unwrap();
+        fs::write(directory.join("example.txt"), "--- input\na").unwrap();
+        fs::write(directory.join("notes.md"), "not an example").unwrap();
+
+        let examples = examples_in(&directory).unwrap();
+        assert_eq!(examples.iter().map(|example| example.name.as_str()).collect::<Vec<_>>(), vec!["example", "larger"]);
+        assert_eq!(examples[1].answer(2), Some("2"));
+
+        fs::write(directory.join("broken.txt"), "oops").unwrap();
+        let error = examples_in(&directory).err().unwrap();
+        assert_eq!(error.to_string(), format!("{}: line 1: expected a section header", directory.join("broken.txt").display()));
+
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(examples_in(&directory).unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+use crate::Error;
+
+// A minimal HTTP/1.0 client for the puzzle site, or anything standing in for
+// it locally. There is no TLS, so only http:// servers can be used.
+pub struct Client {
+    host: String,
+    port: u16,
+    prefix: String,
+    session: Option<String>
+}
+
+impl Client {
+    pub fn new(server: &str, session: Option<String>) -> Result<Self, Error> {
+        let address = server.strip_prefix("http://")
+            .ok_or_else(|| Error::Http(format!("only http:// servers are supported, not '{server}'")))?;
+        let (authority, prefix) = address.split_once('/').unwrap_or((address, ""));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse().map_err(|_| Error::Http(format!("invalid port in '{server}'")))?;
+                (host, port)
+            },
+            None => (authority, 80)
+        };
+
+        if host.is_empty() {
+            return Err(Error::Http(format!("missing host in '{server}'")));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            prefix: prefix.trim_end_matches('/').to_string(),
+            session
+        })
+    }
+
+    pub fn input(&self, year: u16, day: u8) -> Result<String, Error> {
+        self.get(&format!("/{year}/day/{day}/input"))
+    }
+
+    pub fn puzzle(&self, year: u16, day: u8) -> Result<String, Error> {
+        self.get(&format!("/{year}/day/{day}"))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://{}:{}{}", self.host, self.port, self.full_path(path))
+    }
+
+    fn full_path(&self, path: &str) -> String {
+        if self.prefix.is_empty() {
+            path.to_string()
+        } else {
+            format!("/{}{path}", self.prefix)
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<String, Error> {
+        let url = self.url(path);
+        let failed = |error: std::io::Error| Error::Http(format!("GET {url}: {error}"));
+
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).map_err(failed)?;
+        stream.set_read_timeout(Some(Duration::from_secs(30))).map_err(failed)?;
+
+        let mut request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: aoc_runner\r\n", self.full_path(path), self.host);
+        if let Some(session) = &self.session {
+            request.push_str(&format!("Cookie: session={session}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).map_err(failed)?;
+
+        let mut response = vec![];
+        stream.read_to_end(&mut response).map_err(failed)?;
+        let response = String::from_utf8(response).map_err(|_| Error::Http(format!("GET {url}: response is not UTF-8")))?;
+
+        let (head, body) = response.split_once("\r\n\r\n")
+            .ok_or_else(|| Error::Http(format!("GET {url}: malformed response")))?;
+        let status = head.lines().next().unwrap_or_default();
+        match status.split_whitespace().nth(1) {
+            Some("200") => Ok(body.to_string()),
+            _ => Err(Error::Http(format!("GET {url}: {status}")))
+        }
+    }
+}
+
+// The contents of every <pre><code> block on a puzzle page, with markup
+// stripped. The first one is usually the example input.
+pub fn extract_examples(html: &str) -> Vec<String> {
+    let mut examples = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<pre><code>") {
+        rest = &rest[start + "<pre><code>".len()..];
+        let Some(end) = rest.find("</code></pre>") else { break };
+
+        examples.push(unescape(&strip_tags(&rest[..end])));
+        rest = &rest[end..];
+    }
+
+    examples
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for character in html.chars() {
+        match character {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(character),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+    use super::*;
+
+    // Answers each connection with the next canned response, and hands back the requests it saw.
+    fn stand_in(responses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            responses.into_iter().map(|response| {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let count = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..count]);
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+                String::from_utf8(request).unwrap()
+            }).collect()
+        });
+
+        (server, handle)
+    }
+
+    #[test]
+    fn fetches_input() {
+        let (server, handle) = stand_in(vec!["HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\n(()\n"]);
+        let client = Client::new(&server, Some(String::from("abc123"))).unwrap();
+
+        assert_eq!(client.input(2015, 1).unwrap(), "(()\n");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("GET /2015/day/1/input HTTP/1.0\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc123\r\n"));
+    }
+
+    #[test]
+    fn reports_http_errors() {
+        let (server, handle) = stand_in(vec!["HTTP/1.0 404 Not Found\r\n\r\nPlease don't repeatedly request this endpoint"]);
+        let client = Client::new(&format!("{server}/mirror/"), None).unwrap();
+
+        let error = client.puzzle(2022, 26).err().unwrap();
+        assert_eq!(error.to_string(), format!("GET {server}/mirror/2022/day/26: HTTP/1.0 404 Not Found"));
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("GET /mirror/2022/day/26 HTTP/1.0\r\n"));
+        assert!(!requests[0].contains("Cookie"));
+    }
+
+    #[test]
+    fn invalid_servers() {
+        assert_eq!(Client::new("https://adventofcode.com", None).err().unwrap().to_string(), "only http:// servers are supported, not 'https://adventofcode.com'");
+        assert_eq!(Client::new("http://localhost:http", None).err().unwrap().to_string(), "invalid port in 'http://localhost:http'");
+        assert_eq!(Client::new("http://:8080", None).err().unwrap().to_string(), "missing host in 'http://:8080'");
+    }
+
+    #[test]
+    fn extracts_examples() {
+        let html = "<p>For example:</p>\n<pre><code>1000\n2000\n\n<em>4000</em>\n</code></pre>\n<p>Then <code>x</code></p><pre><code>a &lt;- b &amp;&amp; c\n</code></pre>";
+
+        assert_eq!(extract_examples(html), vec!["1000\n2000\n\n4000\n", "a <- b && c\n"]);
+        assert_eq!(extract_examples("<pre><code>unterminated"), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,33 @@
+mod answers;
+mod example;
+mod fetch;
+mod store;
+
+use std::{fmt, io};
+use std::error;
+use std::path::PathBuf;
+use parsing::ParseError;
+
+pub use answers::{answer, Answers, DayAnswers};
+pub use example::{example, examples_in, Example};
+pub use fetch::{extract_examples, Client};
+pub use store::{input, InputStore};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, ParseError),
+    Http(String)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(path, error) => write!(formatter, "{}: {error}", path.display()),
+            Error::Parse(path, error) => write!(formatter, "{}: {error}", path.display()),
+            Error::Http(message) => write!(formatter, "{message}")
+        }
+    }
+}
+
+impl error::Error for Error {}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::Error;
+
+// Puzzle inputs, one file per day under <root>/<year>/<day>.txt.
+pub struct InputStore {
+    root: PathBuf
+}
+
+impl InputStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.root.join(year.to_string()).join(format!("{day:02}.txt"))
+    }
+
+    pub fn contains(&self, year: u16, day: u8) -> bool {
+        self.path(year, day).is_file()
+    }
+
+    pub fn load(&self, year: u16, day: u8) -> Result<String, Error> {
+        let path = self.path(year, day);
+        fs::read_to_string(&path).map_err(|error| Error::Io(path, error))
+    }
+
+    pub fn save(&self, year: u16, day: u8, input: &str) -> Result<PathBuf, Error> {
+        let path = self.path(year, day);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|error| Error::Io(directory.to_path_buf(), error))?;
+        }
+        fs::write(&path, input).map_err(|error| Error::Io(path.clone(), error))?;
+
+        Ok(path)
+    }
+}
+
+impl Default for InputStore {
+    // The inputs directory at the root of the workspace.
+    fn default() -> Self {
+        Self::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("inputs"))
+    }
+}
+
+pub fn input(year: u16, day: u8) -> Result<String, Error> {
+    InputStore::default().load(year, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let root = env::temp_dir().join(format!("fixtures-store-{}", std::process::id()));
+        let store = InputStore::new(&root);
+
+        assert!(!store.contains(2015, 1));
+        assert!(store.load(2015, 1).is_err());
+
+        let path = store.save(2015, 1, "(()\n").unwrap();
+        assert_eq!(path, root.join("2015").join("01.txt"));
+        assert!(store.contains(2015, 1));
+        assert_eq!(store.load(2015, 1).unwrap(), "(()\n");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn default_store() {
+        assert!(InputStore::default().contains(2022, 1));
+        assert!(input(2022, 1).unwrap().starts_with(char::is_numeric));
+    }
+}
@@ -0,0 +1,134 @@
+use std::iter;
+use std::ops::RangeInclusive;
+use crate::{Coordinate, Point};
+
+// Both corners are inclusive.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BoundingBox<T> {
+    pub min: Point<T>,
+    pub max: Point<T>
+}
+
+impl<T: Coordinate> BoundingBox<T> {
+    pub fn new(min: Point<T>, max: Point<T>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn around(point: Point<T>) -> Self {
+        Self { min: point, max: point }
+    }
+
+    pub fn containing<I: IntoIterator<Item = Point<T>>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::around(points.next()?);
+
+        for point in points {
+            bounds.include(point);
+        }
+
+        Some(bounds)
+    }
+
+    pub fn include(&mut self, point: Point<T>) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut bounds = *self;
+        bounds.include(other.min);
+        bounds.include(other.max);
+
+        bounds
+    }
+
+    pub fn expand(&self, margin: T) -> Self {
+        Self {
+            min: Point::new(self.min.x - margin, self.min.y - margin),
+            max: Point::new(self.max.x + margin, self.max.y + margin)
+        }
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        self.min.x <= point.x && point.x <= self.max.x && self.min.y <= point.y && point.y <= self.max.y
+    }
+
+    pub fn is_on_edge(&self, point: &Point<T>) -> bool {
+        self.contains(point)
+            && (point.x == self.min.x || point.x == self.max.x || point.y == self.min.y || point.y == self.max.y)
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn columns(&self) -> RangeInclusive<T> {
+        self.min.x..=self.max.x
+    }
+
+    pub fn rows(&self) -> RangeInclusive<T> {
+        self.min.y..=self.max.y
+    }
+
+    // Row by row, top to bottom.
+    pub fn points(&self) -> impl Iterator<Item = Point<T>> {
+        let bounds = *self;
+        Self::span(bounds.min.y, bounds.max.y).flat_map(move |y| {
+            Self::span(bounds.min.x, bounds.max.x).map(move |x| Point::new(x, y))
+        })
+    }
+
+    fn span(start: T, end: T) -> impl Iterator<Item = T> {
+        let first = if start <= end { Some(start) } else { None };
+        iter::successors(first, move |&value| if value < end { Some(value + T::ONE) } else { None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn containing() {
+        let bounds = BoundingBox::containing([Point::new(2, 3), Point::new(-1, 5), Point::new(4, 0)]).unwrap();
+        assert_eq!(bounds, BoundingBox::new(Point::new(-1, 0), Point::new(4, 5)));
+        assert_eq!(bounds.width(), 6);
+        assert_eq!(bounds.height(), 6);
+        assert_eq!(bounds.area(), 36);
+        assert!(BoundingBox::<i32>::containing([]).is_none());
+    }
+
+    #[test]
+    fn contains() {
+        let bounds = BoundingBox::new(Point::new(0, 0), Point::new(2, 2));
+        assert!(bounds.contains(&Point::new(2, 0)));
+        assert!(!bounds.contains(&Point::new(3, 0)));
+        assert!(bounds.is_on_edge(&Point::new(2, 1)));
+        assert!(!bounds.is_on_edge(&Point::new(1, 1)));
+    }
+
+    #[test]
+    fn union_and_expand() {
+        let a = BoundingBox::around(Point::new(0, 0));
+        let b = BoundingBox::new(Point::new(3, -2), Point::new(4, -1));
+        assert_eq!(a.union(&b), BoundingBox::new(Point::new(0, -2), Point::new(4, 0)));
+        assert_eq!(a.expand(1), BoundingBox::new(Point::new(-1, -1), Point::new(1, 1)));
+    }
+
+    #[test]
+    fn points() {
+        let bounds = BoundingBox::new(Point::new(1usize, 0), Point::new(2, 1));
+        assert_eq!(
+            bounds.points().collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(2, 0), Point::new(1, 1), Point::new(2, 1)]
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::ops::{Index, IndexMut};
+use parsing::ParseError;
+use crate::{BoundingBox, Point};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone
+    {
+        Self { width, height, cells: vec![fill; width * height] }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some(row) = rows.iter().find(|row| row.len() != width) {
+            panic!("Ragged grid: expected rows of width {width}, found {}", row.len());
+        }
+
+        Self { width, height, cells: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn parse<F>(input: &str, mut cell: F) -> Self
+    where
+        F: FnMut(Point<usize>, char) -> T
+    {
+        Self::from_rows(
+            input.lines().enumerate().map(|(row, line)| {
+                line.chars().enumerate().map(|(column, symbol)| cell(Point::new(column, row), symbol)).collect()
+            }).collect()
+        )
+    }
+
+    // Like `parse`, but cells that `cell` rejects and ragged rows become errors instead of panics.
+    pub fn try_parse<F>(input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(Point<usize>, char) -> Option<T>
+    {
+        let mut rows : Vec<Vec<T>> = vec![];
+
+        for (row, line) in input.lines().enumerate() {
+            let cells = line.chars().enumerate().map(|(column, symbol)| {
+                cell(Point::new(column, row), symbol)
+                    .ok_or_else(|| ParseError::unexpected_character(symbol, column + 1).with_line(row + 1))
+            }).collect::<Result<Vec<T>, ParseError>>()?;
+
+            if let Some(first_row) = rows.first() {
+                if cells.len() != first_row.len() {
+                    let message = format!("expected a row of width {}, found {}", first_row.len(), cells.len());
+                    return Err(ParseError::new(message).with_line(row + 1));
+                }
+            }
+            rows.push(cells);
+        }
+
+        Ok(Self::from_rows(rows))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<BoundingBox<usize>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        Some(BoundingBox::new(Point::origin(), Point::new(self.width - 1, self.height - 1)))
+    }
+
+    pub fn contains(&self, point: &Point<usize>) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn index_of(&self, point: &Point<usize>) -> usize {
+        point.y * self.width + point.x
+    }
+
+    pub fn point_at(&self, index: usize) -> Point<usize> {
+        Point::new(index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, point: &Point<usize>) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[self.index_of(point)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: &Point<usize>) -> Option<&mut T> {
+        if self.contains(point) {
+            let index = self.index_of(point);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn neighbors(&self, point: &Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        point.neighbors().filter(|neighbor| self.contains(neighbor))
+    }
+
+    pub fn all_neighbors(&self, point: &Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        point.all_neighbors().filter(|neighbor| self.contains(neighbor))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point<usize>> + '_ {
+        (0..self.cells.len()).map(|index| self.point_at(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point<usize>) -> &T {
+        self.get(&point).unwrap_or_else(|| panic!("{point:?} is outside of a {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, point: Point<usize>) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(&point).unwrap_or_else(|| panic!("{point:?} is outside of a {width}x{height} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<u8> {
+        Grid::parse("123\n456", |_point, symbol| symbol.to_digit(10).unwrap() as u8)
+    }
+
+    #[test]
+    fn parse() {
+        let grid = example();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point::new(0, 1)], 4);
+        assert_eq!(grid.get(&Point::new(3, 0)), None);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+    }
+
+    #[test]
+    fn indices() {
+        let grid = example();
+        for (index, point) in grid.points().enumerate() {
+            assert_eq!(grid.index_of(&point), index);
+            assert_eq!(grid.point_at(index), point);
+        }
+    }
+
+    #[test]
+    fn neighbors_stay_inside() {
+        let grid = example();
+        assert_eq!(grid.neighbors(&Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors(&Point::new(1, 0)).count(), 3);
+        assert_eq!(grid.all_neighbors(&Point::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn mutation() {
+        let mut grid = Grid::new(2, 2, '.');
+        grid[Point::new(1, 1)] = '#';
+        *grid.get_mut(&Point::new(0, 1)).unwrap() = '@';
+        assert_eq!(grid.iter().filter(|(_point, &symbol)| symbol != '.').count(), 2);
+        assert_eq!(grid.bounds(), Some(BoundingBox::new(Point::new(0, 0), Point::new(1, 1))));
+    }
+
+    #[test]
+    fn try_parse() {
+        let grid = Grid::try_parse("123\n456", |_point, symbol| symbol.to_digit(10)).unwrap();
+        assert_eq!(grid[Point::new(2, 1)], 6);
+
+        let error = Grid::try_parse("123\n4x6", |_point, symbol| symbol.to_digit(10)).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 2: unexpected character 'x'");
+
+        let error = Grid::try_parse("123\n45", |_point, symbol| symbol.to_digit(10)).err().unwrap();
+        assert_eq!(error.to_string(), "line 2: expected a row of width 3, found 2");
+    }
+
+    #[test]
+    #[should_panic]
+    fn ragged_rows() {
+        Grid::from_rows(vec![vec![1, 2], vec![3]]);
+    }
+}
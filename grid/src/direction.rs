@@ -0,0 +1,98 @@
+// North is "up" on the screen, i.e. towards smaller y values.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Ord, PartialOrd)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn from_arrow(symbol: char) -> Option<Self> {
+        match symbol {
+            '^' => Some(Direction::North),
+            'v' | 'V' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            '>' => Some(Direction::East),
+            _ => None
+        }
+    }
+
+    pub fn from_letter(symbol: char) -> Option<Self> {
+        match symbol {
+            'N' | 'U' => Some(Direction::North),
+            'S' | 'D' => Some(Direction::South),
+            'W' | 'L' => Some(Direction::West),
+            'E' | 'R' => Some(Direction::East),
+            _ => None
+        }
+    }
+
+    pub fn arrow(&self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::South => 'v',
+            Direction::West => '<',
+            Direction::East => '>',
+        }
+    }
+
+    pub fn turn_left(&self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.turn_left().turn_left()
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::North | Direction::South)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrows_round_trip() {
+        for direction in Direction::ALL {
+            assert_eq!(Direction::from_arrow(direction.arrow()), Some(direction));
+        }
+        assert_eq!(Direction::from_arrow('x'), None);
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(Direction::from_letter('U'), Some(Direction::North));
+        assert_eq!(Direction::from_letter('E'), Some(Direction::East));
+        assert_eq!(Direction::from_letter('x'), None);
+    }
+
+    #[test]
+    fn turning() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_ne!(direction.opposite(), direction);
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.opposite(), Direction::South);
+    }
+}
@@ -0,0 +1,11 @@
+mod bounds;
+mod dense;
+mod direction;
+mod point;
+mod sparse;
+
+pub use bounds::BoundingBox;
+pub use dense::Grid;
+pub use direction::Direction;
+pub use point::{Coordinate, Point};
+pub use sparse::SparseGrid;
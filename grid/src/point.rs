@@ -0,0 +1,202 @@
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Sub};
+use crate::Direction;
+
+pub trait Coordinate: Copy + Ord + Hash + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    fn distance(self, other: Self) -> Self {
+        if self < other { other - self } else { self - other }
+    }
+}
+
+macro_rules! coordinate {
+    ($($type:ty),*) => {
+        $(
+            impl Coordinate for $type {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$type>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$type>::checked_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+coordinate!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+// y grows downwards, so "up" is towards smaller y values.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Ord, PartialOrd)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Coordinate> Point<T> {
+    pub fn origin() -> Self {
+        Self::new(T::ZERO, T::ZERO)
+    }
+
+    pub fn up(&self) -> Self {
+        Self::new(self.x, self.y - T::ONE)
+    }
+
+    pub fn down(&self) -> Self {
+        Self::new(self.x, self.y + T::ONE)
+    }
+
+    pub fn left(&self) -> Self {
+        Self::new(self.x - T::ONE, self.y)
+    }
+
+    pub fn right(&self) -> Self {
+        Self::new(self.x + T::ONE, self.y)
+    }
+
+    pub fn step(&self, direction: Direction) -> Self {
+        self.step_by(direction, T::ONE)
+    }
+
+    pub fn step_by(&self, direction: Direction, distance: T) -> Self {
+        match direction {
+            Direction::North => Self::new(self.x, self.y - distance),
+            Direction::South => Self::new(self.x, self.y + distance),
+            Direction::West => Self::new(self.x - distance, self.y),
+            Direction::East => Self::new(self.x + distance, self.y),
+        }
+    }
+
+    pub fn checked_step(&self, direction: Direction) -> Option<Self> {
+        match direction {
+            Direction::North => self.y.checked_sub(T::ONE).map(|y| Self::new(self.x, y)),
+            Direction::South => self.y.checked_add(T::ONE).map(|y| Self::new(self.x, y)),
+            Direction::West => self.x.checked_sub(T::ONE).map(|x| Self::new(x, self.y)),
+            Direction::East => self.x.checked_add(T::ONE).map(|x| Self::new(x, self.y)),
+        }
+    }
+
+    // Points that would under/overflow the coordinate type are skipped.
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        let point = *self;
+        Direction::ALL.into_iter().filter_map(move |direction| point.checked_step(direction))
+    }
+
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Self> {
+        let point = *self;
+        Direction::ALL.into_iter().flat_map(move |direction| {
+            let straight = point.checked_step(direction);
+            let diagonal = straight.and_then(|neighbor| neighbor.checked_step(direction.turn_right()));
+            [straight, diagonal]
+        }).flatten()
+    }
+
+    pub fn is_neighbor_of(&self, other: &Self) -> bool {
+        self.manhattan_distance(other) == T::ONE
+    }
+
+    pub fn is_adjacent_to(&self, other: &Self) -> bool {
+        self != other && self.x.distance(other.x) <= T::ONE && self.y.distance(other.y) <= T::ONE
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    pub fn scale(&self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{},{}", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use super::*;
+
+    #[test]
+    fn steps() {
+        let point = Point::new(3, 3);
+        assert_eq!(point.up(), Point::new(3, 2));
+        assert_eq!(point.down(), Point::new(3, 4));
+        assert_eq!(point.left(), Point::new(2, 3));
+        assert_eq!(point.right(), Point::new(4, 3));
+        assert_eq!(point.step(Direction::North), point.up());
+        assert_eq!(point.step_by(Direction::East, 4), Point::new(7, 3));
+    }
+
+    #[test]
+    fn neighbors() {
+        let neighbors : HashSet<Point<i32>> = Point::origin().neighbors().collect();
+        assert_eq!(neighbors, HashSet::from([Point::new(0, -1), Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0)]));
+
+        let all_neighbors : HashSet<Point<i32>> = Point::origin().all_neighbors().collect();
+        assert_eq!(all_neighbors.len(), 8);
+        assert!(all_neighbors.iter().all(|neighbor| neighbor.is_adjacent_to(&Point::origin())));
+    }
+
+    #[test]
+    fn unsigned_neighbors_stop_at_zero() {
+        let corner : Point<usize> = Point::origin();
+        assert_eq!(corner.neighbors().count(), 2);
+        assert_eq!(corner.all_neighbors().count(), 3);
+        assert_eq!(corner.checked_step(Direction::West), None);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point::new(8, 7);
+        let b = Point::new(2, 10);
+        assert_eq!(a.manhattan_distance(&b), 9);
+        assert_eq!(Point::new(2usize, 10).manhattan_distance(&Point::new(8, 7)), 9);
+        assert!(a.is_neighbor_of(&a.left()));
+        assert!(!a.is_neighbor_of(&a.left().up()));
+        assert!(a.is_adjacent_to(&a.left().up()));
+        assert!(!a.is_adjacent_to(&a));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Point::new(1, 2) + Point::new(3, 4), Point::new(4, 6));
+        assert_eq!(Point::new(1, 2) - Point::new(3, 4), Point::new(-2, -2));
+        assert_eq!(Point::new(1, 2).scale(3), Point::new(3, 6));
+        assert_eq!(Point::new(1, 2).to_string(), "1,2");
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::collections::hash_map;
+use crate::{BoundingBox, Coordinate, Point};
+
+// Bounds grow on insert. Removing a point from the edge marks them stale, and
+// they get recomputed on the next call to `bounds` instead of on every removal.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T, C = i32> {
+    cells: HashMap<Point<C>, T>,
+    bounds: Option<BoundingBox<C>>,
+    stale_bounds: bool
+}
+
+impl<T, C: Coordinate> Default for SparseGrid<T, C> {
+    fn default() -> Self {
+        Self { cells: HashMap::new(), bounds: None, stale_bounds: false }
+    }
+}
+
+impl<T, C: Coordinate> SparseGrid<T, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, point: Point<C>, value: T) -> Option<T> {
+        match self.bounds.as_mut() {
+            Some(bounds) => bounds.include(point),
+            None => self.bounds = Some(BoundingBox::around(point))
+        }
+
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: &Point<C>) -> Option<T> {
+        let removed = self.cells.remove(point);
+
+        if removed.is_some() && self.bounds.is_some_and(|bounds| bounds.is_on_edge(point)) {
+            self.stale_bounds = true;
+        }
+
+        removed
+    }
+
+    pub fn get(&self, point: &Point<C>) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &Point<C>) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    pub fn contains(&self, point: &Point<C>) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<BoundingBox<C>> {
+        if self.stale_bounds {
+            BoundingBox::containing(self.cells.keys().copied())
+        } else {
+            self.bounds
+        }
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Point<C>, T> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> hash_map::Keys<'_, Point<C>, T> {
+        self.cells.keys()
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, Point<C>, T> {
+        self.cells.values()
+    }
+}
+
+impl<T, C: Coordinate> FromIterator<(Point<C>, T)> for SparseGrid<T, C> {
+    fn from_iter<I: IntoIterator<Item = (Point<C>, T)>>(cells: I) -> Self {
+        let mut grid = Self::new();
+        for (point, value) in cells {
+            grid.insert(point, value);
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let mut grid : SparseGrid<char> = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point::new(500, 0), '+');
+        grid.insert(Point::new(498, 4), '#');
+        assert_eq!(grid.get(&Point::new(498, 4)), Some(&'#'));
+        assert!(!grid.contains(&Point::new(0, 0)));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some(BoundingBox::new(Point::new(498, 0), Point::new(500, 4))));
+    }
+
+    #[test]
+    fn bounds_shrink_after_removing_edges() {
+        let mut grid : SparseGrid<()> = [(Point::new(0, 0), ()), (Point::new(1, 1), ()), (Point::new(5, 5), ())].into_iter().collect();
+        grid.remove(&Point::new(5, 5));
+        assert_eq!(grid.bounds(), Some(BoundingBox::new(Point::new(0, 0), Point::new(1, 1))));
+
+        grid.remove(&Point::new(0, 0));
+        grid.remove(&Point::new(1, 1));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+mod read;
+
+pub use read::{read_chunks, read_lines, ReadError};
+
+// Lines and columns count from 1, the way an editor shows them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { line: None, column: None, message: message.into() }
+    }
+
+    pub fn at_column(column: usize, message: impl Into<String>) -> Self {
+        Self { line: None, column: Some(column), message: message.into() }
+    }
+
+    // `token` has to be a slice of `text` for the column to be known.
+    pub fn at_token(text: &str, token: &str, message: impl Into<String>) -> Self {
+        Self { line: None, column: offset_of(text, token).map(|offset| offset + 1), message: message.into() }
+    }
+
+    pub fn unexpected_character(symbol: char, column: usize) -> Self {
+        Self::at_column(column, format!("unexpected character '{symbol}'"))
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    // Re-anchors an error raised while parsing a block of lines that starts after `count` other lines.
+    pub fn after_lines(mut self, count: usize) -> Self {
+        self.line = self.line.map(|line| line + count);
+        self
+    }
+
+    // Re-anchors an error raised while parsing `part` so its column is relative to `text`.
+    pub fn within(mut self, text: &str, part: &str) -> Self {
+        if let (Some(column), Some(offset)) = (self.column, offset_of(text, part)) {
+            self.column = Some(column + offset);
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(formatter, "line {line}, column {column}: {}", self.message),
+            (Some(line), None) => write!(formatter, "line {line}: {}", self.message),
+            (None, Some(column)) => write!(formatter, "column {column}: {}", self.message),
+            (None, None) => write!(formatter, "{}", self.message)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+fn offset_of(text: &str, part: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let part_start = part.as_ptr() as usize;
+
+    if start <= part_start && part_start + part.len() <= start + text.len() {
+        Some(part_start - start)
+    } else {
+        None
+    }
+}
+
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>
+{
+    input.lines().enumerate().map(|(index, line)| line.parse().map_err(|error: ParseError| error.with_line(index + 1))).collect()
+}
+
+pub fn number<T: FromStr>(text: &str, token: &str) -> Result<T, ParseError> {
+    token.trim().parse().map_err(|_| ParseError::at_token(text, token, format!("invalid number '{token}'")))
+}
+
+pub fn strip_prefix<'a>(text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    text.strip_prefix(prefix).ok_or_else(|| ParseError::at_column(1, format!("expected '{prefix}'")))
+}
+
+pub fn split_once<'a>(text: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(delimiter).ok_or_else(|| ParseError::new(format!("expected '{delimiter}' in '{text}'")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Move(char, u32);
+
+    impl FromStr for Move {
+        type Err = ParseError;
+
+        fn from_str(line: &str) -> Result<Self, Self::Err> {
+            let (direction, distance) = split_once(line, " ")?;
+            match direction {
+                "U" | "D" | "L" | "R" => Ok(Move(direction.chars().next().unwrap(), number(line, distance)?)),
+                _ => Err(ParseError::at_token(line, direction, format!("unknown direction {direction}")))
+            }
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ParseError::new("oops").to_string(), "oops");
+        assert_eq!(ParseError::at_column(3, "oops").to_string(), "column 3: oops");
+        assert_eq!(ParseError::new("oops").with_line(14).to_string(), "line 14: oops");
+        assert_eq!(ParseError::unexpected_character('x', 2).with_line(1).to_string(), "line 1, column 2: unexpected character 'x'");
+    }
+
+    #[test]
+    fn with_line_keeps_the_innermost_line() {
+        assert_eq!(ParseError::new("oops").with_line(2).with_line(7).line, Some(2));
+        assert_eq!(ParseError::new("oops").with_line(2).after_lines(7).line, Some(9));
+        assert_eq!(ParseError::new("oops").after_lines(7).line, None);
+    }
+
+    #[test]
+    fn token_columns() {
+        let line = "a -> XOR b";
+        assert_eq!(ParseError::at_token(line, &line[5..8], "unknown operator XOR").column, Some(6));
+        assert_eq!(ParseError::at_token(line, "XOR", "not a slice").column, None);
+
+        let error = ParseError::at_column(2, "oops").within(line, &line[5..]);
+        assert_eq!(error.column, Some(7));
+    }
+
+    #[test]
+    fn lines() {
+        let moves : Vec<Move> = parse_lines("U 1\nR 22").unwrap();
+        assert_eq!(moves.len(), 2);
+        assert_eq!((moves[1].0, moves[1].1), ('R', 22));
+
+        let error = parse_lines::<Move>("U 1\nX 2").err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 1: unknown direction X");
+
+        let error = parse_lines::<Move>("U 1\nD 2\nL two").err().unwrap();
+        assert_eq!(error.to_string(), "line 3, column 3: invalid number 'two'");
+
+        let error = parse_lines::<Move>("U1").err().unwrap();
+        assert_eq!(error.to_string(), "line 1: expected ' ' in 'U1'");
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(strip_prefix("Valve AA", "Valve "), Ok("AA"));
+        assert_eq!(strip_prefix("Valves AA", "Valve ").err().unwrap().to_string(), "column 1: expected 'Valve '");
+    }
+}
@@ -0,0 +1,121 @@
+use std::{error, fmt, io};
+use std::io::BufRead;
+use crate::ParseError;
+
+// Input streamed from a reader can fail to arrive as well as fail to parse.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError)
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(error) => write!(formatter, "could not read input: {error}"),
+            ReadError::Parse(error) => write!(formatter, "{error}")
+        }
+    }
+}
+
+impl error::Error for ReadError {}
+
+impl From<ParseError> for ReadError {
+    fn from(error: ParseError) -> Self {
+        ReadError::Parse(error)
+    }
+}
+
+// Solvers only report parse errors, so a failed read becomes one.
+impl From<ReadError> for ParseError {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Io(error) => ParseError::new(format!("could not read input: {error}")),
+            ReadError::Parse(error) => error
+        }
+    }
+}
+
+// Hands `feed` whatever the reader has buffered, a chunk at a time, until it
+// runs dry. Chunks can end anywhere, even partway through a character.
+pub fn read_chunks<R, F>(mut reader: R, mut feed: F) -> Result<(), ReadError>
+    where R: BufRead, F: FnMut(&[u8]) -> Result<(), ParseError>
+{
+    loop {
+        let read = match reader.fill_buf() {
+            Ok([]) => return Ok(()),
+            Ok(bytes) => { feed(bytes)?; bytes.len() },
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(ReadError::Io(error))
+        };
+        reader.consume(read);
+    }
+}
+
+// Hands `feed` one line at a time without its line break, reusing the same
+// buffer for each so a long input never has to be held in memory.
+pub fn read_lines<R, F>(mut reader: R, mut feed: F) -> Result<(), ReadError>
+    where R: BufRead, F: FnMut(&str) -> Result<(), ParseError>
+{
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) => feed(line.strip_suffix('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)).unwrap_or(&line))?,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(ReadError::Io(error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read};
+    use super::*;
+
+    // Fails once, as if interrupted by a signal, and then for good.
+    struct Flaky {
+        calls: usize
+    }
+
+    impl Read for Flaky {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            self.calls += 1;
+            match self.calls {
+                1 => Err(io::Error::from(io::ErrorKind::Interrupted)),
+                2 => { buffer[..3].copy_from_slice(b"ab\n"); Ok(3) },
+                _ => Err(io::Error::other("disk on fire"))
+            }
+        }
+    }
+
+    #[test]
+    fn chunks() {
+        let mut read = vec![];
+        read_chunks(BufReader::with_capacity(3, &b"abcdefg"[..]), |chunk| { read.push(chunk.to_vec()); Ok(()) }).unwrap();
+        assert_eq!(read, vec![b"abc".to_vec(), b"def".to_vec(), b"g".to_vec()]);
+
+        let error = read_chunks(&b"abc"[..], |_| Err(ParseError::at_column(2, "no"))).err().unwrap();
+        assert_eq!(error.to_string(), "column 2: no");
+    }
+
+    #[test]
+    fn lines() {
+        let mut read = vec![];
+        read_lines(BufReader::with_capacity(2, &b"one\r\n\ntwo\nthree"[..]), |line| { read.push(line.to_string()); Ok(()) }).unwrap();
+        assert_eq!(read, vec!["one", "", "two", "three"]);
+
+        let error = read_lines(&b"\xff\n"[..], |_| Ok(())).err().unwrap();
+        assert!(matches!(error, ReadError::Io(_)));
+    }
+
+    #[test]
+    fn failed_reads() {
+        let mut read = vec![];
+        let error = read_lines(BufReader::new(Flaky { calls: 0 }), |line| { read.push(line.to_string()); Ok(()) }).err().unwrap();
+        assert_eq!(read, vec!["ab"]);
+        assert_eq!(error.to_string(), "could not read input: disk on fire");
+        assert_eq!(ParseError::from(error).to_string(), "could not read input: disk on fire");
+    }
+}
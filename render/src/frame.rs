@@ -0,0 +1,148 @@
+use std::fmt;
+
+// A picture of a simulation at one moment: a rectangle of symbols, with an
+// optional caption shown above it. The origin places the top left symbol in
+// the simulation's own coordinates, so frames of different sizes line up when
+// they are drawn together.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+    caption: Option<String>,
+    origin: (i32, i32)
+}
+
+impl Frame {
+    pub fn from_fn<F>(width: usize, height: usize, mut cell: F) -> Self
+        where F: FnMut(usize, usize) -> char
+    {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(cell(x, y));
+            }
+        }
+
+        Self { width, height, cells, caption: None, origin: (0, 0) }
+    }
+
+    // Short lines are padded with spaces to the width of the longest one.
+    pub fn parse(text: &str) -> Self {
+        let lines : Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+        Self::from_fn(width, lines.len(), |x, y| lines[y].get(x).copied().unwrap_or(' '))
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    pub fn with_origin(mut self, x: i32, y: i32) -> Self {
+        self.origin = (x, y);
+        self
+    }
+
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[char]> {
+        // chunks panics on a zero width, and a frame that narrow has no rows worth showing
+        self.cells.chunks(self.width.max(1)).take(if self.width == 0 { 0 } else { self.height })
+    }
+
+    // The caption always takes up the first line of a dump, even when it is
+    // empty, so that frames read back exactly as they were written.
+    pub fn dump(&self) -> String {
+        format!("{}\n{}", self.caption.as_deref().unwrap_or(""), self.text())
+    }
+
+    pub fn parse_dump(dump: &str) -> Self {
+        let (caption, text) = dump.split_once('\n').unwrap_or((dump, ""));
+        let frame = Self::parse(text);
+
+        if caption.is_empty() {
+            frame
+        } else {
+            frame.with_caption(caption)
+        }
+    }
+
+    fn text(&self) -> String {
+        self.rows().map(|row| String::from_iter(row) + "\n").collect()
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(caption) = self.caption() {
+            writeln!(formatter, "{caption}")?;
+        }
+
+        write!(formatter, "{}", self.text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_fn() {
+        let frame = Frame::from_fn(3, 2, |x, y| if x == y { '#' } else { '.' });
+
+        assert_eq!((frame.width(), frame.height()), (3, 2));
+        assert_eq!(frame.get(1, 1), Some('#'));
+        assert_eq!(frame.get(2, 1), Some('.'));
+        assert_eq!(frame.get(3, 0), None);
+        assert_eq!(frame.to_string(), "#..\n.#.\n");
+    }
+
+    #[test]
+    fn parse_pads_short_lines() {
+        let frame = Frame::parse("#..\n#\n");
+
+        assert_eq!((frame.width(), frame.height()), (3, 2));
+        assert_eq!(frame.rows().collect::<Vec<_>>(), vec![&['#', '.', '.'][..], &['#', ' ', ' '][..]]);
+    }
+
+    #[test]
+    fn caption() {
+        let frame = Frame::parse("#.\n.#").with_caption("round 1");
+
+        assert_eq!(frame.caption(), Some("round 1"));
+        assert_eq!(frame.to_string(), "round 1\n#.\n.#\n");
+    }
+
+    #[test]
+    fn dump_round_trip() {
+        let captioned = Frame::parse(".#.\n###").with_caption("rock 2");
+        assert_eq!(captioned.dump(), "rock 2\n.#.\n###\n");
+        assert_eq!(Frame::parse_dump(&captioned.dump()), captioned);
+
+        let plain = Frame::parse("ab\ncd");
+        assert_eq!(plain.dump(), "\nab\ncd\n");
+        assert_eq!(Frame::parse_dump(&plain.dump()), plain);
+
+        assert_eq!(Frame::parse_dump(&Frame::default().dump()), Frame::default());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::image::{Canvas, ImageError};
+use crate::{Frame, Palette};
+
+const MAX_CODES : u32 = 4096;
+
+// The frames as a looping animated GIF, shown `delay` apart.
+pub fn gif(frames: &[Frame], palette: &Palette, cell_size: usize, delay: Duration) -> Result<Vec<u8>, ImageError> {
+    let canvas = Canvas::new(frames, palette, cell_size)?;
+    // the colour table has a power of two entries, and at least two
+    let bits = (1..=8).find(|&bits| canvas.colors.len() <= 1 << bits).unwrap();
+    let (Ok(width), Ok(height)) = (u16::try_from(canvas.width), u16::try_from(canvas.height)) else {
+        return Err(ImageError::TooLarge { width: canvas.width, height: canvas.height, format: "GIF" });
+    };
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend(width.to_le_bytes());
+    gif.extend(height.to_le_bytes());
+    // global colour table, 8 bits per channel, and its size
+    gif.push(0x80 | 0x70 | (bits - 1) as u8);
+    gif.extend([0, 0]);
+    for index in 0..1 << bits {
+        let color = canvas.colors.get(index).copied().unwrap_or_default();
+        gif.extend([color.red, color.green, color.blue]);
+    }
+
+    // loop forever
+    gif.extend([0x21, 0xff, 11]);
+    gif.extend(b"NETSCAPE2.0");
+    gif.extend([3, 1, 0, 0, 0]);
+
+    let centiseconds = (delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+    let minimum_code_size = bits.max(2);
+    for frame in frames {
+        gif.extend([0x21, 0xf9, 4, 0]);
+        gif.extend(centiseconds.to_le_bytes());
+        gif.extend([0, 0]);
+
+        gif.push(0x2c);
+        gif.extend([0, 0, 0, 0]);
+        gif.extend(width.to_le_bytes());
+        gif.extend(height.to_le_bytes());
+        gif.push(0);
+
+        gif.push(minimum_code_size as u8);
+        for block in lzw(&canvas.draw(frame), minimum_code_size).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend(block);
+        }
+        gif.push(0);
+    }
+
+    gif.push(0x3b);
+    Ok(gif)
+}
+
+// Writes codes least significant bit first.
+#[derive(Default)]
+struct CodeWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    count: u32
+}
+
+impl CodeWriter {
+    fn write(&mut self, code: u32, size: u32) {
+        self.current |= code << self.count;
+        self.count += size;
+        while self.count >= 8 {
+            self.bytes.push(self.current as u8);
+            self.current >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.current as u8);
+        }
+        self.bytes
+    }
+}
+
+fn lzw(pixels: &[u8], minimum_code_size: u32) -> Vec<u8> {
+    let clear = 1 << minimum_code_size;
+    let end = clear + 1;
+    let mut writer = CodeWriter::default();
+    let mut table : HashMap<(u32, u8), u32> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut size = minimum_code_size + 1;
+
+    writer.write(clear, size);
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.write(end, size);
+        return writer.finish();
+    };
+
+    let mut prefix = first as u32;
+    for &pixel in rest {
+        if let Some(&code) = table.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, size);
+        if next_code == MAX_CODES {
+            writer.write(clear, size);
+            table.clear();
+            next_code = end + 1;
+            size = minimum_code_size + 1;
+        } else {
+            if next_code >= 1 << size {
+                size += 1;
+            }
+            table.insert((prefix, pixel), next_code);
+            next_code += 1;
+        }
+        prefix = pixel as u32;
+    }
+
+    writer.write(prefix, size);
+    if next_code >= 1 << size && size < 12 {
+        size += 1;
+    }
+    writer.write(end, size);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    // A plain LZW decoder to read back what the encoder wrote.
+    fn unlzw(bytes: &[u8], minimum_code_size: u32) -> Vec<u8> {
+        let clear = 1 << minimum_code_size;
+        let end = clear + 1;
+        let mut bit = 0;
+        let mut read = |size: u32| -> u32 {
+            (0..size).fold(0, |value, index| {
+                let set = (bytes[bit / 8] >> (bit % 8)) & 1;
+                bit += 1;
+                value | (set as u32) << index
+            })
+        };
+
+        let mut output = vec![];
+        let mut table : Vec<Vec<u8>> = vec![];
+        let mut size = minimum_code_size + 1;
+        let mut previous : Option<Vec<u8>> = None;
+        loop {
+            let code = read(size);
+            if code == clear {
+                table = (0..clear).map(|index| vec![index as u8]).chain([vec![], vec![]]).collect();
+                size = minimum_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+
+            let entry = match table.get(code as usize) {
+                Some(entry) => entry.clone(),
+                None => {
+                    let mut entry = previous.clone().unwrap();
+                    entry.push(entry[0]);
+                    entry
+                },
+            };
+            output.extend(&entry);
+            if let Some(mut previous) = previous.take() {
+                previous.push(entry[0]);
+                table.push(previous);
+            }
+            if table.len() as u32 >= 1 << size && size < 12 {
+                size += 1;
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let pixels : Vec<u8> = (0..20000u32).map(|index| ((index * index / 7) % 4) as u8).collect();
+
+        assert_eq!(unlzw(&lzw(&pixels, 2), 2), pixels);
+        assert_eq!(unlzw(&lzw(&[3], 2), 2), vec![3]);
+        assert_eq!(unlzw(&lzw(&[], 2), 2), vec![]);
+    }
+
+    #[test]
+    fn encode() {
+        let palette = Palette::new().with('#', Color::new(255, 0, 0)).with('o', Color::new(0, 0, 255));
+        let frames = [Frame::parse("#."), Frame::parse("o#")];
+        let gif = gif(&frames, &palette, 1, Duration::from_millis(250)).unwrap();
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif[6..11], [2, 0, 1, 0, 0xf1]);
+        assert_eq!(gif[13..25], [0, 0, 0, 255, 0, 0, 0, 0, 255, 0, 0, 0]);
+        assert_eq!(&gif[28..39], b"NETSCAPE2.0");
+        assert_eq!(gif[44..50], [0x21, 0xf9, 4, 0, 25, 0]);
+        assert_eq!(gif.iter().filter(|&&byte| byte == 0x2c).count(), 2);
+
+        // the first image's data follows its descriptor
+        let start = 52 + 10;
+        assert_eq!(gif[start], 2);
+        let length = gif[start + 1] as usize;
+        assert_eq!(unlzw(&gif[start + 2..start + 2 + length], 2), vec![1, 0]);
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+
+    #[test]
+    fn too_large() {
+        let frames = [Frame::parse(&"#".repeat(1000))];
+        assert!(gif(&frames, &Palette::new(), 65, Duration::ZERO).is_ok());
+
+        let error = gif(&frames, &Palette::new(), 66, Duration::ZERO).err().unwrap();
+        assert_eq!(error, ImageError::TooLarge { width: 66000, height: 66, format: "GIF" });
+        assert_eq!(error.to_string(), "a 66000x66 pixel image is too large for a GIF");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::{error, fmt};
+use crate::{Color, Frame, Palette};
+
+// Both PNG and GIF store colours as indices into a table of at most 256, and
+// each format caps how many pixels wide and high an image can be.
+#[derive(Debug, PartialEq)]
+pub enum ImageError {
+    TooManyColors(usize),
+    TooLarge { width: usize, height: usize, format: &'static str },
+    TooManyPixels
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageError::TooManyColors(colors) => write!(formatter, "{colors} colours won't fit in a 256 colour image"),
+            ImageError::TooLarge { width, height, format } => write!(formatter, "a {width}x{height} pixel image is too large for a {format}"),
+            ImageError::TooManyPixels => write!(formatter, "the image has more pixels than can be counted")
+        }
+    }
+}
+
+impl error::Error for ImageError {}
+
+// Frames drawn as pixels, with each symbol a square `cell_size` pixels wide.
+// Every frame shares the same size and colour table, and frames smaller than
+// the rest are placed by their origin over a background of blank cells.
+pub(crate) struct Canvas {
+    pub colors: Vec<Color>,
+    pub width: usize,
+    pub height: usize,
+    indices: HashMap<char, u8>,
+    min: (i32, i32),
+    cell_size: usize
+}
+
+impl Canvas {
+    pub fn new(frames: &[Frame], palette: &Palette, cell_size: usize) -> Result<Self, ImageError> {
+        let cell_size = cell_size.max(1);
+        let mut colors : Vec<Color> = vec![];
+        let mut indices = HashMap::new();
+
+        for symbol in std::iter::once(' ').chain(frames.iter().flat_map(|frame| frame.rows().flatten().copied())) {
+            if indices.contains_key(&symbol) {
+                continue;
+            }
+            let color = palette.color_or_default(symbol);
+            let index = match colors.iter().position(|&known| known == color) {
+                Some(index) => index,
+                None => {
+                    colors.push(color);
+                    colors.len() - 1
+                }
+            };
+            indices.insert(symbol, index);
+        }
+        if colors.len() > 256 {
+            return Err(ImageError::TooManyColors(colors.len()));
+        }
+        let indices = indices.into_iter().map(|(symbol, index)| (symbol, index as u8)).collect();
+
+        let min = (
+            frames.iter().map(|frame| frame.origin().0).min().unwrap_or(0),
+            frames.iter().map(|frame| frame.origin().1).min().unwrap_or(0)
+        );
+        let max = (
+            frames.iter().map(|frame| frame.origin().0 + frame.width() as i32).max().unwrap_or(0),
+            frames.iter().map(|frame| frame.origin().1 + frame.height() as i32).max().unwrap_or(0)
+        );
+        let width = ((max.0 - min.0) as usize).checked_mul(cell_size).ok_or(ImageError::TooManyPixels)?;
+        let height = ((max.1 - min.1) as usize).checked_mul(cell_size).ok_or(ImageError::TooManyPixels)?;
+        // `draw` allocates a byte for every pixel
+        width.checked_mul(height).ok_or(ImageError::TooManyPixels)?;
+
+        Ok(Self { colors, width, height, indices, min, cell_size })
+    }
+
+    // One colour index per pixel, row by row.
+    pub fn draw(&self, frame: &Frame) -> Vec<u8> {
+        let mut pixels = vec![self.indices[&' ']; self.width * self.height];
+        let left = (frame.origin().0 - self.min.0) as usize * self.cell_size;
+        let top = (frame.origin().1 - self.min.1) as usize * self.cell_size;
+
+        for (row, symbols) in frame.rows().enumerate() {
+            let line : Vec<u8> = symbols.iter()
+                .flat_map(|symbol| std::iter::repeat_n(self.indices[symbol], self.cell_size))
+                .collect();
+            for offset in 0..self.cell_size {
+                let start = (top + row * self.cell_size + offset) * self.width + left;
+                pixels[start..start + line.len()].copy_from_slice(&line);
+            }
+        }
+
+        pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_colors_between_symbols() {
+        let palette = Palette::new().with('#', Color::new(255, 0, 0)).with('o', Color::new(255, 0, 0));
+        let canvas = Canvas::new(&[Frame::parse("#o.\n @.")], &palette, 1).unwrap();
+
+        assert_eq!(canvas.colors, vec![Color::new(0, 0, 0), Color::new(255, 0, 0), Color::new(200, 200, 200)]);
+        assert_eq!(canvas.draw(&Frame::parse("#o.\n @.")), vec![1, 1, 0, 0, 2, 0]);
+    }
+
+    #[test]
+    fn scales_cells() {
+        let frame = Frame::parse("#.");
+        let canvas = Canvas::new(std::slice::from_ref(&frame), &Palette::new(), 2).unwrap();
+
+        assert_eq!((canvas.width, canvas.height), (4, 2));
+        assert_eq!(canvas.draw(&frame), vec![1, 1, 0, 0, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn places_frames_by_origin() {
+        let frames = [Frame::parse("#").with_origin(1, 1), Frame::parse("##\n##").with_origin(0, 0)];
+        let canvas = Canvas::new(&frames, &Palette::new(), 1).unwrap();
+
+        assert_eq!((canvas.width, canvas.height), (2, 2));
+        assert_eq!(canvas.draw(&frames[0]), vec![0, 0, 0, 1]);
+        assert_eq!(canvas.draw(&frames[1]), vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn too_many_colors() {
+        let symbols : String = (0..300u32).map(|index| char::from_u32(0x100 + index).unwrap()).collect();
+        let palette = symbols.chars().enumerate().fold(Palette::new(), |palette, (index, symbol)| {
+            palette.with(symbol, Color::new((index % 256) as u8, (index / 256) as u8, 1))
+        });
+
+        assert_eq!(Canvas::new(&[Frame::parse(&symbols)], &palette, 1).err(), Some(ImageError::TooManyColors(301)));
+    }
+
+    #[test]
+    fn too_many_pixels() {
+        let frame = Frame::parse("##\n##");
+        assert_eq!(Canvas::new(std::slice::from_ref(&frame), &Palette::new(), usize::MAX / 2 + 1).err(), Some(ImageError::TooManyPixels));
+        assert_eq!(Canvas::new(std::slice::from_ref(&frame), &Palette::new(), 1 << (usize::BITS / 2)).err(), Some(ImageError::TooManyPixels));
+    }
+}
@@ -0,0 +1,28 @@
+mod frame;
+mod gif;
+mod image;
+mod palette;
+mod png;
+mod recorder;
+
+pub use frame::Frame;
+pub use gif::gif;
+pub use image::ImageError;
+pub use palette::{Color, Palette};
+pub use png::png;
+pub use recorder::{Player, Recorder};
+
+pub trait Render {
+    fn render(&self) -> Frame;
+
+    // Colours used for each symbol when the frames are played back in a terminal.
+    fn palette(&self) -> Palette {
+        Palette::default()
+    }
+}
+
+// Simulations that can be advanced one step at a time. `step` returns false
+// once there is nothing left to do, without changing anything.
+pub trait Step {
+    fn step(&mut self) -> bool;
+}
@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::Frame;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8
+}
+
+impl Color {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    // Accepts the "#rrggbb" form that Display writes.
+    pub fn parse(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').filter(|digits| digits.len() == 6 && digits.is_ascii())?;
+        let channel = |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).ok();
+
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    // 24-bit foreground colour escape code.
+    fn ansi(&self) -> String {
+        format!("\x1b[38;2;{};{};{}m", self.red, self.green, self.blue)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+const RESET : &str = "\x1b[0m";
+
+// Maps the symbols in a frame to colours. Symbols without one are left in the
+// terminal's default colour.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Palette {
+    colors: BTreeMap<char, Color>
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, symbol: char, color: Color) -> Self {
+        self.colors.insert(symbol, color);
+        self
+    }
+
+    pub fn color(&self, symbol: char) -> Option<Color> {
+        self.colors.get(&symbol).copied()
+    }
+
+    // Images need a colour for every symbol. Blank looking symbols default to
+    // black and everything else to light grey.
+    pub fn color_or_default(&self, symbol: char) -> Color {
+        self.color(symbol).unwrap_or(match symbol {
+            ' ' | '.' => Color::new(0, 0, 0),
+            _ => Color::new(200, 200, 200)
+        })
+    }
+
+    // The other palette's colours win where both have one for a symbol.
+    pub fn merged(&self, overrides: &Palette) -> Palette {
+        overrides.iter().fold(self.clone(), |palette, (symbol, color)| palette.with(symbol, color))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (char, Color)> + '_ {
+        self.colors.iter().map(|(&symbol, &color)| (symbol, color))
+    }
+
+    // The frame as text with ANSI colour codes, only switching colour between
+    // runs of differently coloured symbols.
+    pub fn paint(&self, frame: &Frame) -> String {
+        let mut painted = String::new();
+        if let Some(caption) = frame.caption() {
+            painted.push_str(caption);
+            painted.push('\n');
+        }
+
+        for row in frame.rows() {
+            let mut current = None;
+            for &symbol in row {
+                let color = self.color(symbol);
+                if color != current {
+                    match color {
+                        Some(color) => painted.push_str(&color.ansi()),
+                        None => painted.push_str(RESET)
+                    }
+                    current = color;
+                }
+                painted.push(symbol);
+            }
+            if current.is_some() {
+                painted.push_str(RESET);
+            }
+            painted.push('\n');
+        }
+
+        painted
+    }
+
+    // One "<symbol> #rrggbb" line per colour, as written by Display.
+    pub fn parse(text: &str) -> Option<Self> {
+        text.lines().filter(|line| !line.is_empty()).try_fold(Self::new(), |palette, line| {
+            let mut chars = line.chars();
+            let symbol = chars.next()?;
+            let color = Color::parse(chars.as_str().strip_prefix(' ')?)?;
+
+            Some(palette.with(symbol, color))
+        })
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (symbol, color) in self.iter() {
+            writeln!(formatter, "{symbol} {color}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        let color = Color::new(255, 136, 0);
+
+        assert_eq!(color.to_string(), "#ff8800");
+        assert_eq!(Color::parse("#ff8800"), Some(color));
+        assert_eq!(Color::parse("ff8800"), None);
+        assert_eq!(Color::parse("#ff88"), None);
+        assert_eq!(Color::parse("#gg8800"), None);
+    }
+
+    #[test]
+    fn defaults_and_overrides() {
+        let palette = Palette::new().with('#', Color::new(255, 0, 0)).with('o', Color::new(0, 0, 255));
+        let overrides = Palette::new().with('o', Color::new(0, 255, 0));
+
+        assert_eq!(palette.color_or_default('#'), Color::new(255, 0, 0));
+        assert_eq!(palette.color_or_default('.'), Color::new(0, 0, 0));
+        assert_eq!(palette.color_or_default('@'), Color::new(200, 200, 200));
+        assert_eq!(palette.merged(&overrides).color('o'), Some(Color::new(0, 255, 0)));
+        assert_eq!(palette.merged(&overrides).color('#'), Some(Color::new(255, 0, 0)));
+    }
+
+    #[test]
+    fn paint() {
+        let palette = Palette::new().with('#', Color::new(255, 0, 0)).with('o', Color::new(0, 0, 255));
+        let frame = Frame::parse("##.o\n....").with_caption("sand");
+
+        assert_eq!(
+            palette.paint(&frame),
+            "sand\n\x1b[38;2;255;0;0m##\x1b[0m.\x1b[38;2;0;0;255mo\x1b[0m\n....\n"
+        );
+        assert_eq!(Palette::new().paint(&frame), frame.to_string());
+    }
+
+    #[test]
+    fn round_trip() {
+        let palette = Palette::new().with('#', Color::new(255, 0, 0)).with(' ', Color::new(1, 2, 3));
+
+        assert_eq!(palette.to_string(), "  #010203\n# #ff0000\n");
+        assert_eq!(Palette::parse(&palette.to_string()), Some(palette));
+        assert_eq!(Palette::parse("# red"), None);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::image::{Canvas, ImageError};
+use crate::{Frame, Palette};
+
+const SIGNATURE : [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// The frame as an 8-bit indexed colour PNG.
+pub fn png(frame: &Frame, palette: &Palette, cell_size: usize) -> Result<Vec<u8>, ImageError> {
+    let canvas = Canvas::new(std::slice::from_ref(frame), palette, cell_size)?;
+    // the header holds each size in four bytes, but only up to 2^31 - 1
+    let (Ok(width), Ok(height)) = (i32::try_from(canvas.width), i32::try_from(canvas.height)) else {
+        return Err(ImageError::TooLarge { width: canvas.width, height: canvas.height, format: "PNG" });
+    };
+    let pixels = canvas.draw(frame);
+
+    let mut header = vec![];
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // bit depth 8, colour type 3 (indexed), default compression, filtering and no interlacing
+    header.extend([8, 3, 0, 0, 0]);
+
+    let colors : Vec<u8> = canvas.colors.iter().flat_map(|color| [color.red, color.green, color.blue]).collect();
+
+    // every scanline starts with its filter type, and they are all unfiltered
+    let mut scanlines = Vec::with_capacity(pixels.len() + canvas.height);
+    for row in pixels.chunks(canvas.width.max(1)).take(canvas.height) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"PLTE", &colors);
+    chunk(&mut png, b"IDAT", &zlib(&scanlines, canvas.width + 1));
+    chunk(&mut png, b"IEND", &[]);
+
+    Ok(png)
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    !bytes.into_iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 }
+        })
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+
+    (b << 16) | a
+}
+
+fn zlib(bytes: &[u8], stride: usize) -> Vec<u8> {
+    // deflate with a 32K window and the fastest compression level
+    let mut zlib = vec![0x78, 0x01];
+    zlib.extend(deflate(bytes, stride));
+    zlib.extend(adler32(bytes).to_be_bytes());
+    zlib
+}
+
+// Writes bits least significant first, as deflate expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    count: u32
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        for bit in 0..bits {
+            self.current |= ((value >> bit) & 1) << self.count;
+            self.count += 1;
+            if self.count == 8 {
+                self.bytes.push(self.current as u8);
+                self.current = 0;
+                self.count = 0;
+            }
+        }
+    }
+
+    // Huffman codes go most significant bit first.
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.current as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASES : [u32; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258
+];
+const LENGTH_EXTRA : [u32; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASES : [u32; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577
+];
+const DISTANCE_EXTRA : [u32; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+const MAX_DISTANCE : usize = 32768;
+const MAX_LENGTH : usize = 258;
+
+// The fixed Huffman code for literals and lengths.
+fn write_symbol(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => { writer.write_code(0x30 + symbol, 8) },
+        144..=255 => { writer.write_code(0x190 + symbol - 144, 9) },
+        256..=279 => { writer.write_code(symbol - 256, 7) },
+        _ => { writer.write_code(0xc0 + symbol - 280, 8) },
+    }
+}
+
+// A single fixed Huffman block. Cells are drawn as runs of the same colour and
+// scaled up rows repeat the one above, so the only matches looked for are
+// against the previous byte and the previous scanline.
+fn deflate(bytes: &[u8], stride: usize) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let match_length = |position: usize, distance: usize| {
+        (0..MAX_LENGTH.min(bytes.len() - position))
+            .take_while(|&offset| bytes[position + offset] == bytes[position + offset - distance])
+            .count()
+    };
+
+    let mut position = 0;
+    while position < bytes.len() {
+        let best = [1, stride].into_iter()
+            .filter(|&distance| distance > 0 && distance <= position && distance <= MAX_DISTANCE)
+            .map(|distance| (match_length(position, distance), distance))
+            .max();
+
+        match best {
+            Some((length, distance)) if length >= 3 => {
+                let code = LENGTH_BASES.iter().rposition(|&base| base <= length as u32).unwrap();
+                write_symbol(&mut writer, 257 + code as u32);
+                writer.write(length as u32 - LENGTH_BASES[code], LENGTH_EXTRA[code]);
+
+                let code = DISTANCE_BASES.iter().rposition(|&base| base <= distance as u32).unwrap();
+                writer.write_code(code as u32, 5);
+                writer.write(distance as u32 - DISTANCE_BASES[code], DISTANCE_EXTRA[code]);
+
+                position += length;
+            },
+            _ => {
+                write_symbol(&mut writer, bytes[position] as u32);
+                position += 1;
+            },
+        }
+    }
+
+    write_symbol(&mut writer, 256);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    // Just enough of an inflater to read back fixed Huffman blocks.
+    fn inflate(bytes: &[u8]) -> Vec<u8> {
+        let mut bit = 0;
+        let mut read = |bits: u32| -> u32 {
+            (0..bits).fold(0, |value, index| {
+                let set = (bytes[bit / 8] >> (bit % 8)) & 1;
+                bit += 1;
+                value | (set as u32) << index
+            })
+        };
+        assert_eq!((read(1), read(2)), (1, 1));
+
+        let mut output : Vec<u8> = vec![];
+        loop {
+            let mut code = 0;
+            let mut length = 0;
+            let symbol = loop {
+                code = (code << 1) | read(1);
+                length += 1;
+                match (length, code) {
+                    (7, 0..=0x17) => break code + 256,
+                    (8, 0x30..=0xbf) => break code - 0x30,
+                    (8, 0xc0..=0xc7) => break code - 0xc0 + 280,
+                    (9, 0x190..=0x1ff) => break code - 0x190 + 144,
+                    _ => {},
+                }
+            };
+            match symbol {
+                0..=255 => { output.push(symbol as u8) },
+                256 => { return output },
+                _ => {
+                    let code = (symbol - 257) as usize;
+                    let length = LENGTH_BASES[code] + read(LENGTH_EXTRA[code]);
+                    let code = (0..5).fold(0, |value, _| (value << 1) | read(1)) as usize;
+                    let distance = DISTANCE_BASES[code] + read(DISTANCE_EXTRA[code]);
+                    for _ in 0..length {
+                        output.push(output[output.len() - distance as usize]);
+                    }
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn deflate_round_trip() {
+        let bytes : Vec<u8> = (0..2000u32).map(|index| ((index / 7) % 5 + index % 3) as u8).chain([200; 600]).collect();
+        let deflated = deflate(&bytes, 40);
+
+        assert_eq!(inflate(&deflated), bytes);
+        assert!(deflated.len() < bytes.len());
+    }
+
+    #[test]
+    fn encode() {
+        let palette = Palette::new().with('#', Color::new(255, 0, 0));
+        let png = png(&Frame::parse("#.\n.#"), &palette, 2).unwrap();
+
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..29], [0, 0, 0, 4, 0, 0, 0, 4, 8, 3, 0, 0, 0]);
+        assert_eq!(&png[37..41], b"PLTE");
+        assert_eq!(png[41..47], [0, 0, 0, 255, 0, 0]);
+
+        let length = u32::from_be_bytes(png[51..55].try_into().unwrap()) as usize;
+        assert_eq!(&png[55..59], b"IDAT");
+        let data = &png[59..59 + length];
+        assert_eq!(data[..2], [0x78, 0x01]);
+        assert_eq!(
+            inflate(&data[2..length - 4]),
+            vec![0, 1, 1, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 1, 1]
+        );
+        assert_eq!(png[png.len() - 12..], [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]);
+    }
+
+    #[test]
+    fn too_large() {
+        let error = png(&Frame::parse("#"), &Palette::new(), 1 << 31).err().unwrap();
+        assert_eq!(error, ImageError::TooLarge { width: 1 << 31, height: 1 << 31, format: "PNG" });
+    }
+}
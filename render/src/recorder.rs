@@ -0,0 +1,350 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+use crate::{Frame, Palette, Render, Step};
+
+const CLEAR_SCREEN : &str = "\x1b[2J\x1b[H";
+const PALETTE_FILE : &str = "palette.txt";
+
+// Collects frames from a simulation as it runs. Long runs can keep only every
+// nth frame, or stop recording after a number of frames.
+pub struct Recorder {
+    frames: Vec<Frame>,
+    palette: Palette,
+    every: usize,
+    limit: Option<usize>,
+    offered: usize,
+    skipped_last: bool
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self { frames: vec![], palette: Palette::default(), every: 1, limit: None, offered: 0, skipped_last: false }
+    }
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn every(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.limit.is_some_and(|limit| self.frames.len() >= limit)
+    }
+
+    // The palette is taken from the first subject recorded.
+    pub fn record<R: Render>(&mut self, subject: &R) {
+        if self.is_full() {
+            return;
+        }
+        if self.frames.is_empty() {
+            self.palette = subject.palette();
+        }
+
+        self.skipped_last = !self.offered.is_multiple_of(self.every);
+        if !self.skipped_last {
+            self.frames.push(subject.render());
+        }
+        self.offered += 1;
+    }
+
+    // Records the subject if the last frame offered was skipped, so the final
+    // state always ends up in the recording.
+    pub fn finish<R: Render>(&mut self, subject: &R) {
+        if self.skipped_last && !self.is_full() {
+            self.frames.push(subject.render());
+            self.skipped_last = false;
+        }
+    }
+
+    pub fn step<S: Step + Render>(&mut self, simulation: &mut S) -> bool {
+        let advanced = simulation.step();
+        if advanced {
+            self.record(simulation);
+        }
+
+        advanced
+    }
+
+    // Records the starting state and every step after it until the simulation
+    // finishes or the recorder fills up. Returns the number of steps taken.
+    pub fn run<S: Step + Render>(&mut self, simulation: &mut S) -> usize {
+        self.run_for(simulation, usize::MAX)
+    }
+
+    pub fn run_for<S: Step + Render>(&mut self, simulation: &mut S, steps: usize) -> usize {
+        self.record(simulation);
+
+        let mut taken = 0;
+        while taken < steps && !self.is_full() && self.step(simulation) {
+            taken += 1;
+        }
+        self.finish(simulation);
+
+        taken
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn player(&self) -> Player<'_> {
+        Player { frames: &self.frames, position: 0 }
+    }
+
+    // Clears the terminal and draws the frame in the recording's colours.
+    pub fn show<W: Write>(&self, out: &mut W, frame: &Frame) -> io::Result<()> {
+        write!(out, "{CLEAR_SCREEN}{}", self.palette.paint(frame))?;
+        out.flush()
+    }
+
+    // Draws each frame over the last one, waiting `delay` in between.
+    pub fn play<W: Write>(&self, out: &mut W, delay: Duration) -> io::Result<()> {
+        for (index, frame) in self.frames.iter().enumerate() {
+            if index > 0 {
+                sleep(delay);
+            }
+            self.show(out, frame)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn frame_path(directory: &Path, index: usize) -> PathBuf {
+        directory.join(format!("frame_{index:05}.txt"))
+    }
+
+    // Writes one text file per frame, plus the palette if there is one.
+    pub fn dump(&self, directory: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(directory)?;
+
+        let mut paths = vec![];
+        for (index, frame) in self.frames.iter().enumerate() {
+            let path = Self::frame_path(directory, index);
+            fs::write(&path, frame.dump())?;
+            paths.push(path);
+        }
+        if !self.palette.is_empty() {
+            fs::write(directory.join(PALETTE_FILE), self.palette.to_string())?;
+        }
+
+        Ok(paths)
+    }
+
+    pub fn load(directory: &Path) -> io::Result<Self> {
+        let mut paths : Vec<PathBuf> = fs::read_dir(directory)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<_>>()?;
+        paths.retain(|path| {
+            path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("frame_") && name.ends_with(".txt"))
+        });
+        paths.sort();
+
+        let frames = paths.iter().map(|path| fs::read_to_string(path).map(|dump| Frame::parse_dump(&dump))).collect::<io::Result<_>>()?;
+        let palette = match fs::read_to_string(directory.join(PALETTE_FILE)) {
+            Ok(text) => Palette::parse(&text).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("invalid palette in {}", directory.display())))?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Palette::default(),
+            Err(error) => return Err(error)
+        };
+
+        Ok(Self { frames, palette, ..Self::default() })
+    }
+}
+
+// Steps backwards and forwards through recorded frames.
+pub struct Player<'a> {
+    frames: &'a [Frame],
+    position: usize
+}
+
+impl<'a> Player<'a> {
+    pub fn current(&self) -> Option<&'a Frame> {
+        self.frames.get(self.position)
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn forward(&mut self) -> bool {
+        let moved = self.position + 1 < self.frames.len();
+        if moved {
+            self.position += 1;
+        }
+
+        moved
+    }
+
+    pub fn back(&mut self) -> bool {
+        let moved = self.position > 0;
+        if moved {
+            self.position -= 1;
+        }
+
+        moved
+    }
+
+    // Positions past the end go to the last frame.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(self.frames.len().saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use crate::Color;
+    use super::*;
+
+    // Counts up to a target, one step at a time.
+    struct Counter {
+        value: usize,
+        target: usize
+    }
+
+    impl Render for Counter {
+        fn render(&self) -> Frame {
+            Frame::from_fn(self.target, 1, |x, _| if x < self.value { '#' } else { '.' })
+        }
+
+        fn palette(&self) -> Palette {
+            Palette::new().with('#', Color::new(0, 255, 0))
+        }
+    }
+
+    impl Step for Counter {
+        fn step(&mut self) -> bool {
+            let advanced = self.value < self.target;
+            if advanced {
+                self.value += 1;
+            }
+
+            advanced
+        }
+    }
+
+    fn texts(recorder: &Recorder) -> Vec<String> {
+        recorder.frames().iter().map(Frame::to_string).collect()
+    }
+
+    #[test]
+    fn run() {
+        let mut recorder = Recorder::new();
+
+        assert_eq!(recorder.run(&mut Counter { value: 0, target: 3 }), 3);
+        assert_eq!(texts(&recorder), vec!["...\n", "#..\n", "##.\n", "###\n"]);
+        assert_eq!(recorder.palette().color('#'), Some(Color::new(0, 255, 0)));
+    }
+
+    #[test]
+    fn every_keeps_the_final_frame() {
+        let mut recorder = Recorder::new().every(2);
+        recorder.run(&mut Counter { value: 0, target: 3 });
+
+        assert_eq!(texts(&recorder), vec!["...\n", "##.\n", "###\n"]);
+    }
+
+    #[test]
+    fn limit_stops_the_run() {
+        let mut recorder = Recorder::new().limit(2);
+        let mut counter = Counter { value: 0, target: 5 };
+
+        assert_eq!(recorder.run(&mut counter), 1);
+        assert_eq!(counter.value, 1);
+        assert_eq!(recorder.len(), 2);
+    }
+
+    #[test]
+    fn run_for() {
+        let mut recorder = Recorder::new();
+        let mut counter = Counter { value: 0, target: 5 };
+
+        assert_eq!(recorder.run_for(&mut counter, 2), 2);
+        assert_eq!(texts(&recorder), vec![".....\n", "#....\n", "##...\n"]);
+        assert!(recorder.step(&mut counter));
+        assert_eq!(recorder.len(), 4);
+    }
+
+    #[test]
+    fn player() {
+        let mut recorder = Recorder::new();
+        recorder.run(&mut Counter { value: 0, target: 2 });
+        let mut player = recorder.player();
+
+        assert_eq!(player.current().unwrap().to_string(), "..\n");
+        assert!(!player.back());
+        assert!(player.forward());
+        assert!(player.forward());
+        assert!(!player.forward());
+        assert_eq!(player.current().unwrap().to_string(), "##\n");
+        assert!(player.back());
+        assert_eq!(player.position(), 1);
+        player.seek(10);
+        assert_eq!(player.position(), 2);
+    }
+
+    #[test]
+    fn play() {
+        let mut recorder = Recorder::new();
+        recorder.run(&mut Counter { value: 0, target: 1 });
+        let mut out = vec![];
+        recorder.play(&mut out, Duration::ZERO).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[2J\x1b[H.\n\x1b[2J\x1b[H\x1b[38;2;0;255;0m#\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn dump_and_load() {
+        let directory = env::temp_dir().join(format!("render_dump_and_load_{}", std::process::id()));
+        let mut recorder = Recorder::new();
+        recorder.run(&mut Counter { value: 0, target: 2 });
+
+        let paths = recorder.dump(&directory).unwrap();
+        assert_eq!(paths, vec![
+            directory.join("frame_00000.txt"),
+            directory.join("frame_00001.txt"),
+            directory.join("frame_00002.txt")
+        ]);
+
+        let loaded = Recorder::load(&directory).unwrap();
+        assert_eq!(loaded.frames(), recorder.frames());
+        assert_eq!(loaded.palette(), recorder.palette());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,321 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! cost {
+    ($($type:ty),*) => {
+        $(
+            impl Cost for $type {
+                const ZERO: Self = 0;
+            }
+        )*
+    };
+}
+
+cost!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C
+}
+
+impl<N, C> Path<N, C> {
+    pub fn start(&self) -> &N {
+        self.nodes.first().unwrap()
+    }
+
+    pub fn goal(&self) -> &N {
+        self.nodes.last().unwrap()
+    }
+}
+
+// Every node discovered by a search, along with its best known cost and the
+// node it was reached from. Nodes are stored once and referred to by index.
+pub struct Reachable<N, C> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> Reachable<N, C> {
+    fn new() -> Self {
+        Self { nodes: vec![], indices: HashMap::new(), costs: vec![], parents: vec![] }
+    }
+
+    fn discover(&mut self, node: N, cost: C, parent: Option<usize>) -> usize {
+        let index = self.nodes.len();
+        self.indices.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.costs.push(cost);
+        self.parents.push(parent);
+
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn cost_to(&self, node: &N) -> Option<C> {
+        self.indices.get(node).map(|&index| self.costs[index])
+    }
+
+    pub fn parent_of(&self, node: &N) -> Option<&N> {
+        self.indices.get(node)
+            .and_then(|&index| self.parents[index])
+            .map(|parent| &self.nodes[parent])
+    }
+
+    pub fn path_to(&self, node: &N) -> Option<Path<N, C>> {
+        self.indices.get(node).map(|&index| self.path_to_index(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.nodes.iter().zip(self.costs.iter().copied())
+    }
+
+    fn path_to_index(&self, index: usize) -> Path<N, C> {
+        let mut nodes = vec![self.nodes[index].clone()];
+        let mut current = index;
+        while let Some(parent) = self.parents[current] {
+            nodes.push(self.nodes[parent].clone());
+            current = parent;
+        }
+        nodes.reverse();
+
+        Path { nodes, cost: self.costs[index] }
+    }
+}
+
+fn breadth_first<N, F, I, G>(start: N, mut neighbors: F, mut is_goal: G) -> (Reachable<N, usize>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool
+{
+    let mut reachable = Reachable::new();
+    let mut to_visit = VecDeque::from([reachable.discover(start, 0, None)]);
+
+    while let Some(index) = to_visit.pop_front() {
+        let node = reachable.nodes[index].clone();
+        if is_goal(&node) {
+            return (reachable, Some(index));
+        }
+
+        let cost = reachable.costs[index] + 1;
+        for neighbor in neighbors(&node) {
+            if !reachable.contains(&neighbor) {
+                to_visit.push_back(reachable.discover(neighbor, cost, Some(index)));
+            }
+        }
+    }
+
+    (reachable, None)
+}
+
+// Dijkstra is A* with a heuristic of zero. The heuristic must never
+// overestimate the remaining cost, or the path found may not be the shortest.
+fn best_first<N, C, F, I, H, G>(start: N, mut neighbors: F, mut heuristic: H, mut is_goal: G) -> (Reachable<N, C>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool
+{
+    let mut reachable = Reachable::new();
+    let mut to_visit = BinaryHeap::new();
+    let mut visited : Vec<bool> = vec![];
+
+    to_visit.push(Reverse((heuristic(&start), reachable.discover(start, C::ZERO, None))));
+    visited.push(false);
+
+    while let Some(Reverse((_priority, index))) = to_visit.pop() {
+        if visited[index] {
+            continue;
+        }
+        visited[index] = true;
+
+        let node = reachable.nodes[index].clone();
+        if is_goal(&node) {
+            return (reachable, Some(index));
+        }
+
+        let cost = reachable.costs[index];
+        for (neighbor, step_cost) in neighbors(&node) {
+            let neighbor_cost = cost + step_cost;
+            let neighbor_index = match reachable.indices.get(&neighbor) {
+                Some(&neighbor_index) => {
+                    if visited[neighbor_index] || neighbor_cost >= reachable.costs[neighbor_index] {
+                        continue;
+                    }
+                    reachable.costs[neighbor_index] = neighbor_cost;
+                    reachable.parents[neighbor_index] = Some(index);
+                    neighbor_index
+                },
+                None => {
+                    visited.push(false);
+                    reachable.discover(neighbor, neighbor_cost, Some(index))
+                }
+            };
+
+            let priority = neighbor_cost + heuristic(&reachable.nodes[neighbor_index]);
+            to_visit.push(Reverse((priority, neighbor_index)));
+        }
+    }
+
+    (reachable, None)
+}
+
+pub fn bfs<N, F, I, G>(start: N, neighbors: F, is_goal: G) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool
+{
+    let (reachable, goal) = breadth_first(start, neighbors, is_goal);
+    goal.map(|index| reachable.path_to_index(index))
+}
+
+pub fn bfs_all<N, F, I>(start: N, neighbors: F) -> Reachable<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>
+{
+    breadth_first(start, neighbors, |_| false).0
+}
+
+pub fn dijkstra<N, C, F, I, G>(start: N, neighbors: F, is_goal: G) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    G: FnMut(&N) -> bool
+{
+    astar(start, neighbors, |_| C::ZERO, is_goal)
+}
+
+pub fn dijkstra_all<N, C, F, I>(start: N, neighbors: F) -> Reachable<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>
+{
+    best_first(start, neighbors, |_| C::ZERO, |_| false).0
+}
+
+pub fn astar<N, C, F, I, H, G>(start: N, neighbors: F, heuristic: H, is_goal: G) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool
+{
+    let (reachable, goal) = best_first(start, neighbors, heuristic, is_goal);
+    goal.map(|index| reachable.path_to_index(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 (7), 0 -> 2 (9), 0 -> 5 (14), 1 -> 2 (10), 1 -> 3 (15),
+    // 2 -> 3 (11), 2 -> 5 (2), 3 -> 4 (6), 4 -> 5 (9), all undirected
+    fn weighted_neighbors(node: &usize) -> Vec<(usize, u32)> {
+        let edges = [(0, 1, 7), (0, 2, 9), (0, 5, 14), (1, 2, 10), (1, 3, 15), (2, 3, 11), (2, 5, 2), (3, 4, 6), (4, 5, 9)];
+
+        edges.iter().filter_map(|&(from, to, cost)| {
+            if from == *node {
+                Some((to, cost))
+            } else if to == *node {
+                Some((from, cost))
+            } else {
+                None
+            }
+        }).collect()
+    }
+
+    fn grid_neighbors(wall: i32) -> impl FnMut(&(i32, i32)) -> Vec<(i32, i32)> {
+        move |&(x, y)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)].into_iter()
+                .filter(|&(x, y)| (0..10).contains(&x) && (0..10).contains(&y))
+                .filter(|&(x, y)| !(x == wall && y < 9))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let path = bfs((0, 0), grid_neighbors(5), |&node| node == (9, 0)).unwrap();
+        assert_eq!(path.cost, 9 + 9 + 9);
+        assert_eq!(path.nodes.len(), path.cost + 1);
+        assert_eq!(*path.start(), (0, 0));
+        assert_eq!(*path.goal(), (9, 0));
+        assert!(path.nodes.contains(&(5, 9)));
+    }
+
+    #[test]
+    fn bfs_without_path() {
+        assert_eq!(bfs((0, 0), grid_neighbors(5), |&node| node == (10, 10)), None);
+    }
+
+    #[test]
+    fn bfs_all_reaches_everything() {
+        let reachable = bfs_all((0, 0), grid_neighbors(5));
+        assert_eq!(reachable.len(), 100 - 9);
+        assert_eq!(reachable.cost_to(&(4, 0)), Some(4));
+        assert_eq!(reachable.cost_to(&(5, 0)), None);
+        assert_eq!(reachable.parent_of(&(0, 0)), None);
+        assert_eq!(reachable.path_to(&(0, 2)).unwrap().nodes, vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_detours() {
+        let path = dijkstra(0, weighted_neighbors, |&node| node == 4).unwrap();
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.nodes, vec![0, 2, 5, 4]);
+    }
+
+    #[test]
+    fn dijkstra_all_costs() {
+        let reachable = dijkstra_all(0, weighted_neighbors);
+        let mut costs : Vec<(usize, u32)> = reachable.iter().map(|(&node, cost)| (node, cost)).collect();
+        costs.sort();
+        assert_eq!(costs, vec![(0, 0), (1, 7), (2, 9), (3, 20), (4, 20), (5, 11)]);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let goal = (9, 0);
+        let neighbors = |node: &(i32, i32)| grid_neighbors(5)(node).into_iter().map(|neighbor| (neighbor, 1));
+        let manhattan = |&(x, y): &(i32, i32)| (goal.0 - x).abs() + (goal.1 - y).abs();
+
+        let with_heuristic = astar((0, 0), neighbors, manhattan, |&node| node == goal).unwrap();
+        let without_heuristic = dijkstra((0, 0), neighbors, |&node| node == goal).unwrap();
+        assert_eq!(with_heuristic.cost, 27);
+        assert_eq!(with_heuristic.cost, without_heuristic.cost);
+    }
+}